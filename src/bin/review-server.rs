@@ -0,0 +1,476 @@
+//! The self-hostable sync server of the review tool.
+//!
+//! It stores the reviews of every reviewer in a JSON file, accepting only the
+//! reviews of the `--manuscripts`. See [`review_tool::types::rank::sync`] for
+//! the API.
+//!
+//! Every request but the CORS preflight needs a token in the
+//! `Authorization: Bearer <token>` header: the shared `--token`, or the token
+//! of the reviewer in the `--tokens` file, a JSON object of reviewer names to
+//! tokens. A reviewer token only syncs the reviews of its reviewer, and the
+//! reviews of every reviewer are read with the shared token only.
+//! Only the `--origin` is allowed to call the server from a browser.
+//!
+//! ```bash
+//! cargo run --release --features server --bin review-server -- \
+//!     --addr 127.0.0.1:8080 --manuscripts manuscripts.csv --data sync.json \
+//!     --token <secret> --origin https://review.example.org
+//! ```
+
+use std::{collections::HashMap, io::Read, path::Path};
+
+use review_tool::types::{
+    deserialize::{deserialize, Format},
+    rank::{
+        registry,
+        sync::{self, decode_path_segment, SyncDatabase, SyncRequest},
+    },
+    ManuscriptDatabase,
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Listen on localhost only, unless `--addr` says otherwise.
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+/// The largest request body accepted, unless `--max-body` says otherwise.
+const DEFAULT_MAX_BODY: usize = 4 * 1024 * 1024;
+
+/// Who may call the server.
+struct Access {
+    /// The token shared by every reviewer.
+    token: Option<String>,
+
+    /// The token of every reviewer.
+    reviewer_tokens: HashMap<String, String>,
+
+    /// The origin allowed to call the server from a browser.
+    origin: Option<String>,
+
+    /// The largest request body accepted, in bytes.
+    max_body: usize,
+}
+
+impl Access {
+    /// The bearer token of the request, if any.
+    fn bearer(request: &Request) -> Option<&str> {
+        request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv("Authorization"))
+            .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+            .map(str::trim)
+    }
+
+    /// Does the request carry the shared token, or the token of `reviewer` if given?
+    fn authorize(&self, request: &Request, reviewer: Option<&str>) -> Result<(), Error> {
+        let token = Self::bearer(request).ok_or(Error::Unauthorized)?;
+
+        let shared = self.token.as_deref().is_some_and(|t| same(t, token));
+        let own = reviewer
+            .and_then(|reviewer| self.reviewer_tokens.get(reviewer))
+            .is_some_and(|t| same(t, token));
+
+        if shared || own {
+            Ok(())
+        } else {
+            Err(Error::Unauthorized)
+        }
+    }
+}
+
+/// Compare two tokens in a time independent of where they differ.
+fn same(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+fn main() {
+    tracing_subscriber::fmt::init();
+
+    let mut addr = DEFAULT_ADDR.to_owned();
+    let mut manuscripts_path = "manuscripts.csv".to_owned();
+    let mut data_path = "sync.json".to_owned();
+    let mut access = Access {
+        token: std::env::var("REVIEW_SERVER_TOKEN").ok(),
+        reviewer_tokens: HashMap::new(),
+        origin: None,
+        max_body: DEFAULT_MAX_BODY,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .unwrap_or_else(|| panic!("{arg} requires a value"));
+
+        match arg.as_str() {
+            "--addr" => addr = value,
+            "--manuscripts" => manuscripts_path = value,
+            "--data" => data_path = value,
+            "--token" => access.token = Some(value),
+            "--tokens" => {
+                let document = std::fs::read_to_string(&value)
+                    .unwrap_or_else(|e| panic!("failed to read {value}: {e}"));
+                access.reviewer_tokens =
+                    serde_json::from_str(&document).expect("failed to read the tokens");
+            }
+            "--origin" => access.origin = Some(value.trim_end_matches('/').to_owned()),
+            "--max-body" => access.max_body = value.parse().expect("invalid --max-body"),
+            _ => panic!("unknown argument: {arg}"),
+        }
+    }
+    assert!(
+        access.token.as_deref().is_some_and(|t| !t.is_empty())
+            || !access.reviewer_tokens.is_empty(),
+        "a token is required: pass --token, --tokens or set REVIEW_SERVER_TOKEN"
+    );
+
+    let manuscripts: ManuscriptDatabase = {
+        let file = std::fs::File::open(&manuscripts_path).expect("failed to open manuscripts");
+
+        deserialize(Format::SitconGdsc, file).expect("failed to deserialize manuscripts")
+    }
+    .into();
+
+    let mut database: SyncDatabase = match std::fs::read_to_string(&data_path) {
+        Ok(document) => serde_json::from_str(&document).expect("failed to read the data"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => SyncDatabase::default(),
+        Err(e) => panic!("failed to read {data_path}: {e}"),
+    };
+
+    let server = Server::http(&addr).unwrap_or_else(|e| panic!("failed to listen on {addr}: {e}"));
+    tracing::info!("serving {} manuscripts on http://{addr}", manuscripts.len());
+
+    for request in server.incoming_requests() {
+        respond(
+            request,
+            &access,
+            &manuscripts,
+            &mut database,
+            Path::new(&data_path),
+        );
+    }
+}
+
+/// Handle the request and respond to it, storing the changed database at `data_path`.
+fn respond(
+    mut request: Request,
+    access: &Access,
+    manuscripts: &ManuscriptDatabase,
+    database: &mut SyncDatabase,
+    data_path: &Path,
+) {
+    let (status, body) = match handle(&mut request, access, manuscripts, database) {
+        Ok(Handled { body, changed }) => {
+            if changed {
+                if let Err(e) = store(database, data_path) {
+                    tracing::error!("failed to store the data: {e}");
+                }
+            }
+
+            (200, body)
+        }
+        Err(e) => {
+            tracing::warn!("{} {}: {e}", request.method(), request.url());
+            (
+                e.status(),
+                serde_json::json!({ "error": e.to_string() }).to_string(),
+            )
+        }
+    };
+
+    let mut response = Response::from_string(body).with_status_code(status);
+    let mut headers = vec![("Content-Type", "application/json")];
+    // The web client may be served from another origin, if it is allowed.
+    if let Some(origin) = &access.origin {
+        headers.extend([
+            ("Access-Control-Allow-Origin", origin.as_str()),
+            ("Access-Control-Allow-Methods", "GET, POST, OPTIONS"),
+            (
+                "Access-Control-Allow-Headers",
+                "Content-Type, Authorization",
+            ),
+            ("Vary", "Origin"),
+        ]);
+    }
+    for (field, value) in headers {
+        response.add_header(Header::from_bytes(field, value).expect("valid header"));
+    }
+
+    if let Err(e) = request.respond(response) {
+        tracing::warn!("failed to respond: {e}");
+    }
+}
+
+/// The result of a handled request.
+struct Handled {
+    body: String,
+
+    /// Has the database been changed?
+    changed: bool,
+}
+
+fn handle(
+    request: &mut Request,
+    access: &Access,
+    manuscripts: &ManuscriptDatabase,
+    database: &mut SyncDatabase,
+) -> Result<Handled, Error> {
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_owned();
+    let segments = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| decode_path_segment(s).ok_or(Error::BadRequest("invalid path")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let unchanged = |body| Handled {
+        body,
+        changed: false,
+    };
+
+    match (request.method(), segments.as_slice()) {
+        // The CORS preflight.
+        (Method::Options, _) => Ok(unchanged(String::new())),
+
+        (Method::Get, ["api", "reviews", rubric]) => {
+            access.authorize(request, None)?;
+            registry::find(rubric).ok_or(Error::NotFound)?;
+
+            let body = match database.get(*rubric) {
+                Some(reviewers) => serde_json::to_string(reviewers)?,
+                None => "{}".to_owned(),
+            };
+            Ok(unchanged(body))
+        }
+
+        (Method::Post, ["api", "sync", rubric, reviewer]) => {
+            let entry = registry::find(rubric).ok_or(Error::NotFound)?;
+            if reviewer.trim().is_empty() {
+                return Err(Error::BadRequest("the reviewer name is empty"));
+            }
+            access.authorize(request, Some(reviewer.trim()))?;
+
+            if request.body_length().is_some_and(|n| n > access.max_body) {
+                return Err(Error::TooLarge);
+            }
+            let mut body = String::new();
+            request
+                .as_reader()
+                .take(access.max_body as u64 + 1)
+                .read_to_string(&mut body)?;
+            if body.len() > access.max_body {
+                return Err(Error::TooLarge);
+            }
+            let sync: SyncRequest = serde_json::from_str(&body)?;
+
+            // Store only the reviews the clients can read back.
+            for (id, change) in &sync.changes {
+                if !manuscripts.contains_key(id) {
+                    return Err(Error::BadRequest("unknown manuscript"));
+                }
+                entry.check_review(id, &change.rank, sync.rubric_version)?;
+            }
+
+            let response = database.reviewer_mut(rubric, reviewer.trim()).apply(sync);
+            if !response.conflicts.is_empty() {
+                tracing::info!(
+                    "{reviewer}: {} conflicts in `{rubric}`",
+                    response.conflicts.len()
+                );
+            }
+
+            Ok(Handled {
+                body: serde_json::to_string(&response)?,
+                changed: true,
+            })
+        }
+
+        _ => Err(Error::NotFound),
+    }
+}
+
+/// Store the database, replacing the file atomically.
+fn store(database: &SyncDatabase, path: &Path) -> Result<(), Error> {
+    let temporary = path.with_extension("json.tmp");
+
+    std::fs::write(&temporary, serde_json::to_vec(database)?)?;
+    std::fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+enum Error {
+    #[error("not found")]
+    NotFound,
+
+    #[error("bad request: {0}")]
+    BadRequest(&'static str),
+
+    #[error("a valid token is required")]
+    Unauthorized,
+
+    #[error("the request body is too large")]
+    TooLarge,
+
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid review: {0}")]
+    Review(#[from] sync::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl Error {
+    fn status(&self) -> u16 {
+        match self {
+            Error::NotFound => 404,
+            Error::BadRequest(_) | Error::Json(_) | Error::Review(_) => 400,
+            Error::Unauthorized => 401,
+            Error::TooLarge => 413,
+            Error::Io(_) => 500,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, thread};
+
+    use review_tool::types::{
+        rank::{
+            sitcon_gdsc,
+            sync::{Change, SyncDatabase, SyncRequest},
+            versioned::Versioned,
+            Rubric,
+        },
+        timestamp::Timestamp,
+        Manuscript, ManuscriptDatabase, ManuscriptId,
+    };
+    use tiny_http::Server;
+
+    use super::{respond, Access};
+
+    /// The status of the response, even if it is an error.
+    fn status(response: Result<ureq::Response, ureq::Error>) -> u16 {
+        match response {
+            Ok(response) => response.status(),
+            Err(ureq::Error::Status(status, _)) => status,
+            Err(e) => panic!("failed to request: {e}"),
+        }
+    }
+
+    #[test]
+    fn test_access() {
+        let manuscript: Manuscript = serde_json::from_value(serde_json::json!({
+            "title": "Talk", "type": "一般議程（40 分鐘）", "abstract": "", "audience": "",
+            "difficulty": "Easy", "description": "", "extra": "",
+            "author": { "name": "Alice", "description": "" },
+        }))
+        .unwrap();
+        let id = ManuscriptId::hash(&manuscript);
+
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let requests = 7;
+        let serving = thread::spawn(move || {
+            let manuscripts: ManuscriptDatabase = vec![manuscript].into();
+            let access = Access {
+                token: Some("shared".into()),
+                reviewer_tokens: HashMap::from([("alice".into(), "alice-token".into())]),
+                origin: None,
+                max_body: 1024,
+            };
+            let mut database = SyncDatabase::default();
+            let data = std::env::temp_dir()
+                .join(format!("review-server-test-{}.json", std::process::id()));
+
+            for request in server.incoming_requests().take(requests) {
+                respond(request, &access, &manuscripts, &mut database, &data);
+            }
+            std::fs::remove_file(data).ok();
+        });
+
+        let reviews = format!("{url}/api/reviews/{}", sitcon_gdsc::Group::ID);
+        let sync = |reviewer: &str| format!("{url}/api/sync/{}/{reviewer}", sitcon_gdsc::Group::ID);
+        let body = |id| {
+            serde_json::to_string(&SyncRequest {
+                rubric_version: sitcon_gdsc::Group::VERSION,
+                changes: vec![(
+                    id,
+                    Change {
+                        base_revision: 0,
+                        updated_at: Timestamp(1),
+                        rank: serde_json::to_value(sitcon_gdsc::Group::default()).unwrap(),
+                    },
+                )],
+            })
+            .unwrap()
+        };
+
+        // The reviews of every reviewer need the shared token.
+        assert_eq!(status(ureq::get(&reviews).call()), 401);
+        assert_eq!(
+            status(
+                ureq::get(&reviews)
+                    .set("Authorization", "Bearer alice-token")
+                    .call()
+            ),
+            401
+        );
+        assert_eq!(
+            status(
+                ureq::get(&reviews)
+                    .set("Authorization", "Bearer shared")
+                    .call()
+            ),
+            200
+        );
+
+        // A reviewer token syncs the reviews of its reviewer only.
+        assert_eq!(
+            status(
+                ureq::post(&sync("bob"))
+                    .set("Authorization", "Bearer alice-token")
+                    .send_string(&body(id))
+            ),
+            401
+        );
+        assert_eq!(
+            status(
+                ureq::post(&sync("alice"))
+                    .set("Authorization", "Bearer alice-token")
+                    .send_string(&" ".repeat(2048))
+            ),
+            413
+        );
+        assert_eq!(
+            status(
+                ureq::post(&sync("alice"))
+                    .set("Authorization", "Bearer alice-token")
+                    .send_string(&body(ManuscriptId([0; 32])))
+            ),
+            400
+        );
+        assert_eq!(
+            status(
+                ureq::post(&sync("alice"))
+                    .set("Authorization", "Bearer alice-token")
+                    .send_string(&body(id))
+            ),
+            200
+        );
+
+        serving.join().unwrap();
+    }
+}
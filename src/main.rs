@@ -1,34 +1,233 @@
 #[cfg(not(target_family = "wasm"))]
-fn main() {
-    use review_tool::types::{
-        deserialize::{deserialize, Format},
-        rank::sitcon_gdsc,
-    };
+use review_tool::types::{
+    assignment::Roster,
+    deserialize::{deserialize, Format},
+    rank::{aggregate::Aggregation, registry, sheet::ReviewerSheets, spreadsheet::Spreadsheet},
+    tag::TagDatabase,
+    workspace::{Workspace, DEFAULT_MANUSCRIPTS_PATH},
+    ManuscriptDatabase,
+};
 
-    tracing_subscriber::fmt::init();
+#[cfg(not(target_family = "wasm"))]
+const USAGE: &str = "\
+usage: review-tool [--rubric <id>]
+       review-tool aggregate <export>...
+       review-tool assign <roster> [--workspace <workspace>]
+       review-tool spreadsheet <export> <output.csv|output.xlsx>";
 
-    // Reading manuscripts
-    let manuscripts = {
-        let file = std::fs::File::open("manuscripts.csv").expect("failed to open manuscripts");
+#[cfg(not(target_family = "wasm"))]
+fn main() {
+    tracing_subscriber::fmt::init();
 
-        deserialize(Format::SitconGdsc, file).expect("failed to deserialize manuscripts")
+    if let Err(e) = run(std::env::args().skip(1)) {
+        eprintln!("error: {e}");
+        if matches!(e, Error::Usage(_) | Error::UnknownArgument(_)) {
+            eprintln!("{USAGE}");
+        }
+        std::process::exit(1);
     }
-    .into();
-
-    let options = eframe::NativeOptions {
-        app_id: Some("review-tool".to_owned()),
-        ..Default::default()
-    };
-    eframe::run_native(
-        "Review Tool",
-        options,
-        Box::new(|cc| {
-            Box::new(
-                review_tool::ui::ReviewToolApp::<sitcon_gdsc::Group>::new(cc, manuscripts).unwrap(),
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn run(args: impl Iterator<Item = String>) -> Result<(), Error> {
+    let mut args = args.peekable();
+    let command = args.peek().cloned().unwrap_or_default();
+
+    match command.as_str() {
+        // `aggregate <export>...` prints the aggregated reviews instead of starting the GUI.
+        "aggregate" => {
+            let paths = args.skip(1).collect::<Vec<_>>();
+            if paths.is_empty() {
+                return Err(Error::Usage("aggregate requires at least one export file"));
+            }
+
+            let manuscripts = read_manuscripts()?;
+            let sheets = paths
+                .iter()
+                .map(|path| read_export(path))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if let Some(mixed) = sheets.iter().find(|s| s.rubric != sheets[0].rubric) {
+                return Err(Error::MixedRubrics(
+                    sheets[0].rubric.clone(),
+                    mixed.rubric.clone(),
+                ));
+            }
+
+            print!(
+                "{}",
+                Aggregation::new(&manuscripts, &sheets).to_table(&manuscripts)
+            );
+        }
+
+        // `assign <roster> [--workspace <workspace>]` prints the assignment of the manuscripts
+        // to the reviewers. The expertise is matched against the tags of the workspace, if any.
+        "assign" => {
+            let path = args
+                .nth(1)
+                .ok_or(Error::Usage("assign requires a roster file"))?;
+            let workspace = match args.next().as_deref() {
+                Some("--workspace") => Some(
+                    args.next()
+                        .ok_or(Error::Usage("--workspace requires a workspace file"))?,
+                ),
+                Some(arg) => return Err(Error::UnknownArgument(arg.to_owned())),
+                None => None,
+            };
+            expect_end(args)?;
+
+            let roster: Roster = serde_yaml::from_str(&read(&path)?)
+                .map_err(|e| Error::Read(path.clone(), e.to_string()))?;
+            let (manuscripts, tags) = match workspace {
+                Some(path) => read_workspace(&path)?,
+                None => (read_manuscripts()?, TagDatabase::new()),
+            };
+
+            let assignment = roster.assign(&manuscripts, &tags);
+            for (id, assigned) in assignment.understaffed() {
+                tracing::warn!(
+                    "`{}` has only {assigned} reviewers without conflicts",
+                    manuscripts[id].title
+                );
+            }
+
+            let yaml = assignment
+                .to_yaml()
+                .map_err(|e| Error::Write("the assignment".to_owned(), e.to_string()))?;
+            print!("{yaml}");
+        }
+
+        // `spreadsheet <export> <output>` writes the reviews of an export as CSV or XLSX.
+        "spreadsheet" => {
+            let path = args
+                .nth(1)
+                .ok_or(Error::Usage("spreadsheet requires an export file"))?;
+            let output = args.next().ok_or(Error::Usage(
+                "spreadsheet requires an output file (.csv or .xlsx)",
+            ))?;
+            expect_end(args)?;
+
+            let manuscripts = read_manuscripts()?;
+            let sheets = read_export(&path)?;
+            let layout = registry::find(&sheets.rubric)
+                .ok_or_else(|| Error::Read(path.clone(), "unknown rubric".to_owned()))?
+                .blank_sheet();
+
+            let spreadsheet = Spreadsheet::new(&manuscripts, &layout, &sheets.reviews);
+            let content = if output.ends_with(".xlsx") {
+                spreadsheet.to_xlsx().map_err(|e| e.to_string())
+            } else {
+                spreadsheet
+                    .to_csv()
+                    .map(String::into_bytes)
+                    .map_err(|e| e.to_string())
+            }
+            .map_err(|e| Error::Write(output.clone(), e))?;
+            std::fs::write(&output, content).map_err(|e| Error::Write(output, e.to_string()))?;
+        }
+
+        "-h" | "--help" => println!("{USAGE}"),
+
+        // `--rubric <id>` selects the rubric; otherwise the last selected one is used.
+        "" | "--rubric" => {
+            let mut rubric = None;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--rubric" => {
+                        rubric = Some(args.next().ok_or(Error::Usage("--rubric requires an ID"))?)
+                    }
+                    _ => return Err(Error::UnknownArgument(arg)),
+                }
+            }
+
+            let manuscripts = read_manuscripts()?;
+            let options = eframe::NativeOptions {
+                app_id: Some("review-tool".to_owned()),
+                ..Default::default()
+            };
+            eframe::run_native(
+                "Review Tool",
+                options,
+                Box::new(move |cc| {
+                    match review_tool::ui::ReviewTool::new(cc, manuscripts, rubric.as_deref()) {
+                        Ok(app) => Box::new(app),
+                        Err(e) => {
+                            eprintln!("error: {e}");
+                            std::process::exit(1);
+                        }
+                    }
+                }),
             )
-        }),
-    )
-    .expect("failed to start UI");
+            .map_err(|e| Error::Ui(e.to_string()))?;
+        }
+
+        _ => return Err(Error::UnknownArgument(command)),
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn expect_end(mut args: impl Iterator<Item = String>) -> Result<(), Error> {
+    match args.next() {
+        Some(arg) => Err(Error::UnknownArgument(arg)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read(path: &str) -> Result<String, Error> {
+    std::fs::read_to_string(path).map_err(|e| Error::Read(path.to_owned(), e.to_string()))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read_manuscripts() -> Result<ManuscriptDatabase, Error> {
+    let file = std::fs::File::open(DEFAULT_MANUSCRIPTS_PATH)
+        .map_err(|e| Error::Read(DEFAULT_MANUSCRIPTS_PATH.to_owned(), e.to_string()))?;
+    let manuscripts = deserialize(Format::SitconGdsc, file)
+        .map_err(|e| Error::Read(DEFAULT_MANUSCRIPTS_PATH.to_owned(), e.to_string()))?;
+
+    Ok(manuscripts.into())
+}
+
+/// Read the manuscripts and the tags of a workspace.
+#[cfg(not(target_family = "wasm"))]
+fn read_workspace(path: &str) -> Result<(ManuscriptDatabase, TagDatabase), Error> {
+    let workspace = Workspace::from_yaml(&read(path)?)
+        .map_err(|e| Error::Read(path.to_owned(), e.to_string()))?;
+    let manuscripts = workspace
+        .manuscripts
+        .load(std::path::Path::new(path).parent())
+        .map_err(|e| Error::Read(path.to_owned(), e.to_string()))?;
+
+    Ok((manuscripts.into(), workspace.tags))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read_export(path: &str) -> Result<ReviewerSheets, Error> {
+    registry::read_export(&read(path)?).map_err(|e| Error::Read(path.to_owned(), e.to_string()))
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[derive(thiserror::Error, Debug)]
+enum Error {
+    #[error("{0}")]
+    Usage(&'static str),
+
+    #[error("unknown argument: {0}")]
+    UnknownArgument(String),
+
+    #[error("failed to read {0}: {1}")]
+    Read(String, String),
+
+    #[error("failed to write {0}: {1}")]
+    Write(String, String),
+
+    #[error("the exports are of different rubrics: `{0}` and `{1}`")]
+    MixedRubrics(String, String),
+
+    #[error("failed to start UI: {0}")]
+    Ui(String),
 }
 
 #[cfg(target_family = "wasm")]
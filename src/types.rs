@@ -5,11 +5,19 @@ use std::{collections::HashMap, ops::Deref, rc::Rc};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+pub mod assignment;
+pub mod blind;
 pub mod deserialize;
+pub mod letter;
+pub mod pairwise;
+pub mod program;
 pub mod rank;
+pub mod tag;
+pub mod timestamp;
+pub mod workspace;
 
 /// The difficulty of a manuscript.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Difficulty {
     Easy = 1,
     Medium = 2,
@@ -33,7 +41,7 @@ impl std::fmt::Display for Difficulty {
 }
 
 /// The author of a manuscript.
-#[derive(PartialEq, Eq, Debug, Hash)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Hash)]
 pub struct Author {
     /// The author of this manuscript.
     pub name: String,
@@ -43,15 +51,17 @@ pub struct Author {
 }
 
 /// The manuscript.
-#[derive(PartialEq, Eq, Debug, Hash)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Hash)]
 pub struct Manuscript {
     /// The title of this manuscript.
     pub title: String,
 
     /// The type of this manuscript.
+    #[serde(rename = "type")]
     pub type_: String,
 
     /// The abstract of this manuscript.
+    #[serde(rename = "abstract")]
     pub abstract_: String,
 
     /// The audience of this manuscript.
@@ -70,6 +80,26 @@ pub struct Manuscript {
     pub author: Author,
 }
 
+#[cfg(test)]
+impl Manuscript {
+    /// An easy manuscript for the tests, with the other fields empty.
+    pub(crate) fn fixture(title: &str, speaker: &str, type_: &str) -> Self {
+        Self {
+            title: title.to_owned(),
+            type_: type_.to_owned(),
+            abstract_: String::new(),
+            audience: String::new(),
+            difficulty: Difficulty::Easy,
+            description: String::new(),
+            extra: String::new(),
+            author: Author {
+                name: speaker.to_owned(),
+                description: String::new(),
+            },
+        }
+    }
+}
+
 /// The SHA-256 ID of the manuscript.
 #[serde_with::serde_as]
 #[derive(Serialize, Deserialize, Hash, PartialEq, Eq, Debug, Clone, Copy)]
@@ -125,11 +155,25 @@ impl ManuscriptDatabase {
     /// If no such item, return the first item.
     /// If nothing in the database, return `None`.
     pub fn previous(&self, id: &ManuscriptId) -> Option<&ManuscriptId> {
-        self.order
-            .split(|x| x == id)
-            .next()
-            .and_then(|x| x.last())
-            .or_else(|| self.first())
+        self.previous_by(id, |_| true)
+    }
+
+    /// Find the previous item to the given manuscript ID among the items `listed`.
+    ///
+    /// If no such item, return the first listed item.
+    /// If nothing is listed, return `None`.
+    pub fn previous_by(
+        &self,
+        id: &ManuscriptId,
+        mut listed: impl FnMut(&ManuscriptId) -> bool,
+    ) -> Option<&ManuscriptId> {
+        let before = self.order.iter().position(|x| x == id).unwrap_or(0);
+
+        self.order[..before]
+            .iter()
+            .rev()
+            .find(|x| listed(x))
+            .or_else(|| self.order.iter().find(|x| listed(x)))
     }
 
     /// Find the next item to the given manuscript ID.
@@ -137,11 +181,28 @@ impl ManuscriptDatabase {
     /// If no such item, return the last item.
     /// If nothing in the database, return `None`.
     pub fn next(&self, id: &ManuscriptId) -> Option<&ManuscriptId> {
-        self.order
-            .split(|x| x == id)
-            .nth(1)
-            .and_then(|x| x.first())
-            .or_else(|| self.last())
+        self.next_by(id, |_| true)
+    }
+
+    /// Find the next item to the given manuscript ID among the items `listed`.
+    ///
+    /// If no such item, return the last listed item.
+    /// If nothing is listed, return `None`.
+    pub fn next_by(
+        &self,
+        id: &ManuscriptId,
+        mut listed: impl FnMut(&ManuscriptId) -> bool,
+    ) -> Option<&ManuscriptId> {
+        let after = self
+            .order
+            .iter()
+            .position(|x| x == id)
+            .map_or(self.order.len(), |i| i + 1);
+
+        self.order[after..]
+            .iter()
+            .find(|x| listed(x))
+            .or_else(|| self.order.iter().rev().find(|x| listed(x)))
     }
 
     /// The iterator of the database.
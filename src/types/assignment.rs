@@ -0,0 +1,311 @@
+//! The assignment of the manuscripts to the reviewers.
+//!
+//! Every manuscript is assigned to `per_manuscript` reviewers. A reviewer is
+//! never assigned to a manuscript they have a conflict with, and the load is
+//! balanced among the reviewers: the manuscripts with the fewest eligible
+//! reviewers are assigned first, each to the least loaded reviewers, and the
+//! reviewers with the matching expertise are preferred on a tie.
+//!
+//! The assignment is shared as:
+//!
+//! ```yaml
+//! per_manuscript: 3
+//! manuscripts:
+//!   <manuscript id>: [<reviewer name>, ...]
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, Map};
+
+use super::{tag::TagDatabase, Manuscript, ManuscriptDatabase, ManuscriptId};
+
+/// The default number of the reviewers per manuscript.
+pub const DEFAULT_PER_MANUSCRIPT: usize = 3;
+
+/// A reviewer in the roster.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Reviewer {
+    pub name: String,
+
+    /// The topics the reviewer is familiar with, matched against
+    /// the type and the tags of the manuscripts, case-insensitively.
+    #[serde(default)]
+    pub expertise: Vec<String>,
+
+    /// The speakers the reviewer must not review, such as their colleagues.
+    ///
+    /// A reviewer always conflicts with the manuscripts of their own.
+    #[serde(default)]
+    pub conflicts: Vec<String>,
+}
+
+impl Reviewer {
+    /// Does the reviewer conflict with the speaker of this manuscript?
+    pub fn conflicts_with(&self, manuscript: &Manuscript) -> bool {
+        let speaker = manuscript.author.name.trim();
+
+        std::iter::once(&self.name)
+            .chain(&self.conflicts)
+            .any(|name| name.trim().eq_ignore_ascii_case(speaker))
+    }
+
+    /// Does the reviewer have the expertise of any of these topics?
+    pub fn is_expert_in<'a>(&self, mut topics: impl Iterator<Item = &'a str>) -> bool {
+        topics.any(|topic| {
+            self.expertise
+                .iter()
+                .any(|e| e.trim().eq_ignore_ascii_case(topic.trim()))
+        })
+    }
+}
+
+/// The reviewers to assign the manuscripts to.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Roster {
+    pub reviewers: Vec<Reviewer>,
+
+    /// The number of the reviewers of every manuscript.
+    pub per_manuscript: usize,
+}
+
+impl Default for Roster {
+    fn default() -> Self {
+        Self {
+            reviewers: Vec::new(),
+            per_manuscript: DEFAULT_PER_MANUSCRIPT,
+        }
+    }
+}
+
+impl Roster {
+    /// Assign the manuscripts to the reviewers.
+    ///
+    /// The expertise is matched against the type of the manuscripts and their `tags`.
+    /// A manuscript gets fewer reviewers than `per_manuscript` if there are
+    /// not enough reviewers without conflicts; see [`Assignment::understaffed`].
+    pub fn assign(&self, manuscripts: &ManuscriptDatabase, tags: &TagDatabase) -> Assignment {
+        let names = self
+            .reviewers
+            .iter()
+            .map(|r| r.name.trim())
+            .collect::<Vec<_>>();
+
+        // the eligible reviewers of every manuscript, and whether they are experts.
+        let mut candidates = manuscripts
+            .iter()
+            .map(|(id, manuscript)| {
+                let eligible = self
+                    .reviewers
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, r)| {
+                        !names[*i].is_empty()
+                            && names[..*i].iter().all(|n| n != &names[*i])
+                            && !r.conflicts_with(manuscript)
+                    })
+                    .map(|(i, r)| {
+                        let topics = std::iter::once(manuscript.type_.as_str()).chain(
+                            tags.get(id)
+                                .into_iter()
+                                .flat_map(|t| t.tags.iter().map(String::as_str)),
+                        );
+
+                        (i, r.is_expert_in(topics))
+                    })
+                    .collect::<Vec<_>>();
+
+                (*id, eligible)
+            })
+            .collect::<Vec<_>>();
+
+        // the most constrained first; the sort is stable to keep it deterministic.
+        candidates.sort_by_key(|(_, eligible)| eligible.len());
+
+        let mut loads = vec![0usize; self.reviewers.len()];
+        let mut assigned = HashMap::with_capacity(manuscripts.len());
+        for (id, mut eligible) in candidates {
+            eligible.sort_by_key(|&(i, expert)| (loads[i], !expert, i));
+            eligible.truncate(self.per_manuscript);
+
+            for &(i, _) in &eligible {
+                loads[i] += 1;
+            }
+            assigned.insert(
+                id,
+                eligible
+                    .into_iter()
+                    .map(|(i, _)| names[i].to_owned())
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        Assignment {
+            per_manuscript: self.per_manuscript,
+            manuscripts: manuscripts
+                .iter()
+                .filter_map(|(id, _)| Some((*id, assigned.remove(id)?)))
+                .collect(),
+        }
+    }
+}
+
+/// The reviewers assigned to every manuscript.
+#[serde_as]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Assignment {
+    /// The number of the reviewers every manuscript should have.
+    pub per_manuscript: usize,
+
+    /// The reviewers of the manuscripts, in the order of the manuscripts.
+    #[serde_as(as = "Map<_, _>")]
+    pub manuscripts: Vec<(ManuscriptId, Vec<String>)>,
+}
+
+impl Assignment {
+    /// Is there nothing assigned?
+    pub fn is_empty(&self) -> bool {
+        self.manuscripts.is_empty()
+    }
+
+    /// The reviewers assigned to this manuscript.
+    pub fn reviewers(&self, id: &ManuscriptId) -> &[String] {
+        self.manuscripts
+            .iter()
+            .find(|(i, _)| i == id)
+            .map_or(&[], |(_, reviewers)| reviewers)
+    }
+
+    /// The manuscripts assigned to this reviewer.
+    ///
+    /// Return `None` if the reviewer is not in this assignment at all.
+    pub fn of(&self, reviewer: &str) -> Option<HashSet<ManuscriptId>> {
+        let reviewer = reviewer.trim();
+        let assigned = self
+            .manuscripts
+            .iter()
+            .filter(|(_, reviewers)| reviewers.iter().any(|r| r == reviewer))
+            .map(|(id, _)| *id)
+            .collect::<HashSet<_>>();
+
+        (!assigned.is_empty()).then_some(assigned)
+    }
+
+    /// The number of the manuscripts assigned to every reviewer.
+    pub fn loads(&self) -> BTreeMap<&str, usize> {
+        let mut loads = BTreeMap::new();
+        for reviewer in self.manuscripts.iter().flat_map(|(_, r)| r) {
+            *loads.entry(reviewer.as_str()).or_default() += 1;
+        }
+
+        loads
+    }
+
+    /// The manuscripts with fewer reviewers than [`Self::per_manuscript`].
+    pub fn understaffed(&self) -> impl Iterator<Item = (&ManuscriptId, usize)> {
+        self.manuscripts
+            .iter()
+            .filter(|(_, reviewers)| reviewers.len() < self.per_manuscript)
+            .map(|(id, reviewers)| (id, reviewers.len()))
+    }
+
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+
+    pub fn from_yaml(document: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reviewer, Roster};
+    use crate::types::{tag::TagDatabase, Manuscript, ManuscriptDatabase};
+
+    fn manuscripts(speakers: &[(&str, &str)]) -> ManuscriptDatabase {
+        speakers
+            .iter()
+            .enumerate()
+            .map(|(i, (speaker, type_))| Manuscript::fixture(&format!("Talk {i}"), speaker, type_))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn reviewer(name: &str, expertise: &[&str], conflicts: &[&str]) -> Reviewer {
+        Reviewer {
+            name: name.into(),
+            expertise: expertise.iter().map(|&e| e.into()).collect(),
+            conflicts: conflicts.iter().map(|&c| c.into()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_assign() {
+        let manuscripts = manuscripts(&[
+            ("Alice", "Web"),
+            ("Dave", "AI"),
+            ("Erin", "AI"),
+            ("Frank", "Web"),
+            ("Grace", "Security"),
+            ("Heidi", "Web"),
+        ]);
+        let roster = Roster {
+            reviewers: vec![
+                reviewer("Alice", &["web"], &[]),
+                reviewer("Bob", &["AI"], &["dave"]),
+                reviewer("Carol", &[], &[]),
+                reviewer("Dan", &["Security"], &[]),
+            ],
+            per_manuscript: 2,
+        };
+
+        let assignment = roster.assign(&manuscripts, &TagDatabase::new());
+        assert_eq!(assignment.understaffed().count(), 0);
+
+        // 12 reviews among 4 reviewers.
+        assert!(assignment.loads().values().all(|&load| load == 3));
+
+        for (id, reviewers) in &assignment.manuscripts {
+            let manuscript = &manuscripts[id];
+
+            assert_eq!(reviewers.len(), 2);
+            assert!(roster
+                .reviewers
+                .iter()
+                .filter(|r| reviewers.contains(&r.name))
+                .all(|r| !r.conflicts_with(manuscript)));
+        }
+
+        let alice = assignment.of("Alice").unwrap();
+        assert_eq!(alice.len(), 3);
+        assert!(!alice.contains(manuscripts.first().unwrap()));
+        assert!(assignment.of("Mallory").is_none());
+
+        let document = assignment.to_yaml().unwrap();
+        assert_eq!(super::Assignment::from_yaml(&document).unwrap(), assignment);
+    }
+
+    #[test]
+    fn test_assign_understaffed() {
+        let manuscripts = manuscripts(&[("Alice", "Web"), ("Bob", "Web")]);
+        let roster = Roster {
+            reviewers: vec![
+                reviewer("Alice", &[], &[]),
+                reviewer("Bob", &[], &[]),
+                reviewer("Carol", &[], &["alice"]),
+            ],
+            per_manuscript: 2,
+        };
+
+        let assignment = roster.assign(&manuscripts, &TagDatabase::new());
+        let understaffed = assignment.understaffed().collect::<Vec<_>>();
+
+        assert_eq!(understaffed, [(manuscripts.first().unwrap(), 1)]);
+        assert_eq!(
+            assignment.reviewers(manuscripts.first().unwrap()),
+            ["Bob".to_owned()]
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! The blind review: hiding the speaker identity from the reviewer.
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Manuscript, ManuscriptId};
+
+/// The mask to replace the speaker name with.
+pub const MASK: &str = "███";
+
+/// The hosts of the profile sites which reveal the identity.
+const PROFILE_HOSTS: &[&str] = &[
+    "github.com",
+    "gitlab.com",
+    "twitter.com",
+    "x.com",
+    "facebook.com",
+    "instagram.com",
+    "linkedin.com",
+    "medium.com",
+    "youtube.com",
+    "threads.net",
+    "hackmd.io/@",
+    "ithelp.ithome.com.tw/users",
+];
+
+/// The names shorter than this (in chars) are not masked,
+/// or too many unrelated words would be masked.
+const MIN_NAME_CHARS: usize = 2;
+
+/// The parts of a speaker name to mask.
+///
+/// A name like `火山 / Kazan` is split into `火山` and `Kazan`.
+pub fn name_parts(name: &str) -> Vec<&str> {
+    name.split(['/', '／', ',', '，', '(', ')', '（', '）', '|'])
+        .map(str::trim)
+        .filter(|part| part.chars().count() >= MIN_NAME_CHARS)
+        .collect()
+}
+
+/// Find the byte ranges of `parts` in `text`, ignoring the ASCII case.
+fn find_parts(text: &str, parts: &[&str]) -> Vec<(usize, usize)> {
+    // ASCII lowercasing keeps the byte offsets.
+    let lower = text.to_ascii_lowercase();
+    let mut ranges = Vec::new();
+
+    for part in parts {
+        let part = part.to_ascii_lowercase();
+        ranges.extend(
+            lower
+                .match_indices(part.as_str())
+                .map(|(start, m)| (start, start + m.len())),
+        );
+    }
+
+    ranges.sort_unstable();
+    ranges
+}
+
+/// Mask the speaker name in `text`.
+pub fn mask<'a>(text: &'a str, parts: &[&str]) -> Cow<'a, str> {
+    let ranges = find_parts(text, parts);
+    if ranges.is_empty() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut masked = String::with_capacity(text.len());
+    let mut cursor = 0;
+
+    for (start, end) in ranges {
+        // the overlapped ranges are masked already.
+        if start < cursor {
+            cursor = cursor.max(end);
+            continue;
+        }
+
+        masked.push_str(&text[cursor..start]);
+        masked.push_str(MASK);
+        cursor = end;
+    }
+    masked.push_str(&text[cursor..]);
+
+    Cow::Owned(masked)
+}
+
+/// Why a field may reveal the speaker.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LeakReason {
+    /// The speaker name is mentioned.
+    NameMentioned,
+
+    /// The link to a profile, or containing the speaker name.
+    ProfileLink(String),
+}
+
+/// A field which may reveal the speaker.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IdentityLeak {
+    /// The field name, such as `description`.
+    pub field: &'static str,
+
+    pub reason: LeakReason,
+}
+
+impl std::fmt::Display for IdentityLeak {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reason {
+            LeakReason::NameMentioned => write!(f, "{}: the speaker name is mentioned", self.field),
+            LeakReason::ProfileLink(link) => write!(f, "{}: profile link {link}", self.field),
+        }
+    }
+}
+
+/// Find the fields of the manuscript which may reveal the speaker.
+///
+/// The speaker name is looked up in every field,
+/// and the links are looked up in the description and extra.
+pub fn identity_leaks(manuscript: &Manuscript) -> Vec<IdentityLeak> {
+    let parts = name_parts(&manuscript.author.name);
+    let mut leaks = Vec::new();
+
+    let fields = [
+        ("title", &manuscript.title),
+        ("abstract", &manuscript.abstract_),
+        ("audience", &manuscript.audience),
+        ("description", &manuscript.description),
+        ("extra", &manuscript.extra),
+    ];
+
+    for (field, text) in fields {
+        if !find_parts(text, &parts).is_empty() {
+            leaks.push(IdentityLeak {
+                field,
+                reason: LeakReason::NameMentioned,
+            });
+        }
+    }
+
+    for (field, text) in [
+        ("description", &manuscript.description),
+        ("extra", &manuscript.extra),
+    ] {
+        for link in links(text) {
+            let lower = link.to_ascii_lowercase();
+            let is_profile = PROFILE_HOSTS.iter().any(|host| lower.contains(host))
+                || !find_parts(link, &parts).is_empty();
+
+            if is_profile {
+                leaks.push(IdentityLeak {
+                    field,
+                    reason: LeakReason::ProfileLink(link.to_owned()),
+                });
+            }
+        }
+    }
+
+    leaks
+}
+
+/// Find the links in `text`.
+fn links(text: &str) -> impl Iterator<Item = &str> {
+    text.match_indices("http").filter_map(|(start, _)| {
+        let rest = &text[start..];
+        if !(rest.starts_with("http://") || rest.starts_with("https://")) {
+            return None;
+        }
+
+        let end = rest
+            .find(|c: char| c.is_whitespace() || !c.is_ascii())
+            .unwrap_or(rest.len());
+        Some(&rest[..end])
+    })
+}
+
+/// The log of the blind review.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct BlindLog {
+    /// The manuscripts whose speaker has been shown to the reviewer.
+    #[serde(default)]
+    unblinded: HashSet<ManuscriptId>,
+
+    /// Was the review done blind?
+    ///
+    /// It is recorded when the manuscript is marked as reviewed.
+    #[serde(default)]
+    reviews: HashMap<ManuscriptId, bool>,
+}
+
+impl BlindLog {
+    /// Record that the speaker of the manuscript has been shown.
+    pub fn mark_unblinded(&mut self, id: ManuscriptId) {
+        self.unblinded.insert(id);
+    }
+
+    /// Record the review of the manuscript is done.
+    ///
+    /// The review is blind if the speaker has never been shown.
+    pub fn record_review(&mut self, id: ManuscriptId) {
+        let blind = !self.unblinded.contains(&id);
+        self.reviews.insert(id, blind);
+    }
+
+    /// Was the review of the manuscript done blind?
+    ///
+    /// Return `None` if the review has not been recorded.
+    pub fn was_blind(&self, id: &ManuscriptId) -> Option<bool> {
+        self.reviews.get(id).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::Manuscript;
+
+    use super::{identity_leaks, mask, name_parts, LeakReason, MASK};
+
+    #[test]
+    fn test_name_parts() {
+        assert_eq!(name_parts("火山 / Kazan"), ["火山", "Kazan"]);
+        assert_eq!(name_parts("FKT"), ["FKT"]);
+        assert!(name_parts("A").is_empty());
+    }
+
+    #[test]
+    fn test_mask() {
+        let parts = name_parts("火山 / Kazan");
+
+        assert_eq!(
+            mask("我是火山，網站 https://kazan.tw", &parts),
+            format!("我是{MASK}，網站 https://{MASK}.tw")
+        );
+        assert_eq!(mask("nothing here", &parts), "nothing here");
+    }
+
+    #[test]
+    fn test_identity_leaks() {
+        let manuscript = Manuscript {
+            description: "我的 GitHub https://github.com/someone".into(),
+            extra: "簡報：https://docs.google.com/presentation/d/1".into(),
+            ..Manuscript::fixture("開源貢獻", "uccu", "general (30mins)")
+        };
+
+        let leaks = identity_leaks(&manuscript);
+        assert_eq!(leaks.len(), 1);
+        assert_eq!(leaks[0].field, "description");
+        assert_eq!(
+            leaks[0].reason,
+            LeakReason::ProfileLink("https://github.com/someone".into())
+        );
+    }
+}
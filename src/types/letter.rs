@@ -0,0 +1,551 @@
+//! The feedback letters to the authors.
+//!
+//! A letter is rendered from a [`LetterTemplate`] with the manuscript fields,
+//! the decision, the group scores and the group comments. The private notes
+//! of the reviewer, the tags and the item comments are never included.
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    rank::sheet::ReviewSheet,
+    tag::TagDatabase,
+    timestamp::{DateTime, Timestamp},
+    Manuscript, ManuscriptDatabase, ManuscriptId,
+};
+
+/// The language of a letter.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "zh")]
+    Chinese,
+
+    #[serde(rename = "en")]
+    English,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Chinese, Language::English];
+
+    /// The name of this language, in itself.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Chinese => "中文",
+            Language::English => "English",
+        }
+    }
+}
+
+/// The decision on a manuscript, set by the committee.
+///
+/// It is kept apart from the shortlist, which is only a reminder of the reviewer.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    #[default]
+    Undecided,
+    Accepted,
+    Declined,
+}
+
+impl Decision {
+    pub const ALL: [Decision; 3] = [Decision::Undecided, Decision::Accepted, Decision::Declined];
+
+    /// The decision on the manuscript.
+    pub fn of(tags: &TagDatabase, id: &ManuscriptId) -> Self {
+        tags.get(id).map_or_else(Decision::default, |t| t.decision)
+    }
+
+    pub fn is_undecided(&self) -> bool {
+        *self == Decision::Undecided
+    }
+
+    pub fn label(&self, language: Language) -> &'static str {
+        match (self, language) {
+            (Decision::Undecided, Language::Chinese) => "待定",
+            (Decision::Accepted, Language::Chinese) => "錄取",
+            (Decision::Declined, Language::Chinese) => "未錄取",
+            (Decision::Undecided, Language::English) => "Undecided",
+            (Decision::Accepted, Language::English) => "Accepted",
+            (Decision::Declined, Language::English) => "Not accepted",
+        }
+    }
+}
+
+/// The template of a letter.
+///
+/// The placeholders are written as `{{name}}`. See [`PLACEHOLDERS`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct LetterTemplate {
+    pub subject: String,
+    pub body: String,
+}
+
+/// The placeholders of a [`LetterTemplate`], with their descriptions.
+pub const PLACEHOLDERS: &[(&str, &str)] = &[
+    ("title", "the title of the manuscript"),
+    ("speaker", "the name of the speaker"),
+    ("type", "the type of the manuscript"),
+    ("decision", "the decision"),
+    ("total", "the total score"),
+    ("scores", "the list of the group scores"),
+    ("comments", "the group comments, under the group names"),
+];
+
+impl LetterTemplate {
+    /// The built-in template of the language.
+    pub fn builtin(language: Language) -> Self {
+        match language {
+            Language::Chinese => Self {
+                subject: "【{{decision}}】「{{title}}」審稿結果".to_owned(),
+                body: indoc::indoc! {"
+                    {{speaker}} 您好：
+
+                    感謝您投稿「{{title}}」({{type}})。經審稿委員會討論，審稿結果為：**{{decision}}**。
+
+                    ## 評分
+
+                    {{scores}}
+
+                    總分：{{total}}
+
+                    ## 審稿意見
+
+                    {{comments}}
+
+                    再次感謝您的投稿！
+                "}
+                .to_owned(),
+            },
+            Language::English => Self {
+                subject: "[{{decision}}] Review result of \"{{title}}\"".to_owned(),
+                body: indoc::indoc! {"
+                    Dear {{speaker}},
+
+                    Thank you for submitting \"{{title}}\" ({{type}}). After the discussion of the committee, the decision is: **{{decision}}**.
+
+                    ## Scores
+
+                    {{scores}}
+
+                    Total: {{total}}
+
+                    ## Comments from the reviewers
+
+                    {{comments}}
+
+                    Thank you again for your submission!
+                "}
+                .to_owned(),
+            },
+        }
+    }
+
+    /// Render the letter of a manuscript.
+    pub fn render(
+        &self,
+        manuscript: &Manuscript,
+        decision: Decision,
+        review: &ReviewSheet,
+        language: Language,
+    ) -> Result<Letter, Error> {
+        let value = |name: &str| -> Result<String, Error> {
+            Ok(match name {
+                "title" => manuscript.title.clone(),
+                "speaker" => manuscript.author.name.clone(),
+                "type" => manuscript.type_.clone(),
+                "decision" => decision.label(language).to_owned(),
+                "total" => format!("{:.2}", review.total),
+                "scores" => review
+                    .groups
+                    .iter()
+                    .map(|g| format!("- {}: {:.2}", g.name, g.score))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                "comments" => {
+                    let mut comments = String::new();
+                    for group in review
+                        .groups
+                        .iter()
+                        .filter(|g| !g.comment.trim().is_empty())
+                    {
+                        // writing to a String never fails.
+                        let _ = write!(
+                            comments,
+                            "### {}\n\n{}\n\n",
+                            group.name,
+                            group.comment.trim()
+                        );
+                    }
+                    comments.trim_end().to_owned()
+                }
+                _ => return Err(Error::UnknownPlaceholder(name.to_owned())),
+            })
+        };
+
+        Ok(Letter {
+            speaker: manuscript.author.name.clone(),
+            title: manuscript.title.clone(),
+            subject: substitute(&self.subject, value)?,
+            body: substitute(&self.body, value)?,
+        })
+    }
+}
+
+/// The templates of every language.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct LetterTemplates {
+    pub zh: LetterTemplate,
+    pub en: LetterTemplate,
+}
+
+impl LetterTemplates {
+    pub fn get(&self, language: Language) -> &LetterTemplate {
+        match language {
+            Language::Chinese => &self.zh,
+            Language::English => &self.en,
+        }
+    }
+
+    pub fn get_mut(&mut self, language: Language) -> &mut LetterTemplate {
+        match language {
+            Language::Chinese => &mut self.zh,
+            Language::English => &mut self.en,
+        }
+    }
+}
+
+impl Default for LetterTemplates {
+    fn default() -> Self {
+        Self {
+            zh: LetterTemplate::builtin(Language::Chinese),
+            en: LetterTemplate::builtin(Language::English),
+        }
+    }
+}
+
+/// Replace the `{{name}}` placeholders in `template`.
+fn substitute(
+    template: &str,
+    value: impl Fn(&str) -> Result<String, Error>,
+) -> Result<String, Error> {
+    let mut rendered = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        rendered += &rest[..start];
+
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or(Error::UnclosedPlaceholder)?;
+        rendered += &value(after[..end].trim())?;
+        rest = &after[end + 2..];
+    }
+    rendered += rest;
+
+    Ok(rendered)
+}
+
+/// A rendered letter.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Letter {
+    /// The name of the speaker, to whom the letter is sent.
+    pub speaker: String,
+
+    /// The title of the manuscript.
+    pub title: String,
+
+    pub subject: String,
+    pub body: String,
+}
+
+impl Letter {
+    /// Render the letters of the reviewed manuscripts with a decision.
+    pub fn of_reviewed<'a>(
+        manuscripts: &ManuscriptDatabase,
+        tags: &TagDatabase,
+        review: impl Fn(&ManuscriptId) -> Option<&'a ReviewSheet>,
+        template: &LetterTemplate,
+        language: Language,
+    ) -> Result<Vec<Letter>, Error> {
+        manuscripts
+            .iter()
+            .filter_map(|(id, manuscript)| {
+                let review = review(id).filter(|r| r.reviewed)?;
+                let decision = Decision::of(tags, id);
+                if decision.is_undecided() {
+                    return None;
+                }
+
+                Some(template.render(manuscript, decision, review, language))
+            })
+            .collect()
+    }
+
+    /// Write as a Markdown document.
+    pub fn to_markdown(&self) -> String {
+        format!("# {}\n\n{}", self.subject, self.body)
+    }
+
+    /// Write as an email message (RFC 5322) from `from`.
+    ///
+    /// The manuscripts have no email address, so the recipient is
+    /// an empty group named after the speaker, to be filled in before sending.
+    pub fn to_eml(&self, from: &str, date: Timestamp) -> String {
+        let DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        } = date.to_date_time();
+        let month = MONTHS[month as usize - 1];
+
+        let mut message = String::new();
+        let _ = write!(
+            message,
+            "From: {}\r\n\
+             To: {}:;\r\n\
+             Subject: {}\r\n\
+             Date: {day} {month} {year} {hour:02}:{minute:02}:{second:02} +0000\r\n\
+             MIME-Version: 1.0\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\
+             Content-Transfer-Encoding: 8bit\r\n\
+             \r\n",
+            strip_line_breaks(from),
+            encode_word(&strip_line_breaks(&self.speaker)),
+            encode_word(&strip_line_breaks(&self.subject)),
+        );
+        for line in self.body.lines() {
+            message += line;
+            message += "\r\n";
+        }
+
+        message
+    }
+}
+
+/// Bundle the letters in a Markdown document, separated by rules.
+pub fn to_markdown(letters: &[Letter]) -> String {
+    letters
+        .iter()
+        .map(Letter::to_markdown)
+        .collect::<Vec<_>>()
+        .join("\n---\n\n")
+}
+
+/// Bundle the letters in an mbox, which most mail clients can import.
+pub fn to_mbox(letters: &[Letter], from: &str, date: Timestamp) -> String {
+    let mut mbox = String::new();
+
+    for letter in letters {
+        let _ = writeln!(mbox, "From review-tool {}", date.0);
+        for line in letter.to_eml(from, date).lines() {
+            // mboxrd: quote the lines which look like the separator.
+            if line.trim_start_matches('>').starts_with("From ") {
+                mbox.push('>');
+            }
+            mbox += line;
+            mbox.push('\n');
+        }
+        mbox.push('\n');
+    }
+
+    mbox
+}
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Replace the line breaks in a header value, which would otherwise start a new header.
+fn strip_line_breaks(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+/// The most bytes of text in an encoded word, which keeps the word within
+/// the 75 characters of RFC 2047: `=?UTF-8?B?` and `?=` and 60 Base64 characters.
+const ENCODED_WORD_BYTES: usize = 45;
+
+/// Encode a header value as RFC 2047 encoded words, if it is not ASCII.
+///
+/// A long value is split into several words on the character boundaries,
+/// folded onto the continuation lines.
+fn encode_word(text: &str) -> String {
+    if text.is_ascii() {
+        return text.to_owned();
+    }
+
+    let mut words = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if i + c.len_utf8() - start > ENCODED_WORD_BYTES {
+            words.push(&text[start..i]);
+            start = i;
+        }
+    }
+    words.push(&text[start..]);
+
+    words
+        .into_iter()
+        .map(|word| format!("=?UTF-8?B?{}?=", base64(word.as_bytes())))
+        .collect::<Vec<_>>()
+        .join("\r\n ")
+}
+
+const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("unknown placeholder `{{{{{0}}}}}`")]
+    UnknownPlaceholder(String),
+
+    #[error("a placeholder is not closed with `}}}}`")]
+    UnclosedPlaceholder,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        encode_word, to_mbox, Decision, Language, Letter, LetterTemplate, BASE64_ALPHABET,
+    };
+    use crate::types::{
+        rank::{sheet::ReviewSheet, sitcon_gdsc, CommentableItemGroup},
+        tag::TagDatabase,
+        timestamp::Timestamp,
+        Manuscript, ManuscriptDatabase,
+    };
+
+    #[test]
+    fn test_render() {
+        let manuscript = Manuscript::fixture("從零開始的 Rust", "小明", "Talk");
+        let mut rank = sitcon_gdsc::Group::default();
+        *rank.subject.comment_mut() = "主題明確".to_owned();
+        let review = ReviewSheet::of(&rank);
+
+        let letter = LetterTemplate::builtin(Language::Chinese)
+            .render(&manuscript, Decision::Accepted, &review, Language::Chinese)
+            .unwrap();
+        assert_eq!(letter.subject, "【錄取】「從零開始的 Rust」審稿結果");
+        assert!(letter.body.starts_with("小明 您好："));
+        assert!(letter.body.contains("主題明確"));
+        assert!(!letter.body.contains("{{"));
+
+        let unknown = LetterTemplate {
+            subject: "{{note}}".into(),
+            body: String::new(),
+        };
+        assert!(unknown
+            .render(&manuscript, Decision::Declined, &review, Language::English)
+            .is_err());
+
+        assert_eq!(encode_word("Hi"), "Hi");
+        assert_eq!(encode_word("小明"), "=?UTF-8?B?5bCP5piO?=");
+
+        let mbox = to_mbox(&[letter], "committee@example.com", Timestamp(0));
+        assert!(mbox.starts_with("From review-tool 0\n"));
+        assert!(mbox.contains("Date: 1 Jan 1970 00:00:00 +0000"));
+    }
+
+    #[test]
+    fn test_skip_undecided() {
+        let manuscripts: ManuscriptDatabase = vec![
+            Manuscript::fixture("Accepted", "A", "Talk"),
+            Manuscript::fixture("Undecided", "B", "Talk"),
+            Manuscript::fixture("Starred", "C", "Talk"),
+        ]
+        .into();
+        let ids = manuscripts.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        let review = ReviewSheet {
+            reviewed: true,
+            ..ReviewSheet::of(&sitcon_gdsc::Group::default())
+        };
+
+        let mut tags = TagDatabase::new();
+        tags.get_or_default(ids[0]).decision = Decision::Accepted;
+        // The shortlist is not a decision.
+        tags.get_or_default(ids[2]).starred = true;
+        assert_eq!(Decision::of(&tags, &ids[1]), Decision::Undecided);
+        assert_eq!(Decision::of(&tags, &ids[2]), Decision::Undecided);
+
+        let template = LetterTemplate::builtin(Language::English);
+        let letters = Letter::of_reviewed(
+            &manuscripts,
+            &tags,
+            |_| Some(&review),
+            &template,
+            Language::English,
+        )
+        .unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].title, "Accepted");
+        assert!(letters[0].subject.starts_with("[Accepted]"));
+    }
+
+    #[test]
+    fn test_encode_long_subject() {
+        fn decode(word: &str) -> Vec<u8> {
+            let encoded = word
+                .strip_prefix("=?UTF-8?B?")
+                .and_then(|w| w.strip_suffix("?="))
+                .unwrap();
+            let mut bits = 0u32;
+            let mut len = 0;
+            let mut bytes = Vec::new();
+            for c in encoded.bytes().filter(|&c| c != b'=') {
+                let value = BASE64_ALPHABET.iter().position(|&a| a == c).unwrap();
+                bits = bits << 6 | value as u32;
+                len += 6;
+                if len >= 8 {
+                    len -= 8;
+                    bytes.push((bits >> len) as u8);
+                }
+            }
+            bytes
+        }
+
+        let title =
+            "【錄取】「從零開始的 Rust：以所有權與生命週期打造安全又快速的系統程式」審稿結果";
+        let encoded = encode_word(title);
+        let words = encoded.split("\r\n ").collect::<Vec<_>>();
+        assert!(words.len() > 1);
+        assert!(words.iter().all(|w| w.len() <= 75));
+        let decoded = words.iter().flat_map(|w| decode(w)).collect::<Vec<_>>();
+        assert_eq!(String::from_utf8(decoded).unwrap(), title);
+        // Every word is valid UTF-8 on its own.
+        assert!(words.iter().all(|w| String::from_utf8(decode(w)).is_ok()));
+
+        let letter = Letter {
+            speaker: "小明".into(),
+            title: String::new(),
+            subject: "審稿結果\r\nBcc: everyone@example.com".into(),
+            body: String::new(),
+        };
+        let eml = letter.to_eml("committee@example.com\nBcc: x@example.com", Timestamp(0));
+        assert!(!eml.lines().any(|line| line.starts_with("Bcc:")));
+    }
+}
@@ -0,0 +1,368 @@
+//! The pairwise comparison of manuscripts.
+//!
+//! Instead of scoring a manuscript absolutely, the reviewer picks the
+//! stronger one of two manuscripts on a rank group. The comparisons are
+//! fitted with the Bradley–Terry model to produce a global ordering.
+
+use std::{
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{timestamp::Timestamp, ManuscriptId};
+
+/// The max number of iterations to fit the model.
+const MAX_ITERATIONS: usize = 1000;
+
+/// The model is converged if the strengths change less than this.
+const TOLERANCE: f64 = 1e-9;
+
+/// The outcome of a comparison.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Outcome {
+    /// The left manuscript is stronger.
+    Left,
+
+    /// The right manuscript is stronger.
+    Right,
+
+    /// Both are equally strong.
+    Tie,
+}
+
+/// A comparison of two manuscripts on a rank group.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Comparison {
+    pub left: ManuscriptId,
+    pub right: ManuscriptId,
+
+    /// The key of the compared group, such as `subject`.
+    pub group: String,
+
+    pub outcome: Outcome,
+    pub compared_at: Timestamp,
+}
+
+impl Comparison {
+    /// The points of `left` and `right`. A tie is half a win for both.
+    fn points(&self) -> (f64, f64) {
+        match self.outcome {
+            Outcome::Left => (1.0, 0.0),
+            Outcome::Right => (0.0, 1.0),
+            Outcome::Tie => (0.5, 0.5),
+        }
+    }
+}
+
+/// The database of the comparisons, in the order of comparing.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct ComparisonDatabase(Vec<Comparison>);
+
+impl ComparisonDatabase {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Record a comparison.
+    pub fn record(
+        &mut self,
+        left: ManuscriptId,
+        right: ManuscriptId,
+        group: &str,
+        outcome: Outcome,
+    ) {
+        self.0.push(Comparison {
+            left,
+            right,
+            group: group.to_owned(),
+            outcome,
+            compared_at: Timestamp::now(),
+        });
+    }
+
+    /// The comparisons on `group`, or all the comparisons if `group` is `None`.
+    pub fn of_group<'a>(&'a self, group: Option<&'a str>) -> impl Iterator<Item = &'a Comparison> {
+        self.0.iter().filter(move |c| match group {
+            Some(g) => c.group == g,
+            None => true,
+        })
+    }
+}
+
+impl Deref for ComparisonDatabase {
+    type Target = Vec<Comparison>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for ComparisonDatabase {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// A manuscript in the fitted ordering.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RankingEntry {
+    pub id: ManuscriptId,
+
+    /// The log-strength in the Bradley–Terry model.
+    ///
+    /// The probability that `a` beats `b` is
+    /// `1 / (1 + exp(b.strength - a.strength))`.
+    pub strength: f64,
+
+    /// The standard error of [`RankingEntry::strength`].
+    pub std_error: f64,
+
+    /// The number of comparisons of this manuscript.
+    pub comparisons: usize,
+
+    /// The wins of this manuscript. A tie is half a win.
+    pub wins: f64,
+}
+
+impl RankingEntry {
+    /// The 95% confidence interval of the strength.
+    pub fn interval(&self) -> (f64, f64) {
+        (
+            self.strength - 1.96 * self.std_error,
+            self.strength + 1.96 * self.std_error,
+        )
+    }
+}
+
+/// The fitted Bradley–Terry model.
+pub struct Ranking {
+    /// The entries in the order of `ids` given to [`Ranking::fit`].
+    entries: Vec<RankingEntry>,
+
+    /// The number of comparisons between each pair.
+    games: Vec<Vec<f64>>,
+}
+
+impl Ranking {
+    /// Fit the Bradley–Terry model with the MM algorithm.
+    ///
+    /// Every manuscript plays a virtual tie against a pseudo-manuscript
+    /// of strength 0, so the manuscripts without comparisons or losses
+    /// still have a finite strength. The pseudo-manuscript also anchors
+    /// the scale, so the strengths are not normalized.
+    ///
+    /// The comparisons of the manuscripts not in `ids` are ignored.
+    pub fn fit<'a>(
+        ids: &[ManuscriptId],
+        comparisons: impl IntoIterator<Item = &'a Comparison>,
+    ) -> Self {
+        let n = ids.len();
+        if n == 0 {
+            return Self {
+                entries: Vec::new(),
+                games: Vec::new(),
+            };
+        }
+
+        let index = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect::<HashMap<_, _>>();
+
+        let mut games = vec![vec![0.0; n]; n];
+        let mut wins = vec![0.0; n];
+        let mut counts = vec![0; n];
+
+        for c in comparisons {
+            let (Some(&l), Some(&r)) = (index.get(&c.left), index.get(&c.right)) else {
+                continue;
+            };
+            if l == r {
+                continue;
+            }
+
+            let (lp, rp) = c.points();
+            games[l][r] += 1.0;
+            games[r][l] += 1.0;
+            wins[l] += lp;
+            wins[r] += rp;
+            counts[l] += 1;
+            counts[r] += 1;
+        }
+
+        // The MM algorithm works on the (exponential) strength.
+        let mut p = vec![1.0; n];
+        for _ in 0..MAX_ITERATIONS {
+            let next = (0..n)
+                .map(|i| {
+                    // the virtual tie: half a win in a game against strength 1.
+                    let denominator = 1.0 / (p[i] + 1.0)
+                        + (0..n)
+                            .filter(|&j| games[i][j] > 0.0)
+                            .map(|j| games[i][j] / (p[i] + p[j]))
+                            .sum::<f64>();
+
+                    (wins[i] + 0.5) / denominator
+                })
+                .collect::<Vec<_>>();
+
+            let change = p
+                .iter()
+                .zip(next.iter())
+                .map(|(a, b)| (a.ln() - b.ln()).abs())
+                .fold(0.0, f64::max);
+            p = next;
+
+            if change < TOLERANCE {
+                break;
+            }
+        }
+
+        let entries = (0..n)
+            .map(|i| {
+                // the diagonal of the Fisher information.
+                let information = p[i] / (p[i] + 1.0).powi(2)
+                    + (0..n)
+                        .filter(|&j| games[i][j] > 0.0)
+                        .map(|j| games[i][j] * p[i] * p[j] / (p[i] + p[j]).powi(2))
+                        .sum::<f64>();
+
+                RankingEntry {
+                    id: ids[i],
+                    strength: p[i].ln(),
+                    std_error: information.sqrt().recip(),
+                    comparisons: counts[i],
+                    wins: wins[i],
+                }
+            })
+            .collect();
+
+        Self { entries, games }
+    }
+
+    /// The entries sorted by the strength, the strongest first.
+    pub fn ordered(&self) -> Vec<&RankingEntry> {
+        let mut ordered = self.entries.iter().collect::<Vec<_>>();
+        ordered.sort_by(|a, b| b.strength.total_cmp(&a.strength));
+        ordered
+    }
+
+    /// The entry of the manuscript.
+    pub fn get(&self, id: &ManuscriptId) -> Option<&RankingEntry> {
+        self.entries.iter().find(|e| e.id == *id)
+    }
+
+    /// Choose the most informative pair to compare next.
+    ///
+    /// The pairs whose outcome is most uncertain – close strengths and
+    /// large standard errors – and rarely compared are preferred.
+    /// The pair `except` (in either order) is never chosen.
+    pub fn next_pair(
+        &self,
+        except: Option<(ManuscriptId, ManuscriptId)>,
+    ) -> Option<(ManuscriptId, ManuscriptId)> {
+        let n = self.entries.len();
+        let mut best: Option<(f64, usize, usize)> = None;
+
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let (a, b) = (&self.entries[i], &self.entries[j]);
+
+                if let Some((x, y)) = except {
+                    if (x, y) == (a.id, b.id) || (y, x) == (a.id, b.id) {
+                        continue;
+                    }
+                }
+
+                let p = 1.0 / (1.0 + (b.strength - a.strength).exp());
+                let score = p * (1.0 - p) * (a.std_error + b.std_error) / (1.0 + self.games[i][j]);
+
+                match best {
+                    Some((best_score, _, _)) if best_score >= score => {}
+                    _ => best = Some((score, i, j)),
+                }
+            }
+        }
+
+        best.map(|(_, i, j)| {
+            // show the less compared manuscript on the left.
+            if self.entries[j].comparisons < self.entries[i].comparisons {
+                (self.entries[j].id, self.entries[i].id)
+            } else {
+                (self.entries[i].id, self.entries[j].id)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComparisonDatabase, Outcome, Ranking};
+    use crate::types::ManuscriptId;
+
+    fn id(n: u8) -> ManuscriptId {
+        ManuscriptId([n; 32])
+    }
+
+    #[test]
+    fn test_fit_order() {
+        let ids = [id(0), id(1), id(2)];
+        let mut db = ComparisonDatabase::new();
+
+        // 2 > 1 > 0
+        for _ in 0..3 {
+            db.record(id(2), id(1), "subject", Outcome::Left);
+            db.record(id(1), id(0), "subject", Outcome::Left);
+            db.record(id(0), id(2), "subject", Outcome::Right);
+        }
+        db.record(id(0), id(1), "content", Outcome::Left);
+
+        let ranking = Ranking::fit(&ids, db.of_group(Some("subject")));
+        let ordered = ranking.ordered().iter().map(|e| e.id).collect::<Vec<_>>();
+
+        assert_eq!(ordered, [id(2), id(1), id(0)]);
+        assert_eq!(ranking.get(&id(1)).unwrap().comparisons, 6);
+        assert_eq!(ranking.get(&id(1)).unwrap().wins, 3.0);
+    }
+
+    #[test]
+    fn test_fit_without_comparisons() {
+        let ids = [id(0), id(1)];
+        let ranking = Ranking::fit(&ids, []);
+
+        for entry in ranking.ordered() {
+            assert!(entry.strength.abs() < 1e-9);
+            assert!(entry.std_error.is_finite());
+        }
+    }
+
+    #[test]
+    fn test_tie() {
+        let ids = [id(0), id(1)];
+        let mut db = ComparisonDatabase::new();
+        db.record(id(0), id(1), "subject", Outcome::Tie);
+
+        let ranking = Ranking::fit(&ids, db.iter());
+        let (a, b) = (ranking.get(&id(0)).unwrap(), ranking.get(&id(1)).unwrap());
+
+        assert!((a.strength - b.strength).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_next_pair_prefers_uncompared() {
+        let ids = [id(0), id(1), id(2)];
+        let mut db = ComparisonDatabase::new();
+        for _ in 0..5 {
+            db.record(id(0), id(1), "subject", Outcome::Tie);
+        }
+
+        let ranking = Ranking::fit(&ids, db.iter());
+        let (l, r) = ranking.next_pair(None).unwrap();
+
+        assert!(l == id(2) || r == id(2));
+        assert_ne!(ranking.next_pair(Some((l, r))), Some((l, r)));
+    }
+}
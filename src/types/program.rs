@@ -0,0 +1,847 @@
+//! The program: the accepted talks in the rooms and the time slots.
+//!
+//! The program is drafted from the scores of the manuscripts: among the
+//! talks that fit, the ones of the highest total score are chosen by an exact
+//! branch-and-bound search, then placed the highest score first, each in the
+//! shortest free slot it fits in. A speaker gives at most one talk, the quotas
+//! of the types and the difficulties are respected, and the pinned talks are
+//! kept in place. The draft can be edited and exported as CSV or iCalendar.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{timestamp::Timestamp, Difficulty, Manuscript, ManuscriptDatabase, ManuscriptId};
+
+/// The duration of a talk whose type has no duration, in minutes.
+pub const DEFAULT_MINUTES: u32 = 40;
+
+/// Parse the duration of a talk from its type, such as `一般議程（40 分鐘）`
+/// or `Lightning talk (5 min)`.
+pub fn parse_minutes(type_: &str) -> Option<u32> {
+    let mut rest = type_;
+
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        let digits = &rest[start..];
+        let end = digits
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(digits.len());
+        let unit = digits[end..].trim_start().to_lowercase();
+
+        if unit.starts_with('分') || unit.starts_with("min") {
+            return digits[..end].parse().ok();
+        }
+        rest = &digits[end..];
+    }
+
+    None
+}
+
+/// A time slot, shared by all the rooms.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Slot {
+    /// The start time, as `HH:MM`.
+    pub start: String,
+
+    /// The length of this slot, in minutes.
+    pub minutes: u32,
+}
+
+impl Slot {
+    /// The start time, in minutes since midnight.
+    pub fn start_minute(&self) -> Result<u32, Error> {
+        let invalid = || Error::InvalidTime(self.start.clone());
+
+        let (hour, minute) = self.start.trim().split_once(':').ok_or_else(invalid)?;
+        let hour = hour.parse::<u32>().map_err(|_| invalid())?;
+        let minute = minute.parse::<u32>().map_err(|_| invalid())?;
+        if hour >= 24 || minute >= 60 {
+            return Err(invalid());
+        }
+
+        Ok(hour * 60 + minute)
+    }
+}
+
+/// The manuscripts counted by a [`Quota`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    /// The manuscripts whose type contains this text.
+    Type(String),
+
+    Difficulty(Difficulty),
+}
+
+impl Category {
+    pub fn matches(&self, manuscript: &Manuscript) -> bool {
+        match self {
+            Category::Type(type_) => manuscript.type_.contains(type_.as_str()),
+            Category::Difficulty(difficulty) => manuscript.difficulty == *difficulty,
+        }
+    }
+}
+
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Category::Type(type_) => write!(f, "type “{type_}”"),
+            Category::Difficulty(difficulty) => write!(f, "difficulty “{difficulty}”"),
+        }
+    }
+}
+
+/// The max number of the talks of a category.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Quota {
+    pub category: Category,
+    pub max: usize,
+}
+
+/// The rooms and the time slots of the program.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ProgramConfig {
+    /// The date of the event, as `YYYY-MM-DD`. It is required by iCalendar.
+    #[serde(default)]
+    pub date: String,
+
+    pub rooms: Vec<String>,
+    pub slots: Vec<Slot>,
+
+    /// The duration of a talk whose type has no duration.
+    pub default_minutes: u32,
+
+    #[serde(default)]
+    pub quotas: Vec<Quota>,
+}
+
+impl Default for ProgramConfig {
+    fn default() -> Self {
+        Self {
+            date: String::new(),
+            rooms: vec!["R0".to_owned()],
+            slots: ["09:00", "10:00", "11:00"]
+                .map(|start| Slot {
+                    start: start.to_owned(),
+                    minutes: DEFAULT_MINUTES,
+                })
+                .to_vec(),
+            default_minutes: DEFAULT_MINUTES,
+            quotas: Vec::new(),
+        }
+    }
+}
+
+/// A talk placed in the program.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ScheduledTalk {
+    pub id: ManuscriptId,
+
+    /// The index of the room in [`ProgramConfig::rooms`].
+    pub room: usize,
+
+    /// The index of the slot in [`ProgramConfig::slots`].
+    pub slot: usize,
+
+    /// Is this talk kept in place when the program is filled again?
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// A broken constraint in an edited program.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Violation {
+    /// The speaker gives more than one talk.
+    SpeakerTwice { speaker: String },
+
+    /// The talk is longer than its slot.
+    TooLong { id: ManuscriptId, minutes: u32 },
+
+    /// There are more talks of the category than its quota.
+    OverQuota { category: Category, count: usize },
+}
+
+/// The draft of the program.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Program {
+    pub config: ProgramConfig,
+    pub talks: Vec<ScheduledTalk>,
+}
+
+impl Program {
+    /// The duration of a talk, in minutes.
+    pub fn minutes(&self, manuscript: &Manuscript) -> u32 {
+        parse_minutes(&manuscript.type_).unwrap_or(self.config.default_minutes)
+    }
+
+    /// The talk in the room at the slot.
+    pub fn at(&self, room: usize, slot: usize) -> Option<&ScheduledTalk> {
+        self.talks.iter().find(|t| t.room == room && t.slot == slot)
+    }
+
+    /// Place the manuscript in the room at the slot, replacing the talk there.
+    ///
+    /// The placed talk is pinned, as it has been chosen by hand.
+    pub fn place(&mut self, id: ManuscriptId, room: usize, slot: usize) {
+        self.talks
+            .retain(|t| t.id != id && !(t.room == room && t.slot == slot));
+        self.talks.push(ScheduledTalk {
+            id,
+            room,
+            slot,
+            pinned: true,
+        });
+    }
+
+    /// Remove the talk in the room at the slot.
+    pub fn remove(&mut self, room: usize, slot: usize) {
+        self.talks.retain(|t| !(t.room == room && t.slot == slot));
+    }
+
+    /// Fill the program with the scored manuscripts, keeping the pinned talks.
+    ///
+    /// The total score of the placed talks is the highest possible under the
+    /// constraints. The manuscripts without a score are never placed.
+    pub fn fill(&mut self, manuscripts: &ManuscriptDatabase, scores: &HashMap<ManuscriptId, f64>) {
+        let (rooms, slots) = (self.config.rooms.len(), self.config.slots.len());
+        self.talks.retain(|t| {
+            t.pinned && t.room < rooms && t.slot < slots && manuscripts.contains_key(&t.id)
+        });
+
+        let pinned_speakers = self
+            .talks
+            .iter()
+            .map(|t| speaker_key(&manuscripts[&t.id]))
+            .collect::<HashSet<_>>();
+        let quotas = self
+            .config
+            .quotas
+            .iter()
+            .map(|q| {
+                let taken = self
+                    .talks
+                    .iter()
+                    .filter(|t| q.category.matches(&manuscripts[&t.id]))
+                    .count();
+                q.max.saturating_sub(taken)
+            })
+            .collect::<Vec<_>>();
+
+        // the free cells, the shortest and the earliest first.
+        let mut cells = (0..slots)
+            .flat_map(|slot| (0..rooms).map(move |room| (room, slot)))
+            .filter(|&(room, slot)| self.at(room, slot).is_none())
+            .collect::<Vec<_>>();
+        cells.sort_by_key(|&(room, slot)| (self.config.slots[slot].minutes, slot, room));
+        let mut lengths = cells
+            .iter()
+            .map(|&(_, slot)| self.config.slots[slot].minutes)
+            .collect::<Vec<_>>();
+        lengths.dedup();
+        let capacity = lengths
+            .iter()
+            .map(|&length| {
+                cells
+                    .iter()
+                    .filter(|&&(_, slot)| self.config.slots[slot].minutes >= length)
+                    .count()
+            })
+            .collect::<Vec<_>>();
+
+        let mut speakers = HashMap::new();
+        let mut candidates = manuscripts
+            .iter()
+            .filter(|(id, _)| self.talks.iter().all(|t| t.id != **id))
+            .filter_map(|(id, manuscript)| {
+                let speaker = speaker_key(manuscript);
+                if pinned_speakers.contains(&speaker) {
+                    return None;
+                }
+                let minutes = self.minutes(manuscript);
+                let fit = lengths.iter().position(|&length| length >= minutes)?;
+                let next = speakers.len();
+                Some(Candidate {
+                    id: *id,
+                    score: *scores.get(id)?,
+                    speaker: *speakers.entry(speaker).or_insert(next),
+                    quotas: self
+                        .config
+                        .quotas
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, q)| q.category.matches(manuscript))
+                        .map(|(i, _)| i)
+                        .collect(),
+                    fit,
+                })
+            })
+            .collect::<Vec<_>>();
+        // stable sort: the manuscripts with the same score keep their order.
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        let mut search = Search::new(&candidates, speakers.len(), &quotas, &capacity);
+        search.visit(&mut vec![None; candidates.len()]);
+
+        // the chosen talks always fit: a talk taking the shortest free slot
+        // never keeps a longer talk out.
+        for i in search.best.1 {
+            let minutes = self.minutes(&manuscripts[&candidates[i].id]);
+            let Some((room, slot)) = cells
+                .iter()
+                .copied()
+                .filter(|&(room, slot)| self.at(room, slot).is_none())
+                .find(|&(_, slot)| self.config.slots[slot].minutes >= minutes)
+            else {
+                continue;
+            };
+
+            self.talks.push(ScheduledTalk {
+                id: candidates[i].id,
+                room,
+                slot,
+                pinned: false,
+            });
+        }
+
+        self.sort();
+    }
+
+    /// The broken constraints, such as those broken by editing.
+    pub fn violations(&self, manuscripts: &ManuscriptDatabase) -> Vec<Violation> {
+        let talks = self
+            .talks
+            .iter()
+            .filter_map(|t| Some((t, manuscripts.get(&t.id)?)))
+            .collect::<Vec<_>>();
+        let mut violations = Vec::new();
+
+        let mut speakers = HashSet::new();
+        for (_, manuscript) in &talks {
+            if !speakers.insert(speaker_key(manuscript)) {
+                violations.push(Violation::SpeakerTwice {
+                    speaker: manuscript.author.name.clone(),
+                });
+            }
+        }
+
+        for (talk, manuscript) in &talks {
+            let minutes = self.minutes(manuscript);
+            if self
+                .config
+                .slots
+                .get(talk.slot)
+                .is_some_and(|s| s.minutes < minutes)
+            {
+                violations.push(Violation::TooLong {
+                    id: talk.id,
+                    minutes,
+                });
+            }
+        }
+
+        for quota in &self.config.quotas {
+            let count = talks
+                .iter()
+                .filter(|(_, m)| quota.category.matches(m))
+                .count();
+            if count > quota.max {
+                violations.push(Violation::OverQuota {
+                    category: quota.category.clone(),
+                    count,
+                });
+            }
+        }
+
+        violations
+    }
+
+    /// Write as CSV, with a row per talk in the order of the time.
+    pub fn to_csv(&self, manuscripts: &ManuscriptDatabase) -> Result<String, Error> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        writer.write_record(["Start", "End", "Room", "Title", "Speaker", "Type"])?;
+        for (talk, manuscript, start, end) in self.timed_talks(manuscripts)? {
+            writer.write_record([
+                format_time(start).as_str(),
+                format_time(end).as_str(),
+                self.config.rooms[talk.room].as_str(),
+                manuscript.title.as_str(),
+                manuscript.author.name.as_str(),
+                manuscript.type_.as_str(),
+            ])?;
+        }
+
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| csv::Error::from(e.into_error()))?;
+        // Only strings have been written.
+        Ok(String::from_utf8(bytes).expect("valid UTF-8"))
+    }
+
+    /// Write as iCalendar (RFC 5545), an event per talk in the local time.
+    pub fn to_ics(
+        &self,
+        manuscripts: &ManuscriptDatabase,
+        now: Timestamp,
+    ) -> Result<String, Error> {
+        let date = parse_date(&self.config.date)?;
+        let stamp = now.to_date_time();
+
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_owned(),
+            "VERSION:2.0".to_owned(),
+            "PRODID:-//review-tool//program//EN".to_owned(),
+        ];
+        for (talk, manuscript, start, end) in self.timed_talks(manuscripts)? {
+            let uid = talk.id.0.iter().fold(String::new(), |mut uid, byte| {
+                let _ = write!(uid, "{byte:02x}");
+                uid
+            });
+
+            lines.extend([
+                "BEGIN:VEVENT".to_owned(),
+                format!("UID:{uid}@review-tool"),
+                format!(
+                    "DTSTAMP:{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+                    stamp.year, stamp.month, stamp.day, stamp.hour, stamp.minute, stamp.second
+                ),
+                format!("DTSTART:{date}T{:02}{:02}00", start / 60, start % 60),
+                format!("DTEND:{date}T{:02}{:02}00", end / 60, end % 60),
+                format!("SUMMARY:{}", escape_text(&manuscript.title)),
+                format!("LOCATION:{}", escape_text(&self.config.rooms[talk.room])),
+                format!(
+                    "DESCRIPTION:{}",
+                    escape_text(&format!("{}\n{}", manuscript.author.name, manuscript.type_))
+                ),
+                "END:VEVENT".to_owned(),
+            ]);
+        }
+        lines.push("END:VCALENDAR".to_owned());
+
+        Ok(lines.iter().map(|l| fold_line(l)).collect())
+    }
+
+    /// The talks with their start and end times, in the order of the time.
+    fn timed_talks<'a>(
+        &'a self,
+        manuscripts: &'a ManuscriptDatabase,
+    ) -> Result<Vec<(&'a ScheduledTalk, &'a Manuscript, u32, u32)>, Error> {
+        let mut talks = Vec::new();
+
+        for talk in &self.talks {
+            let (Some(manuscript), Some(slot)) =
+                (manuscripts.get(&talk.id), self.config.slots.get(talk.slot))
+            else {
+                continue;
+            };
+            if talk.room >= self.config.rooms.len() {
+                continue;
+            }
+
+            let start = slot.start_minute()?;
+            talks.push((
+                talk,
+                manuscript.as_ref(),
+                start,
+                start + self.minutes(manuscript),
+            ));
+        }
+        talks.sort_by_key(|(talk, _, start, _)| (*start, talk.room));
+
+        Ok(talks)
+    }
+
+    /// Sort the talks by the slot and the room.
+    fn sort(&mut self) {
+        self.talks.sort_by_key(|t| (t.slot, t.room));
+    }
+}
+
+/// A scored manuscript that may be placed by [`Program::fill`].
+struct Candidate {
+    id: ManuscriptId,
+    score: f64,
+    /// The index of the speaker.
+    speaker: usize,
+    /// The indices of the quotas it counts toward.
+    quotas: Vec<usize>,
+    /// The index of the shortest slot length it fits in.
+    fit: usize,
+}
+
+/// The branch-and-bound search for the candidates of the highest total score.
+///
+/// Every constraint is a row: at most one talk of a speaker, at most the
+/// places left in a quota, and, since a talk fits every slot at least as long
+/// as the shortest one it fits in, for every slot length at most as many
+/// talks needing that length or more as the free slots that long. The bound
+/// is the linear relaxation of the rows, solved by [`simplex`].
+struct Search {
+    scores: Vec<f64>,
+    /// Whether the candidate is in the row, and the limit of the row.
+    rows: Vec<(Vec<bool>, f64)>,
+    best: (f64, Vec<usize>),
+}
+
+impl Search {
+    /// The scores closer than this are the same.
+    const EPSILON: f64 = 1e-9;
+
+    fn new(
+        candidates: &[Candidate],
+        speakers: usize,
+        quotas: &[usize],
+        capacity: &[usize],
+    ) -> Self {
+        let row = |holds: &dyn Fn(&Candidate) -> bool, limit: usize| {
+            (candidates.iter().map(holds).collect(), limit as f64)
+        };
+        let rows = (0..speakers)
+            .map(|s| row(&|c| c.speaker == s, 1))
+            .chain(
+                quotas
+                    .iter()
+                    .enumerate()
+                    .map(|(q, &left)| row(&|c| c.quotas.contains(&q), left)),
+            )
+            .chain(
+                capacity
+                    .iter()
+                    .enumerate()
+                    .map(|(l, &free)| row(&|c| c.fit >= l, free)),
+            )
+            .collect();
+
+        Self {
+            scores: candidates.iter().map(|c| c.score).collect(),
+            rows,
+            best: (0.0, Vec::new()),
+        }
+    }
+
+    /// Search the candidates not `fixed` to be taken or left out.
+    fn visit(&mut self, fixed: &mut Vec<Option<bool>>) {
+        let free = (0..fixed.len())
+            .filter(|&i| fixed[i].is_none())
+            .collect::<Vec<_>>();
+        let taken = (0..fixed.len()).filter(|&i| fixed[i] == Some(true));
+
+        let score = taken.clone().map(|i| self.scores[i]).sum::<f64>();
+        let limits = self
+            .rows
+            .iter()
+            .map(|(holds, limit)| limit - taken.clone().filter(|&i| holds[i]).count() as f64)
+            .collect::<Vec<_>>();
+        if limits.iter().any(|&limit| limit < 0.0) {
+            return;
+        }
+        let rows = self
+            .rows
+            .iter()
+            .map(|(holds, _)| {
+                free.iter()
+                    .map(|&i| if holds[i] { 1.0 } else { 0.0 })
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+        let costs = free.iter().map(|&i| self.scores[i]).collect::<Vec<_>>();
+
+        let (bound, x) = simplex(&rows, &limits, &costs);
+        if score + bound <= self.best.0 + Self::EPSILON {
+            return;
+        }
+
+        // branch on the first fractional candidate, the highest scored.
+        match x
+            .iter()
+            .position(|&x| x > Self::EPSILON && x < 1.0 - Self::EPSILON)
+        {
+            Some(k) => {
+                for take in [true, false] {
+                    fixed[free[k]] = Some(take);
+                    self.visit(fixed);
+                }
+                fixed[free[k]] = None;
+            }
+            None => {
+                let chosen = (0..fixed.len()).filter(|&i| fixed[i] == Some(true)).chain(
+                    free.iter()
+                        .zip(&x)
+                        .filter(|(_, &x)| x > 0.5)
+                        .map(|(&i, _)| i),
+                );
+                let mut chosen = chosen.collect::<Vec<_>>();
+                chosen.sort_unstable();
+                self.best = (score + bound, chosen);
+            }
+        }
+    }
+}
+
+/// Maximize `costs · x` subject to `rows · x <= limits` and `x >= 0`, with
+/// `limits >= 0` and the `x` bounded by the rows. Returns the maximum and `x`.
+///
+/// It is the tableau simplex method with Bland's rule, which never cycles.
+fn simplex(rows: &[Vec<f64>], limits: &[f64], costs: &[f64]) -> (f64, Vec<f64>) {
+    const EPSILON: f64 = 1e-9;
+    let (m, n) = (rows.len(), costs.len());
+    let last = n + m;
+
+    // the slack variables start as the basis.
+    let mut tableau = rows
+        .iter()
+        .zip(limits)
+        .enumerate()
+        .map(|(i, (row, &limit))| {
+            let mut line = row.clone();
+            line.extend((0..m).map(|k| if k == i { 1.0 } else { 0.0 }));
+            line.push(limit);
+            line
+        })
+        .collect::<Vec<_>>();
+    let mut objective = costs.iter().map(|c| -c).collect::<Vec<_>>();
+    objective.resize(last + 1, 0.0);
+    let mut basis = (n..last).collect::<Vec<_>>();
+
+    while let Some(enter) = (0..last).find(|&j| objective[j] < -EPSILON) {
+        let Some(leave) = (0..m)
+            .filter(|&i| tableau[i][enter] > EPSILON)
+            .min_by(|&i, &k| {
+                let ratio = |i: usize| tableau[i][last] / tableau[i][enter];
+                ratio(i).total_cmp(&ratio(k)).then(basis[i].cmp(&basis[k]))
+            })
+        else {
+            break;
+        };
+
+        let pivot = tableau[leave][enter];
+        for value in &mut tableau[leave] {
+            *value /= pivot;
+        }
+        let line = tableau[leave].clone();
+        let others = tableau.iter_mut().enumerate().filter(|(i, _)| *i != leave);
+        for row in others.map(|(_, row)| row).chain([&mut objective]) {
+            let factor = row[enter];
+            if factor != 0.0 {
+                for (value, l) in row.iter_mut().zip(&line) {
+                    *value -= factor * l;
+                }
+            }
+        }
+        basis[leave] = enter;
+    }
+
+    let mut x = vec![0.0; n];
+    for (i, &j) in basis.iter().enumerate() {
+        if j < n {
+            x[j] = tableau[i][last];
+        }
+    }
+    (objective[last], x)
+}
+
+/// The speaker, compared case-insensitively.
+fn speaker_key(manuscript: &Manuscript) -> String {
+    manuscript.author.name.trim().to_lowercase()
+}
+
+fn format_time(minute: u32) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+/// Parse `YYYY-MM-DD` as the iCalendar date `YYYYMMDD`.
+fn parse_date(date: &str) -> Result<String, Error> {
+    let parts = date.trim().split('-').collect::<Vec<_>>();
+
+    match parts.as_slice() {
+        [year, month, day]
+            if year.len() == 4
+                && month.len() == 2
+                && day.len() == 2
+                && parts.iter().all(|p| p.chars().all(|c| c.is_ascii_digit())) =>
+        {
+            Ok(format!("{year}{month}{day}"))
+        }
+        _ => Err(Error::InvalidDate(date.to_owned())),
+    }
+}
+
+/// Escape the iCalendar text value.
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold the content line at 75 octets, without splitting a character.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded += "\r\n ";
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded += "\r\n";
+
+    folded
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("invalid time `{0}`, expected HH:MM")]
+    InvalidTime(String),
+
+    #[error("invalid date `{0}`, expected YYYY-MM-DD")]
+    InvalidDate(String),
+
+    #[error("failed to write CSV: {0}")]
+    Csv(#[from] csv::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{parse_minutes, Category, Program, ProgramConfig, Quota, Slot, Violation};
+    use crate::types::{timestamp::Timestamp, Manuscript, ManuscriptDatabase};
+
+    fn manuscripts(talks: &[(&str, &str, &str)]) -> ManuscriptDatabase {
+        talks
+            .iter()
+            .map(|(title, speaker, type_)| Manuscript::fixture(title, speaker, type_))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    #[test]
+    fn test_parse_minutes() {
+        assert_eq!(parse_minutes("一般議程（40 分鐘）"), Some(40));
+        assert_eq!(parse_minutes("Lightning talk 2024 (5 min)"), Some(5));
+        assert_eq!(parse_minutes("Talk"), None);
+    }
+
+    #[test]
+    fn test_fill() {
+        let manuscripts = manuscripts(&[
+            ("A", "Alice", "一般議程（40 分鐘）"),
+            ("B", "alice", "一般議程（40 分鐘）"),
+            ("C", "Bob", "閃電講（5 分鐘）"),
+            ("D", "Carol", "閃電講（5 分鐘）"),
+            ("E", "Dave", "一般議程（40 分鐘）"),
+            ("F", "Eve", "一般議程（40 分鐘）"),
+        ]);
+        let ids = manuscripts.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        let scores = ids
+            .iter()
+            .zip([9.0, 8.0, 7.0, 6.0, 5.0, 1.0])
+            .map(|(id, score)| (*id, score))
+            .collect::<HashMap<_, _>>();
+
+        let mut program = Program {
+            config: ProgramConfig {
+                date: "2026-08-01".into(),
+                rooms: vec!["R0".into(), "R1".into()],
+                slots: vec![
+                    Slot {
+                        start: "09:00".into(),
+                        minutes: 40,
+                    },
+                    Slot {
+                        start: "10:00".into(),
+                        minutes: 10,
+                    },
+                ],
+                default_minutes: 40,
+                quotas: vec![Quota {
+                    category: Category::Type("閃電講".into()),
+                    max: 1,
+                }],
+            },
+            talks: Vec::new(),
+        };
+        // Eve is pinned, despite the lowest score.
+        program.place(ids[5], 1, 0);
+        program.fill(&manuscripts, &scores);
+
+        // B: the same speaker as A; D: over the quota; E: no 40-minute slot left.
+        let placed = program.talks.iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(placed, [ids[0], ids[5], ids[2]]);
+        // the lightning talk takes the short slot.
+        assert_eq!(program.at(0, 1).map(|t| t.id), Some(ids[2]));
+        assert!(program.violations(&manuscripts).is_empty());
+
+        program.place(ids[1], 0, 1);
+        assert_eq!(
+            program.violations(&manuscripts),
+            [
+                Violation::SpeakerTwice {
+                    speaker: "alice".into()
+                },
+                Violation::TooLong {
+                    id: ids[1],
+                    minutes: 40
+                },
+            ]
+        );
+
+        let ics = program.to_ics(&manuscripts, Timestamp(0)).unwrap();
+        assert!(ics.contains("DTSTART:20260801T090000\r\n"));
+        assert!(ics.contains("DTEND:20260801T094000\r\n"));
+        assert!(ics.contains("DESCRIPTION:Alice\\n一般議程（40 分鐘）\r\n"));
+
+        let csv = program.to_csv(&manuscripts).unwrap();
+        assert!(csv.starts_with("Start,End,Room,Title,Speaker,Type\n09:00,09:40,R0,A,Alice,"));
+    }
+
+    #[test]
+    fn test_fill_beats_greedy() {
+        let manuscripts = manuscripts(&[
+            ("X", "Alice", "一般議程（40 分鐘）"),
+            ("Y", "Alice", "閃電講（5 分鐘）"),
+            ("Z", "Bob", "一般議程（40 分鐘）"),
+        ]);
+        let ids = manuscripts.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        let scores = ids
+            .iter()
+            .zip([10.0, 9.0, 8.0])
+            .map(|(id, score)| (*id, score))
+            .collect::<HashMap<_, _>>();
+
+        let mut program = Program {
+            config: ProgramConfig {
+                date: "2026-08-01".into(),
+                rooms: vec!["R0".into()],
+                slots: vec![
+                    Slot {
+                        start: "09:00".into(),
+                        minutes: 40,
+                    },
+                    Slot {
+                        start: "10:00".into(),
+                        minutes: 10,
+                    },
+                ],
+                default_minutes: 40,
+                quotas: Vec::new(),
+            },
+            talks: Vec::new(),
+        };
+        program.fill(&manuscripts, &scores);
+
+        // greedily, X takes Alice's only talk and the only 40-minute slot,
+        // scoring 10; Y and Z together score 17.
+        let placed = program.talks.iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(placed, [ids[2], ids[1]]);
+        assert!(program.violations(&manuscripts).is_empty());
+    }
+}
@@ -5,14 +5,35 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+use self::versioned::Versioned;
 use super::ManuscriptId;
 
+pub mod aggregate;
+pub mod agreement;
+mod breakdown;
+pub mod changelog;
+pub mod crdt;
+pub mod dashboard;
+pub mod dump;
+pub mod export;
+pub mod filter;
+pub mod leaderboard;
+pub mod registry;
+pub mod sheet;
 pub mod sitcon_gdsc;
+pub mod snapshot;
+pub mod spreadsheet;
+pub mod sync;
+pub mod triage;
+pub mod undo;
+pub mod versioned;
+
+pub use breakdown::{ScoreBreakdown, ScoreRow};
 
 /// A rank item.
-pub trait Item: Sized {
+pub trait Item {
     /// The item name.
     fn name(&self) -> &str;
 
@@ -43,8 +64,8 @@ pub trait ItemGroup {
     /// The score of this group.
     fn score(&self) -> f64;
 
-    /// The description of the score.
-    fn score_description(&self) -> Option<String>;
+    /// The itemized explanation of the score.
+    fn score_breakdown(&self) -> Option<ScoreBreakdown>;
 }
 
 pub trait CommentableItemGroup: ItemGroup {
@@ -55,6 +76,15 @@ pub trait CommentableItemGroup: ItemGroup {
     fn comment_mut(&mut self) -> &mut String;
 }
 
+/// A group whose items can be enumerated.
+pub trait IterableItemGroup: CommentableItemGroup {
+    /// The items in this group, with their serialized keys.
+    fn items(&self) -> Vec<(&'static str, &dyn Item)>;
+
+    /// The mutable items in this group, with their serialized keys.
+    fn items_mut(&mut self) -> Vec<(&'static str, &mut dyn Item)>;
+}
+
 /// A group of [`ItemGroup`] – we called it *meta*.
 pub trait MetaGroup {
     /// Is all of the items in this meta group reviewed?
@@ -66,6 +96,33 @@ pub trait MutableMetaGroup: MetaGroup {
     fn reviewed_mut(&mut self) -> &mut bool;
 }
 
+/// A meta group whose groups can be enumerated.
+pub trait IterableMetaGroup: MetaGroup {
+    /// The groups in this meta group, with their serialized keys.
+    fn groups(&self) -> Vec<(&'static str, &dyn IterableItemGroup)>;
+
+    /// The mutable groups in this meta group, with their serialized keys.
+    fn groups_mut(&mut self) -> Vec<(&'static str, &mut dyn IterableItemGroup)>;
+
+    /// The total score of all the groups.
+    fn total_score(&self) -> f64 {
+        self.groups().iter().map(|(_, g)| g.score()).sum()
+    }
+}
+
+/// A rubric – the meta group to fill for every manuscript.
+///
+/// Register the rubric in [`registry`] to make it selectable.
+pub trait Rubric:
+    MutableMetaGroup + IterableMetaGroup + Versioned + Default + Serialize + DeserializeOwned + 'static
+{
+    /// The unique ID of this rubric. The reviews are stored under this ID.
+    const ID: &'static str;
+
+    /// The human-readable name of this rubric.
+    const NAME: &'static str;
+}
+
 // No meta group.
 impl MetaGroup for () {
     fn reviewed(&self) -> bool {
@@ -141,6 +198,24 @@ impl AsRef<str> for StandardChoice {
 }
 
 impl StandardChoice {
+    /// All the choices, in the order of [`StandardChoice::ordinal`].
+    pub const ALL: [StandardChoice; 4] = [
+        StandardChoice::No,
+        StandardChoice::Maybe,
+        StandardChoice::Partial,
+        StandardChoice::Full,
+    ];
+
+    /// The ordinal value of this choice: *No* (0) < *Maybe* < *Partial* < *Full* (3).
+    pub fn ordinal(&self) -> usize {
+        match self {
+            StandardChoice::No => 0,
+            StandardChoice::Maybe => 1,
+            StandardChoice::Partial => 2,
+            StandardChoice::Full => 3,
+        }
+    }
+
     /// Show choice as emoji.
     pub fn as_emoji(&self) -> &'static str {
         match self {
@@ -0,0 +1,307 @@
+//! The aggregation of the reviews of several reviewers.
+
+use std::fmt::Write;
+
+use super::sheet::ReviewerSheets;
+use crate::types::{ManuscriptDatabase, ManuscriptId};
+
+/// The summary statistics of some scores.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Stats {
+    /// The number of the scores.
+    pub count: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub min: f64,
+    pub max: f64,
+
+    /// The sample standard deviation. It is 0 for a single score.
+    pub std_dev: f64,
+}
+
+impl Stats {
+    /// Summarize the scores. Return `None` if there is no score.
+    pub fn of(scores: impl IntoIterator<Item = f64>) -> Option<Self> {
+        let mut scores = scores.into_iter().collect::<Vec<_>>();
+        if scores.is_empty() {
+            return None;
+        }
+        scores.sort_by(f64::total_cmp);
+
+        let count = scores.len();
+        let mean = scores.iter().sum::<f64>() / count as f64;
+        let median = if count % 2 == 0 {
+            (scores[count / 2 - 1] + scores[count / 2]) / 2.0
+        } else {
+            scores[count / 2]
+        };
+        let std_dev = if count > 1 {
+            let variance =
+                scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (count - 1) as f64;
+            variance.sqrt()
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            count,
+            mean,
+            median,
+            min: scores[0],
+            max: scores[count - 1],
+            std_dev,
+        })
+    }
+}
+
+/// The aggregated scores of a group.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GroupAggregate {
+    /// The serialized key of the group.
+    pub key: &'static str,
+    pub name: String,
+    pub stats: Option<Stats>,
+}
+
+/// The aggregated reviews of a manuscript.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ManuscriptAggregate {
+    pub id: ManuscriptId,
+
+    /// The statistics of the total scores.
+    pub total: Option<Stats>,
+
+    pub groups: Vec<GroupAggregate>,
+
+    /// The reviewers who have not reviewed this manuscript.
+    pub missing: Vec<String>,
+}
+
+impl ManuscriptAggregate {
+    /// The number of the reviewers who have reviewed this manuscript.
+    pub fn reviewers(&self) -> usize {
+        self.total.map_or(0, |s| s.count)
+    }
+}
+
+/// The aggregated reviews of all the manuscripts.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Aggregation {
+    /// The names of the reviewers.
+    pub reviewers: Vec<String>,
+
+    /// The manuscripts, ranked by the mean total score.
+    ///
+    /// The manuscripts no one reviewed are put last.
+    pub manuscripts: Vec<ManuscriptAggregate>,
+}
+
+impl Aggregation {
+    /// Aggregate the reviewed reviews of the manuscripts in `manuscripts`.
+    pub fn new(manuscripts: &ManuscriptDatabase, sheets: &[ReviewerSheets]) -> Self {
+        // the groups, in the order they appear.
+        let mut groups: Vec<(&'static str, &str)> = Vec::new();
+        for review in sheets.iter().flat_map(|s| s.reviews.values()) {
+            for group in &review.groups {
+                if !groups.iter().any(|(key, _)| *key == group.key) {
+                    groups.push((group.key, &group.name));
+                }
+            }
+        }
+
+        let mut aggregates = manuscripts
+            .iter()
+            .map(|(id, _)| {
+                let reviews = sheets
+                    .iter()
+                    .filter_map(|s| s.reviewed(id))
+                    .collect::<Vec<_>>();
+
+                ManuscriptAggregate {
+                    id: *id,
+                    total: Stats::of(reviews.iter().map(|r| r.total)),
+                    groups: groups
+                        .iter()
+                        .map(|&(key, name)| GroupAggregate {
+                            key,
+                            name: name.to_owned(),
+                            stats: Stats::of(
+                                reviews.iter().filter_map(|r| r.group(key)).map(|g| g.score),
+                            ),
+                        })
+                        .collect(),
+                    missing: sheets
+                        .iter()
+                        .filter(|s| s.reviewed(id).is_none())
+                        .map(|s| s.reviewer.clone())
+                        .collect(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // `sort_by` is stable, so the ties keep the order of the manuscripts.
+        aggregates.sort_by(|a, b| match (a.total, b.total) {
+            (Some(a), Some(b)) => b.mean.total_cmp(&a.mean),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+
+        Self {
+            reviewers: sheets.iter().map(|s| s.reviewer.clone()).collect(),
+            manuscripts: aggregates,
+        }
+    }
+
+    /// The aggregate of the manuscript.
+    pub fn get(&self, id: &ManuscriptId) -> Option<&ManuscriptAggregate> {
+        self.manuscripts.iter().find(|m| m.id == *id)
+    }
+
+    /// Render the ranked table as plain text, followed by the warnings.
+    pub fn to_table(&self, manuscripts: &ManuscriptDatabase) -> String {
+        let mut table = String::new();
+        let n = self.reviewers.len();
+
+        // writing to a String never fails.
+        let _ = write!(
+            table,
+            "{:>4} {:>9} {:>6} {:>6} {:>6} {:>6} {:>6}",
+            "#", "Reviewers", "Mean", "Median", "Min", "Max", "SD"
+        );
+        if let Some(first) = self.manuscripts.first() {
+            for group in &first.groups {
+                let _ = write!(table, " {:>14}", group.key);
+            }
+        }
+        let _ = writeln!(table, "  Title");
+
+        for (i, aggregate) in self.manuscripts.iter().enumerate() {
+            let title = manuscripts
+                .get(&aggregate.id)
+                .map_or("", |m| m.title.as_str());
+
+            let _ = write!(
+                table,
+                "{:>4} {:>9}",
+                i + 1,
+                format!("{}/{n}", aggregate.reviewers())
+            );
+            match aggregate.total {
+                Some(s) => {
+                    let _ = write!(
+                        table,
+                        " {:>6.2} {:>6.2} {:>6.2} {:>6.2} {:>6.2}",
+                        s.mean, s.median, s.min, s.max, s.std_dev
+                    );
+                }
+                None => {
+                    let _ = write!(
+                        table,
+                        " {:>6} {:>6} {:>6} {:>6} {:>6}",
+                        "-", "-", "-", "-", "-"
+                    );
+                }
+            }
+            for group in &aggregate.groups {
+                let cell = group.stats.map_or_else(
+                    || "-".to_owned(),
+                    |s| format!("{:.2} ± {:.2}", s.mean, s.std_dev),
+                );
+                let _ = write!(table, " {cell:>14}");
+            }
+            let _ = writeln!(table, "  {title}");
+        }
+
+        let warnings = self.warnings(manuscripts);
+        if !warnings.is_empty() {
+            let _ = writeln!(table);
+            for warning in warnings {
+                let _ = writeln!(table, "⚠ {warning}");
+            }
+        }
+
+        table
+    }
+
+    /// The warnings of the missing reviews.
+    pub fn warnings(&self, manuscripts: &ManuscriptDatabase) -> Vec<String> {
+        self.manuscripts
+            .iter()
+            .filter(|m| !m.missing.is_empty())
+            .map(|m| {
+                let title = manuscripts.get(&m.id).map_or("", |m| m.title.as_str());
+
+                format!("{title}: not reviewed by {}", m.missing.join(", "))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{Aggregation, Stats};
+    use crate::types::{
+        rank::{
+            sheet::{ReviewSheet, ReviewerSheets},
+            sitcon_gdsc, MutableMetaGroup,
+        },
+        timestamp::Timestamp,
+        Manuscript, ManuscriptDatabase,
+    };
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::of([2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+
+        assert_eq!(stats.count, 8);
+        assert_eq!(stats.mean, 5.0);
+        assert_eq!(stats.median, 4.5);
+        assert_eq!((stats.min, stats.max), (2.0, 9.0));
+        assert!((stats.std_dev - 2.138).abs() < 1e-3);
+
+        assert_eq!(Stats::of([3.0]).unwrap().std_dev, 0.0);
+        assert!(Stats::of([]).is_none());
+    }
+
+    #[test]
+    fn test_aggregation() {
+        let manuscripts: ManuscriptDatabase = ["A", "B"]
+            .into_iter()
+            .map(|title| Manuscript::fixture(title, title, ""))
+            .collect::<Vec<_>>()
+            .into();
+        let ids = manuscripts.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+
+        let mut reviewed = sitcon_gdsc::Group::default();
+        *reviewed.reviewed_mut() = true;
+
+        // Alice reviewed B only; Bob reviewed both.
+        let sheets =
+            [("Alice", &ids[1..]), ("Bob", &ids[..])].map(|(reviewer, ids)| ReviewerSheets {
+                reviewer: reviewer.into(),
+                rubric: "sitcon-gdsc".into(),
+                exported_at: Timestamp(0),
+                reviews: ids
+                    .iter()
+                    .map(|id| (*id, ReviewSheet::of(&reviewed)))
+                    .collect::<HashMap<_, _>>(),
+            });
+
+        let aggregation = Aggregation::new(&manuscripts, &sheets);
+        let (first, second) = (&aggregation.manuscripts[0], &aggregation.manuscripts[1]);
+
+        assert_eq!(first.id, ids[0]);
+        assert_eq!(first.reviewers(), 1);
+        assert_eq!(first.missing, ["Alice"]);
+        assert_eq!(second.reviewers(), 2);
+        assert_eq!(second.groups.len(), 3);
+        assert_eq!(second.total.unwrap().mean, 30.0);
+        assert_eq!(
+            aggregation.warnings(&manuscripts),
+            ["A: not reviewed by Alice"]
+        );
+    }
+}
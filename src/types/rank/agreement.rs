@@ -0,0 +1,385 @@
+//! The inter-rater agreement of the rank items.
+//!
+//! The choices are treated as ordinal: *No* < *Maybe* < *Partial* < *Full*.
+//! An item the reviewers agree poorly on is probably ambiguous, and its
+//! guideline should be clarified.
+
+use super::sheet::ReviewerSheets;
+use crate::types::ManuscriptId;
+
+/// The number of the categories of [`super::StandardChoice`].
+///
+/// See [`super::StandardChoice::ordinal`].
+const CATEGORIES: usize = 4;
+
+/// Krippendorff's alpha with the ordinal metric.
+///
+/// `units` are the ordinal values each unit (manuscript) received.
+/// The units with less than 2 values are not pairable and ignored.
+/// Return `None` if there is no variation to measure.
+pub fn krippendorff_alpha(units: &[Vec<usize>]) -> Option<f64> {
+    // the coincidence matrix.
+    let mut o = [[0.0; CATEGORIES]; CATEGORIES];
+    for values in units.iter().filter(|v| v.len() >= 2) {
+        let weight = 1.0 / (values.len() - 1) as f64;
+
+        for (i, &c) in values.iter().enumerate() {
+            for (j, &k) in values.iter().enumerate() {
+                if i != j {
+                    o[c][k] += weight;
+                }
+            }
+        }
+    }
+
+    let n_c = o.map(|row| row.iter().sum::<f64>());
+    let n = n_c.iter().sum::<f64>();
+    if n <= 1.0 {
+        return None;
+    }
+
+    let delta = |c: usize, k: usize| {
+        let (low, high) = (c.min(k), c.max(k));
+        let between = n_c[low..=high].iter().sum::<f64>() - (n_c[c] + n_c[k]) / 2.0;
+        between.powi(2)
+    };
+
+    let mut observed = 0.0;
+    let mut expected = 0.0;
+    for c in 0..CATEGORIES {
+        for k in 0..CATEGORIES {
+            observed += o[c][k] * delta(c, k);
+            expected += n_c[c] * n_c[k] * delta(c, k);
+        }
+    }
+
+    if expected == 0.0 {
+        return None;
+    }
+    Some(1.0 - (n - 1.0) * observed / expected)
+}
+
+/// Cohen's kappa with the quadratic weights.
+///
+/// `pairs` are the ordinal values the two reviewers gave to the same units.
+/// Return `None` if there is no variation to measure.
+pub fn weighted_kappa(pairs: &[(usize, usize)]) -> Option<f64> {
+    if pairs.is_empty() {
+        return None;
+    }
+
+    let total = pairs.len() as f64;
+    let mut observed = [[0.0; CATEGORIES]; CATEGORIES];
+    let mut rows = [0.0; CATEGORIES];
+    let mut columns = [0.0; CATEGORIES];
+    for &(a, b) in pairs {
+        observed[a][b] += 1.0 / total;
+        rows[a] += 1.0 / total;
+        columns[b] += 1.0 / total;
+    }
+
+    let weight =
+        |i: usize, j: usize| (i as f64 - j as f64).powi(2) / ((CATEGORIES - 1) as f64).powi(2);
+
+    let mut disagreement = 0.0;
+    let mut chance = 0.0;
+    for i in 0..CATEGORIES {
+        for j in 0..CATEGORIES {
+            disagreement += weight(i, j) * observed[i][j];
+            chance += weight(i, j) * rows[i] * columns[j];
+        }
+    }
+
+    if chance == 0.0 {
+        return None;
+    }
+    Some(1.0 - disagreement / chance)
+}
+
+/// How reliable an alpha is, after Krippendorff's recommendation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Reliability {
+    /// α ≥ 0.8
+    Reliable,
+
+    /// 0.667 ≤ α < 0.8
+    Tentative,
+
+    /// α < 0.667
+    Unreliable,
+}
+
+impl Reliability {
+    pub fn of(alpha: f64) -> Self {
+        if alpha >= 0.8 {
+            Self::Reliable
+        } else if alpha >= 0.667 {
+            Self::Tentative
+        } else {
+            Self::Unreliable
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reliability::Reliable => "reliable",
+            Reliability::Tentative => "tentative",
+            Reliability::Unreliable => "unreliable",
+        }
+    }
+}
+
+/// The weighted kappa between two reviewers.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PairKappa {
+    /// The indices of the reviewers in [`AgreementReport::reviewers`].
+    pub reviewers: (usize, usize),
+
+    pub kappa: Option<f64>,
+
+    /// The number of the manuscripts both reviewed.
+    pub units: usize,
+}
+
+/// The agreement of a rank item.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ItemAgreement {
+    pub group_key: &'static str,
+    pub group_name: String,
+    pub item_key: &'static str,
+    pub item_name: String,
+
+    /// Krippendorff's alpha of all the reviewers.
+    pub alpha: Option<f64>,
+
+    /// The number of the manuscripts reviewed by at least 2 reviewers.
+    pub units: usize,
+
+    /// The kappas of every pair of the reviewers.
+    pub kappas: Vec<PairKappa>,
+}
+
+impl ItemAgreement {
+    /// The mean of the defined kappas.
+    pub fn mean_kappa(&self) -> Option<f64> {
+        let kappas = self
+            .kappas
+            .iter()
+            .filter_map(|k| k.kappa)
+            .collect::<Vec<_>>();
+
+        (!kappas.is_empty()).then(|| kappas.iter().sum::<f64>() / kappas.len() as f64)
+    }
+}
+
+/// The agreement of every rank item.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AgreementReport {
+    pub reviewers: Vec<String>,
+
+    /// The items, in the order of the rubric.
+    pub items: Vec<ItemAgreement>,
+}
+
+impl AgreementReport {
+    /// Measure the agreement of the reviewed reviews.
+    pub fn new(sheets: &[ReviewerSheets]) -> Self {
+        // the items, in the order they appear.
+        let mut items: Vec<ItemAgreement> = Vec::new();
+        for review in sheets.iter().flat_map(|s| s.reviews.values()) {
+            for group in &review.groups {
+                for item in &group.items {
+                    if !items
+                        .iter()
+                        .any(|i| i.group_key == group.key && i.item_key == item.key)
+                    {
+                        items.push(ItemAgreement {
+                            group_key: group.key,
+                            group_name: group.name.clone(),
+                            item_key: item.key,
+                            item_name: item.name.clone(),
+                            alpha: None,
+                            units: 0,
+                            kappas: Vec::new(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut ids = sheets
+            .iter()
+            .flat_map(|s| s.reviews.keys().copied())
+            .collect::<Vec<_>>();
+        ids.sort_unstable_by_key(|id| id.0);
+        ids.dedup();
+
+        for item in &mut items {
+            // the choice of every reviewer on every manuscript.
+            let choices = |id: &ManuscriptId| {
+                sheets
+                    .iter()
+                    .map(|s| {
+                        let review = s.reviewed(id)?;
+                        let group = review.group(item.group_key)?;
+                        let choice = group.items.iter().find(|i| i.key == item.item_key)?;
+
+                        Some(choice.choice.ordinal())
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let table = ids.iter().map(choices).collect::<Vec<_>>();
+
+            let units = table
+                .iter()
+                .map(|row| row.iter().flatten().copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            item.alpha = krippendorff_alpha(&units);
+            item.units = units.iter().filter(|u| u.len() >= 2).count();
+
+            for a in 0..sheets.len() {
+                for b in (a + 1)..sheets.len() {
+                    let pairs = table
+                        .iter()
+                        .filter_map(|row| Some((row[a]?, row[b]?)))
+                        .collect::<Vec<_>>();
+
+                    item.kappas.push(PairKappa {
+                        reviewers: (a, b),
+                        kappa: weighted_kappa(&pairs),
+                        units: pairs.len(),
+                    });
+                }
+            }
+        }
+
+        Self {
+            reviewers: sheets.iter().map(|s| s.reviewer.clone()).collect(),
+            items,
+        }
+    }
+
+    /// Export the report as CSV.
+    pub fn to_csv(&self) -> Result<String, csv::Error> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        let mut header = [
+            "group",
+            "item",
+            "alpha",
+            "reliability",
+            "units",
+            "mean kappa",
+        ]
+        .map(ToOwned::to_owned)
+        .to_vec();
+        if let Some(item) = self.items.first() {
+            header.extend(item.kappas.iter().map(|k| {
+                format!(
+                    "kappa {} / {}",
+                    self.reviewers[k.reviewers.0], self.reviewers[k.reviewers.1]
+                )
+            }));
+        }
+        writer.write_record(&header)?;
+
+        let format = |value: Option<f64>| value.map_or_else(String::new, |v| format!("{v:.3}"));
+        for item in &self.items {
+            let mut record = vec![
+                item.group_name.clone(),
+                item.item_name.clone(),
+                format(item.alpha),
+                item.alpha
+                    .map_or("", |alpha| Reliability::of(alpha).as_str())
+                    .to_owned(),
+                item.units.to_string(),
+                format(item.mean_kappa()),
+            ];
+            record.extend(item.kappas.iter().map(|k| format(k.kappa)));
+
+            writer.write_record(&record)?;
+        }
+
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| csv::Error::from(e.into_error()))?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{krippendorff_alpha, weighted_kappa, AgreementReport};
+    use crate::types::{
+        rank::{
+            sheet::{ReviewSheet, ReviewerSheets},
+            sitcon_gdsc, Item, MutableMetaGroup, StandardChoice,
+        },
+        timestamp::Timestamp,
+        ManuscriptId,
+    };
+
+    #[test]
+    fn test_krippendorff_alpha() {
+        let units = [vec![0, 0], vec![1, 1], vec![2, 2], vec![3, 2], vec![1]];
+        let alpha = krippendorff_alpha(&units).unwrap();
+
+        assert!((alpha - (1.0 - 56.0 / 624.0)).abs() < 1e-9);
+        assert_eq!(krippendorff_alpha(&[vec![0, 0], vec![1, 1]]), Some(1.0));
+        assert_eq!(krippendorff_alpha(&[vec![3, 3], vec![3, 3]]), None);
+    }
+
+    #[test]
+    fn test_weighted_kappa() {
+        let pairs = [(0, 0), (1, 1), (2, 2), (3, 2)];
+
+        assert!((weighted_kappa(&pairs).unwrap() - 0.875).abs() < 1e-9);
+        assert_eq!(weighted_kappa(&[(3, 3), (3, 3)]), None);
+        assert_eq!(weighted_kappa(&[]), None);
+    }
+
+    #[test]
+    fn test_report() {
+        let ids = [ManuscriptId([0; 32]), ManuscriptId([1; 32])];
+        let sheets = ["Alice", "Bob"].map(|reviewer| {
+            let reviews = ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| {
+                    let mut rank = sitcon_gdsc::Group::default();
+                    *rank.reviewed_mut() = true;
+                    if reviewer == "Bob" && i == 1 {
+                        *rank.subject.coding_related.choice_mut() = StandardChoice::No;
+                    }
+
+                    (*id, ReviewSheet::of(&rank))
+                })
+                .collect::<HashMap<_, _>>();
+
+            ReviewerSheets {
+                reviewer: reviewer.into(),
+                rubric: "sitcon-gdsc".into(),
+                exported_at: Timestamp(0),
+                reviews,
+            }
+        });
+
+        let report = AgreementReport::new(&sheets);
+        let coding = report
+            .items
+            .iter()
+            .find(|i| i.item_key == "coding_related")
+            .unwrap();
+
+        assert_eq!(coding.units, 2);
+        assert!(coding.alpha.unwrap().abs() < 1e-9);
+        assert_eq!(coding.kappas.len(), 1);
+        assert!(report.items.iter().filter(|i| i.alpha.is_none()).count() > 0);
+
+        let csv = report.to_csv().unwrap();
+        assert!(csv.lines().next().unwrap().ends_with("kappa Alice / Bob"));
+        assert_eq!(csv.lines().count(), report.items.len() + 1);
+    }
+}
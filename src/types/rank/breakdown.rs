@@ -0,0 +1,143 @@
+//! The itemized explanation of a group score.
+
+use super::{Item, StandardChoice};
+
+/// A row of the [`ScoreBreakdown`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScoreRow {
+    /// The points an item contributed.
+    Item {
+        name: String,
+        choice: StandardChoice,
+        points: f64,
+    },
+
+    /// The cap or rounding applied to the items above.
+    ///
+    /// `points` is the change of the score, such as `-1.5` for a cap.
+    Adjustment { description: String, points: f64 },
+}
+
+/// The itemized explanation of a group score.
+///
+/// A score consists of *parts*. The items are added to the current part,
+/// and the caps and rounding apply to the current part only.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ScoreBreakdown {
+    rows: Vec<ScoreRow>,
+
+    /// The total of the finished parts.
+    finished: f64,
+
+    /// The subtotal of the current part.
+    current: f64,
+}
+
+impl ScoreBreakdown {
+    /// Add the points an item contributed to the current part.
+    pub fn item(mut self, item: &(impl Item + ?Sized), points: f64) -> Self {
+        self.rows.push(ScoreRow::Item {
+            name: item.name().to_owned(),
+            choice: item.choice(),
+            points,
+        });
+        self.current += points;
+        self
+    }
+
+    /// Cap the current part to `max`.
+    pub fn cap(self, description: &str, max: f64) -> Self {
+        let capped = self.current.min(max);
+        self.adjust(description, capped)
+    }
+
+    /// Round the current part to the nearest integer.
+    pub fn round(self, description: &str) -> Self {
+        let rounded = self.current.round();
+        self.adjust(description, rounded)
+    }
+
+    /// Finish the current part and start a new one.
+    pub fn part(mut self) -> Self {
+        self.finished += self.current;
+        self.current = 0.0;
+        self
+    }
+
+    /// Set the current part to `value`, recording the change if any.
+    fn adjust(mut self, description: &str, value: f64) -> Self {
+        let points = value - self.current;
+
+        if points.abs() > f64::EPSILON {
+            self.rows.push(ScoreRow::Adjustment {
+                description: description.to_owned(),
+                points,
+            });
+        }
+
+        self.current = value;
+        self
+    }
+
+    /// The rows of this breakdown.
+    pub fn rows(&self) -> &[ScoreRow] {
+        &self.rows
+    }
+
+    /// The total score.
+    pub fn total(&self) -> f64 {
+        self.finished + self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ScoreBreakdown, ScoreRow};
+    use crate::types::rank::{sitcon_gdsc, Item, ItemGroup, StandardChoice};
+
+    #[test]
+    fn test_cap_and_round() {
+        let group = sitcon_gdsc::expressive::Group::default();
+
+        let breakdown = ScoreBreakdown::default()
+            .item(&group.organized, 3.3)
+            .item(&group.fluent, 3.3)
+            .item(&group.completeness, 3.3)
+            .round("round")
+            .cap("cap", 9.0)
+            .part()
+            .item(&group.organized, 1.0);
+
+        assert_eq!(breakdown.total(), 10.0);
+        assert_eq!(breakdown.rows().len(), 6);
+        assert!(matches!(
+            &breakdown.rows()[4],
+            ScoreRow::Adjustment { points, .. } if *points == -1.0
+        ));
+    }
+
+    #[test]
+    fn test_no_adjustment() {
+        let group = sitcon_gdsc::expressive::Group::default();
+
+        let breakdown = ScoreBreakdown::default()
+            .item(&group.organized, 3.0)
+            .round("round")
+            .cap("cap", 7.0);
+
+        assert_eq!(breakdown.rows().len(), 1);
+    }
+
+    #[test]
+    fn test_sitcon_gdsc_scores() {
+        let mut subject = sitcon_gdsc::subject::Group::default();
+        assert_eq!(subject.score(), 10.0);
+
+        *subject.coding_related.choice_mut() = StandardChoice::No;
+        *subject.floss_related.choice_mut() = StandardChoice::Partial;
+        assert_eq!(subject.score(), 8.5);
+
+        assert_eq!(sitcon_gdsc::content::Group::default().score(), 10.0);
+        assert_eq!(sitcon_gdsc::expressive::Group::default().score(), 10.0);
+    }
+}
@@ -0,0 +1,253 @@
+//! The change history of the reviews: which field of a review changed, from
+//! what to what, and when.
+//!
+//! The fields are compared on the [`ReviewSheet`]s, so the history works with
+//! any rubric. The edits of a comment within [`MERGE_SECONDS`] are merged into
+//! one entry, so typing a comment does not log every letter. Every change of
+//! a choice or of the reviewed state has its own entry.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::sheet::ReviewSheet;
+use crate::types::{timestamp::Timestamp, ManuscriptId};
+
+/// The edits of the same comment within this many seconds are merged.
+pub const MERGE_SECONDS: u64 = 60;
+
+/// The suffix of the field names of the comments.
+const COMMENT_SUFFIX: &str = " / comment";
+
+/// The field name of the reviewed state.
+pub const REVIEWED_FIELD: &str = "Reviewed";
+
+/// A change of a field of a review.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ChangeEntry {
+    pub at: Timestamp,
+
+    /// The human-readable name of the field, such as `主題相關 / 和開源相關`.
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// When the review of a manuscript was started and finished.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ReviewTimes {
+    /// The first change.
+    pub started: Timestamp,
+
+    /// The last time it was marked as reviewed, if it is reviewed.
+    pub finished: Option<Timestamp>,
+}
+
+impl ReviewTimes {
+    /// The seconds from the first change to the finish.
+    pub fn duration(&self) -> Option<u64> {
+        Some(self.finished?.seconds_since(self.started))
+    }
+}
+
+/// The changes of the fields from `before` to `after`, as `(field, old, new)`.
+///
+/// Both sheets should be of the same rubric.
+pub fn diff(before: &ReviewSheet, after: &ReviewSheet) -> Vec<(String, String, String)> {
+    let mut changes = Vec::new();
+    let yes_no = |b: bool| if b { "Yes" } else { "No" }.to_owned();
+
+    if before.reviewed != after.reviewed {
+        changes.push((
+            REVIEWED_FIELD.to_owned(),
+            yes_no(before.reviewed),
+            yes_no(after.reviewed),
+        ));
+    }
+
+    for group in &after.groups {
+        let Some(old_group) = before.group(group.key) else {
+            continue;
+        };
+
+        for item in &group.items {
+            let Some(old) = old_group.items.iter().find(|i| i.key == item.key) else {
+                continue;
+            };
+            let field = format!("{} / {}", group.name, item.name);
+
+            if old.choice != item.choice {
+                changes.push((
+                    field.clone(),
+                    old.choice.to_string(),
+                    item.choice.to_string(),
+                ));
+            }
+            if old.comment != item.comment {
+                changes.push((
+                    format!("{field}{COMMENT_SUFFIX}"),
+                    old.comment.clone().unwrap_or_default(),
+                    item.comment.clone().unwrap_or_default(),
+                ));
+            }
+        }
+
+        if old_group.comment != group.comment {
+            changes.push((
+                format!("{}{COMMENT_SUFFIX}", group.name),
+                old_group.comment.clone(),
+                group.comment.clone(),
+            ));
+        }
+    }
+
+    changes
+}
+
+/// The change history of every manuscript.
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+pub struct ChangeLog(HashMap<ManuscriptId, Vec<ChangeEntry>>);
+
+impl ChangeLog {
+    /// Record the changes of a review from `before` to `after` at `at`.
+    pub fn record(
+        &mut self,
+        id: ManuscriptId,
+        before: &ReviewSheet,
+        after: &ReviewSheet,
+        at: Timestamp,
+    ) {
+        let changes = diff(before, after);
+        if changes.is_empty() {
+            return;
+        }
+
+        let entries = self.0.entry(id).or_default();
+        for (field, old, new) in changes {
+            // Merge into the recent entry of the comment, if it is the last one.
+            let recent = entries.last_mut().filter(|e| {
+                e.field == field
+                    && field.ends_with(COMMENT_SUFFIX)
+                    && at.seconds_since(e.at) < MERGE_SECONDS
+            });
+
+            match recent {
+                Some(entry) => {
+                    entry.new = new;
+                    entry.at = at;
+                    if entry.old == entry.new {
+                        entries.pop();
+                    }
+                }
+                None => entries.push(ChangeEntry {
+                    at,
+                    field,
+                    old,
+                    new,
+                }),
+            }
+        }
+    }
+
+    /// The changes of a manuscript, from the oldest.
+    pub fn entries(&self, id: &ManuscriptId) -> &[ChangeEntry] {
+        self.0.get(id).map_or(&[], Vec::as_slice)
+    }
+
+    /// When the review of a manuscript was started and finished.
+    pub fn times(&self, id: &ManuscriptId) -> Option<ReviewTimes> {
+        let entries = self.entries(id);
+        let started = entries.first()?.at;
+        let finished = entries
+            .iter()
+            .rev()
+            .find(|e| e.field == REVIEWED_FIELD)
+            .filter(|e| e.new == "Yes")
+            .map(|e| e.at);
+
+        Some(ReviewTimes { started, finished })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangeLog, REVIEWED_FIELD};
+    use crate::types::{
+        rank::{sheet::ReviewSheet, sitcon_gdsc, CommentableItemGroup, Item, StandardChoice},
+        timestamp::Timestamp,
+        ManuscriptId,
+    };
+
+    #[test]
+    fn test_record() {
+        let id = ManuscriptId([0; 32]);
+        let mut log = ChangeLog::default();
+        let mut rank = sitcon_gdsc::Group::default();
+        let mut sheet = ReviewSheet::of(&rank);
+
+        let mut edit = |log: &mut ChangeLog, rank: &sitcon_gdsc::Group, at: u64| {
+            let after = ReviewSheet::of(rank);
+            log.record(id, &sheet, &after, Timestamp(at));
+            sheet = after;
+        };
+
+        *rank.subject.floss_related.choice_mut() = StandardChoice::No;
+        edit(&mut log, &rank, 1000);
+        // typing a comment is merged into one entry.
+        rank.subject.comment_mut().push('G');
+        edit(&mut log, &rank, 1010);
+        rank.subject.comment_mut().push_str("ood");
+        edit(&mut log, &rank, 1020);
+        rank.reviewed = true;
+        edit(&mut log, &rank, 1600);
+
+        let entries = log.entries(&id);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].old, StandardChoice::Full.to_string());
+        assert_eq!(entries[0].new, StandardChoice::No.to_string());
+        assert_eq!(
+            (entries[1].old.as_str(), entries[1].new.as_str()),
+            ("", "Good")
+        );
+        assert_eq!(entries[1].at, Timestamp(1020));
+        assert_eq!(entries[2].field, REVIEWED_FIELD);
+
+        let times = log.times(&id).unwrap();
+        assert_eq!(times.duration(), Some(600));
+    }
+
+    #[test]
+    fn test_record_choice_flip_flop() {
+        let id = ManuscriptId([0; 32]);
+        let mut log = ChangeLog::default();
+        let mut rank = sitcon_gdsc::Group::default();
+        let mut sheet = ReviewSheet::of(&rank);
+
+        // a choice changed and changed back at once is still two changes.
+        for (at, choice) in [(1000, StandardChoice::No), (1005, StandardChoice::Full)] {
+            *rank.subject.floss_related.choice_mut() = choice;
+            let after = ReviewSheet::of(&rank);
+            log.record(id, &sheet, &after, Timestamp(at));
+            sheet = after;
+        }
+
+        let entries = log
+            .entries(&id)
+            .iter()
+            .map(|e| (e.old.clone(), e.new.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                (
+                    StandardChoice::Full.to_string(),
+                    StandardChoice::No.to_string()
+                ),
+                (
+                    StandardChoice::No.to_string(),
+                    StandardChoice::Full.to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,579 @@
+//! The conflict-free merging of the reviews edited offline.
+//!
+//! Every field of a review – the choice and the comment of a rank item, the
+//! comment of a group, the reviewed flag – is a multi-value register. A write
+//! is tagged with a *dot*: the replica (an installation of this tool) which
+//! made it, and a Lamport clock, which is ahead of every write the replica
+//! has seen. The [`ReviewSet`] keeps the dots it has seen as a version
+//! vector, so the merge can tell whether two writes of a field are ordered
+//! or concurrent:
+//!
+//! - A write seen by the other copy and replaced there is dropped.
+//! - The concurrent writes are all kept. They are resolved as the latest
+//!   write wins (by the wall clock of the writing device, then the Lamport
+//!   clock, then the replica ID), except the comments (the fields named
+//!   `comment`), which are joined so nothing written is lost.
+//!
+//! A removed review, such as a reset one, is a write of its [`DELETED`] field,
+//! so the merge drops it from the copies which have not seen the removal.
+//!
+//! The merge is commutative, associative and idempotent: the copies merged
+//! in any order resolve to the same reviews.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{GroupMetaDatabase, Rubric};
+use crate::types::{timestamp::Timestamp, ManuscriptId};
+
+/// The separator of the joined concurrent comments.
+pub const COMMENT_SEPARATOR: &str = "\n\n";
+
+/// The path of the field marking a review removed.
+///
+/// The `$` is never in a field name of a rubric, so it can't collide.
+pub const DELETED: &str = "$deleted";
+
+/// The ID of a replica, which is random for every installation.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[serde(transparent)]
+pub struct ReplicaId(pub String);
+
+impl ReplicaId {
+    /// Generate a random replica ID of 128 bits from the random source of the system.
+    pub fn random() -> Self {
+        let mut bytes = [0; 16];
+        getrandom::getrandom(&mut bytes).expect("the platform has no random source");
+
+        Self(bytes.iter().map(|b| format!("{b:02x}")).collect())
+    }
+}
+
+impl Default for ReplicaId {
+    /// A new replica, see [`ReplicaId::random`].
+    fn default() -> Self {
+        Self::random()
+    }
+}
+
+/// A write of a field.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Entry {
+    pub replica: ReplicaId,
+
+    /// The Lamport clock of the write.
+    pub counter: u64,
+
+    /// The wall clock of the write, to resolve the concurrent writes.
+    #[serde(default)]
+    pub at: Timestamp,
+
+    pub value: Value,
+}
+
+impl Entry {
+    fn is_same_write(&self, other: &Entry) -> bool {
+        self.replica == other.replica && self.counter == other.counter
+    }
+}
+
+/// The concurrent writes of every field of a review, by the path of the field.
+pub type Fields = BTreeMap<String, Vec<Entry>>;
+
+/// The reviews with the history needed to merge them.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct ReviewSet {
+    /// The version vector: the last clock seen of every replica.
+    context: BTreeMap<ReplicaId, u64>,
+
+    reviews: HashMap<ManuscriptId, Fields>,
+}
+
+impl ReviewSet {
+    /// Has this set seen the write?
+    fn covers(&self, entry: &Entry) -> bool {
+        self.context
+            .get(&entry.replica)
+            .is_some_and(|&counter| entry.counter <= counter)
+    }
+
+    /// Record the fields of `rank` changed since the last record as the writes of `replica` at `at`.
+    ///
+    /// The reviews not in `rank` any more are recorded as removed, and the
+    /// removed ones in `rank` again as restored.
+    /// Return the number of the changed fields.
+    pub fn record<R: Rubric>(
+        &mut self,
+        replica: &ReplicaId,
+        rank: &GroupMetaDatabase<R>,
+        at: Timestamp,
+    ) -> Result<usize, serde_json::Error> {
+        let mut changed = 0;
+
+        for (id, review) in rank.iter() {
+            let mut leaves = Vec::new();
+            flatten(String::new(), serde_json::to_value(review)?, &mut leaves);
+
+            let fields = self.reviews.entry(*id).or_default();
+            if fields.contains_key(DELETED) {
+                leaves.push((DELETED.to_owned(), Value::Bool(false)));
+            }
+            for (path, value) in leaves {
+                changed += self.write(replica, *id, path, value, at);
+            }
+        }
+
+        let removed = self
+            .reviews
+            .keys()
+            .filter(|id| !rank.contains_key(id))
+            .copied()
+            .collect::<Vec<_>>();
+        for id in removed {
+            changed += self.write(replica, id, DELETED.to_owned(), Value::Bool(true), at);
+        }
+
+        Ok(changed)
+    }
+
+    /// Write the field of `path` of the review `id`, if it is not `value` already.
+    ///
+    /// Return the number of the changed fields: 1 or 0.
+    fn write(
+        &mut self,
+        replica: &ReplicaId,
+        id: ManuscriptId,
+        path: String,
+        value: Value,
+        at: Timestamp,
+    ) -> usize {
+        // The Lamport clock: ahead of every write seen.
+        let counter = self.context.values().max().copied().unwrap_or_default() + 1;
+
+        let entries = self
+            .reviews
+            .entry(id)
+            .or_default()
+            .entry(path.clone())
+            .or_default();
+        if resolve_field(&path, entries).as_ref() == Some(&value) {
+            return 0;
+        }
+
+        *entries = vec![Entry {
+            replica: replica.clone(),
+            counter,
+            at,
+            value,
+        }];
+        self.context.insert(replica.clone(), counter);
+
+        1
+    }
+
+    /// Merge the other copy into this one.
+    pub fn merge(&mut self, other: &ReviewSet) {
+        let empty = Fields::new();
+        let ids = self
+            .reviews
+            .keys()
+            .chain(other.reviews.keys())
+            .copied()
+            .collect::<Vec<_>>();
+
+        for id in ids {
+            let theirs = other.reviews.get(&id).unwrap_or(&empty);
+            let ours = self.reviews.entry(id).or_default();
+            let paths = ours
+                .keys()
+                .chain(theirs.keys())
+                .cloned()
+                .collect::<Vec<_>>();
+
+            for path in paths {
+                let theirs = theirs.get(&path).map_or(&[][..], Vec::as_slice);
+                let ours = ours.entry(path).or_default();
+
+                // keep the writes both have, and the ones the other has not seen.
+                let mut merged = ours
+                    .iter()
+                    .filter(|e| theirs.iter().any(|t| t.is_same_write(e)) || !other.covers(e))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                for entry in theirs {
+                    let seen = self
+                        .context
+                        .get(&entry.replica)
+                        .is_some_and(|&counter| entry.counter <= counter);
+
+                    if !ours.iter().any(|o| o.is_same_write(entry)) && !seen {
+                        merged.push(entry.clone());
+                    }
+                }
+                merged.sort_by(|a, b| (&a.replica, a.counter).cmp(&(&b.replica, b.counter)));
+
+                *ours = merged;
+            }
+        }
+
+        for (replica, &counter) in &other.context {
+            let ours = self.context.entry(replica.clone()).or_default();
+            *ours = (*ours).max(counter);
+        }
+    }
+
+    /// Resolve the reviews, except the removed ones.
+    pub fn resolve<R: Rubric>(&self) -> Result<GroupMetaDatabase<R>, serde_json::Error> {
+        let mut rank = GroupMetaDatabase::with_capacity(self.reviews.len());
+
+        for (id, fields) in &self.reviews {
+            let deleted = fields.get(DELETED).and_then(|e| resolve_field(DELETED, e));
+            if deleted == Some(Value::Bool(true)) {
+                continue;
+            }
+
+            let mut review = Value::Object(Map::new());
+            for (path, entries) in fields.iter().filter(|(path, _)| *path != DELETED) {
+                if let Some(value) = resolve_field(path, entries) {
+                    insert(&mut review, path, value);
+                }
+            }
+
+            rank.insert(*id, serde_json::from_value(review)?);
+        }
+
+        Ok(rank)
+    }
+
+    /// The fields with the concurrent writes, by the manuscript.
+    pub fn conflicts(&self) -> impl Iterator<Item = (&ManuscriptId, &str)> {
+        self.reviews.iter().flat_map(|(id, fields)| {
+            fields
+                .iter()
+                .filter(|(_, entries)| entries.len() > 1)
+                .map(move |(path, _)| (id, path.as_str()))
+        })
+    }
+}
+
+/// Is the field of `path` a comment?
+fn is_comment(path: &str) -> bool {
+    path.rsplit('/').next() == Some("comment")
+}
+
+/// Resolve the concurrent writes of the field of `path`.
+fn resolve_field(path: &str, entries: &[Entry]) -> Option<Value> {
+    let comments = entries
+        .iter()
+        .map(|e| match &e.value {
+            Value::String(s) => Some(Some(s.as_str())),
+            Value::Null => Some(None),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>();
+
+    // Join the comments, so nothing written is lost.
+    if let Some(comments) = comments.filter(|_| entries.len() > 1 && is_comment(path)) {
+        let mut comments = comments
+            .into_iter()
+            .flatten()
+            .filter(|c| !c.is_empty())
+            .collect::<Vec<_>>();
+        comments.dedup();
+
+        if !comments.is_empty() {
+            return Some(Value::String(comments.join(COMMENT_SEPARATOR)));
+        }
+    }
+
+    entries
+        .iter()
+        .max_by(|a, b| (a.at, a.counter, &a.replica).cmp(&(b.at, b.counter, &b.replica)))
+        .map(|e| e.value.clone())
+}
+
+/// Flatten a value to the leaves and their paths, such as `subject/comment`.
+fn flatten(path: String, value: Value, leaves: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let path = if path.is_empty() {
+                    key
+                } else {
+                    format!("{path}/{key}")
+                };
+                flatten(path, value, leaves);
+            }
+        }
+        leaf => leaves.push((path, leaf)),
+    }
+}
+
+/// Insert a leaf at `path` into an object.
+fn insert(root: &mut Value, path: &str, value: Value) {
+    let mut node = root;
+    let mut keys = path.split('/').peekable();
+
+    while let Some(key) = keys.next() {
+        let Value::Object(map) = node else {
+            return;
+        };
+
+        if keys.peek().is_none() {
+            map.insert(key.to_owned(), value);
+            return;
+        }
+        node = map
+            .entry(key.to_owned())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+}
+
+/// The portable file of a [`ReviewSet`].
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReviewSetExport {
+    pub reviewer: String,
+    pub rubric: String,
+
+    /// The version of the serialized meta group.
+    pub rubric_version: u32,
+    pub exported_at: Timestamp,
+    pub reviews: ReviewSet,
+}
+
+impl ReviewSetExport {
+    pub fn new<R: Rubric>(reviewer: &str, reviews: ReviewSet) -> Self {
+        Self {
+            reviewer: reviewer.to_owned(),
+            rubric: R::ID.to_owned(),
+            rubric_version: R::VERSION,
+            exported_at: Timestamp::now(),
+            reviews,
+        }
+    }
+
+    pub fn to_yaml(&self) -> Result<String, Error> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// Read a file of the rubric `R`.
+    ///
+    /// The history can't be migrated, so the file must be of the current version.
+    pub fn from_yaml<R: Rubric>(document: &str) -> Result<Self, Error> {
+        let export: Self = serde_yaml::from_str(document)?;
+
+        if export.rubric != R::ID {
+            return Err(Error::RubricMismatch {
+                found: export.rubric,
+                expected: R::ID,
+            });
+        }
+        if export.rubric_version != R::VERSION {
+            return Err(Error::VersionMismatch {
+                found: export.rubric_version,
+                expected: R::VERSION,
+            });
+        }
+
+        Ok(export)
+    }
+
+    /// The suggested file name of this export.
+    pub fn file_name(&self) -> String {
+        format!(
+            "merge-{}-{}.yaml",
+            self.rubric,
+            self.exported_at.file_stamp()
+        )
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("failed to (de)serialize the reviews: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("the file is of the rubric `{found}`, but `{expected}` is selected")]
+    RubricMismatch {
+        found: String,
+        expected: &'static str,
+    },
+
+    #[error("the file is of the rubric version {found}, but {expected} is required")]
+    VersionMismatch { found: u32, expected: u32 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReplicaId, ReviewSet, COMMENT_SEPARATOR};
+    use crate::types::{
+        rank::{
+            sitcon_gdsc, CommentableItemGroup, GroupMetaDatabase, Item, MutableMetaGroup,
+            StandardChoice,
+        },
+        timestamp::Timestamp,
+        ManuscriptId,
+    };
+
+    #[test]
+    fn test_merge() {
+        let id = ManuscriptId([0; 32]);
+        let (browser, native) = (ReplicaId("browser".into()), ReplicaId("native".into()));
+
+        let mut rank = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        rank.insert(id, sitcon_gdsc::Group::default());
+        let mut base = ReviewSet::default();
+        base.record(&browser, &rank, Timestamp(0)).unwrap();
+
+        // The browser marks it reviewed; both edit the comment and a choice.
+        let mut a = (base.clone(), base.resolve::<sitcon_gdsc::Group>().unwrap());
+        let review = a.1.get_mut(&id).unwrap();
+        *review.reviewed_mut() = true;
+        *review.content.comment_mut() = "from the browser".into();
+        *review.subject.coding_related.choice_mut() = StandardChoice::Partial;
+        assert_eq!(a.0.record(&browser, &a.1, Timestamp(1)).unwrap(), 3);
+
+        let mut b = (base.clone(), base.resolve::<sitcon_gdsc::Group>().unwrap());
+        let review = b.1.get_mut(&id).unwrap();
+        *review.content.comment_mut() = "from the app".into();
+        *review.subject.coding_related.choice_mut() = StandardChoice::No;
+        *review.expressive.fluent.choice_mut() = StandardChoice::Maybe;
+        b.0.record(&native, &b.1, Timestamp(2)).unwrap();
+
+        let mut ab = a.0.clone();
+        ab.merge(&b.0);
+        let mut ba = b.0.clone();
+        ba.merge(&a.0);
+        assert_eq!(ab, ba);
+
+        // idempotent.
+        let mut again = ab.clone();
+        again.merge(&b.0);
+        assert_eq!(again, ab);
+
+        let merged = ab.resolve::<sitcon_gdsc::Group>().unwrap();
+        let review = &merged[&id];
+        assert!(review.reviewed);
+        assert_eq!(
+            review.content.comment(),
+            format!("from the browser{COMMENT_SEPARATOR}from the app")
+        );
+        assert_eq!(review.expressive.fluent.choice(), StandardChoice::Maybe);
+        assert_eq!(ab.conflicts().count(), 2);
+
+        // The later edit after the merge supersedes the concurrent writes.
+        let mut merged = merged;
+        *merged.get_mut(&id).unwrap().content.comment_mut() = "settled".into();
+        ab.record(&native, &merged, Timestamp(3)).unwrap();
+        ba.merge(&ab);
+        assert_eq!(ba.conflicts().count(), 1);
+        assert_eq!(
+            ba.resolve::<sitcon_gdsc::Group>().unwrap()[&id]
+                .content
+                .comment(),
+            "settled"
+        );
+    }
+
+    #[test]
+    fn test_later_write_wins() {
+        let id = ManuscriptId([0; 32]);
+        let (busy, idle) = (ReplicaId("busy".into()), ReplicaId("idle".into()));
+
+        let mut rank = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        rank.insert(id, sitcon_gdsc::Group::default());
+        let mut base = ReviewSet::default();
+        base.record(&busy, &rank, Timestamp(0)).unwrap();
+
+        // The busy replica edits many times, and the idle one once, but later.
+        let mut a = (base.clone(), base.resolve::<sitcon_gdsc::Group>().unwrap());
+        for (at, choice) in [
+            StandardChoice::Partial,
+            StandardChoice::No,
+            StandardChoice::Full,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            *a.1.get_mut(&id)
+                .unwrap()
+                .subject
+                .coding_related
+                .choice_mut() = choice;
+            a.0.record(&busy, &a.1, Timestamp(at as u64 + 1)).unwrap();
+        }
+
+        let mut b = (base.clone(), base.resolve::<sitcon_gdsc::Group>().unwrap());
+        *b.1.get_mut(&id)
+            .unwrap()
+            .subject
+            .coding_related
+            .choice_mut() = StandardChoice::Maybe;
+        b.0.record(&idle, &b.1, Timestamp(10)).unwrap();
+
+        let mut ab = a.0.clone();
+        ab.merge(&b.0);
+        let mut ba = b.0.clone();
+        ba.merge(&a.0);
+        assert_eq!(ab, ba);
+        assert_eq!(
+            ab.resolve::<sitcon_gdsc::Group>().unwrap()[&id]
+                .subject
+                .coding_related
+                .choice(),
+            StandardChoice::Maybe
+        );
+
+        // The clock of the idle replica catches up after the merge.
+        let mut reviewed = ba.resolve::<sitcon_gdsc::Group>().unwrap();
+        *reviewed.get_mut(&id).unwrap().reviewed_mut() = true;
+        ba.record(&idle, &reviewed, Timestamp(11)).unwrap();
+        assert!(ba.context[&idle] > ba.context[&busy]);
+    }
+
+    #[test]
+    fn test_merge_after_reset() {
+        let (id, other) = (ManuscriptId([0; 32]), ManuscriptId([1; 32]));
+        let (laptop, desktop) = (ReplicaId("laptop".into()), ReplicaId("desktop".into()));
+
+        let mut rank = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        rank.insert(id, sitcon_gdsc::Group::default());
+        rank.insert(other, sitcon_gdsc::Group::default());
+        *rank.get_mut(&id).unwrap().content.comment_mut() = "wiped".into();
+        let mut base = ReviewSet::default();
+        base.record(&laptop, &rank, Timestamp(0)).unwrap();
+
+        // The laptop resets all the reviews; the desktop edits the other one.
+        let mut a = base.clone();
+        assert_eq!(
+            a.record(
+                &laptop,
+                &GroupMetaDatabase::<sitcon_gdsc::Group>::new(),
+                Timestamp(1)
+            )
+            .unwrap(),
+            2
+        );
+        let mut b = (base.clone(), base.resolve::<sitcon_gdsc::Group>().unwrap());
+        *b.1.get_mut(&other).unwrap().reviewed_mut() = true;
+        b.0.record(&desktop, &b.1, Timestamp(2)).unwrap();
+
+        let mut ab = a.clone();
+        ab.merge(&b.0);
+        let mut ba = b.0.clone();
+        ba.merge(&a);
+        assert_eq!(ab, ba);
+        assert!(ab.resolve::<sitcon_gdsc::Group>().unwrap().is_empty());
+
+        // Reviewing it again restores it, over the removal.
+        let mut again = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        again.insert(id, sitcon_gdsc::Group::default());
+        ab.record(&laptop, &again, Timestamp(3)).unwrap();
+        ba.merge(&ab);
+        let resolved = ba.resolve::<sitcon_gdsc::Group>().unwrap();
+        assert_eq!(resolved.keys().collect::<Vec<_>>(), [&id]);
+        assert_eq!(resolved[&id].content.comment(), "");
+    }
+}
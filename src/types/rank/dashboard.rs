@@ -0,0 +1,216 @@
+//! The statistics of the submissions and of the reviews of a reviewer.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::{sheet::ReviewSheet, StandardChoice};
+use crate::types::{Difficulty, ManuscriptDatabase, ManuscriptId};
+
+/// The share of a choice over which an item is considered skewed.
+pub const SKEW_THRESHOLD: f64 = 0.8;
+
+/// The least number of the reviews to tell whether an item is skewed.
+pub const SKEW_MIN_REVIEWS: usize = 5;
+
+/// The scores of a group in the reviewed manuscripts.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GroupDistribution {
+    pub name: String,
+    pub scores: Vec<f64>,
+}
+
+impl GroupDistribution {
+    /// Count the scores in the bins of `width`, as `(the start of the bin, count)`.
+    pub fn histogram(&self, width: f64) -> Vec<(f64, usize)> {
+        let mut bins = BTreeMap::<i64, usize>::new();
+
+        for score in &self.scores {
+            *bins.entry((score / width).floor() as i64).or_default() += 1;
+        }
+
+        bins.into_iter()
+            .map(|(bin, count)| (bin as f64 * width, count))
+            .collect()
+    }
+}
+
+/// The choices of an item in the reviewed manuscripts.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ItemDistribution {
+    pub group: String,
+    pub name: String,
+
+    /// The number of every choice, indexed by [`StandardChoice::ordinal`].
+    pub counts: [usize; 4],
+}
+
+impl ItemDistribution {
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// The share of the choice, from 0 to 1.
+    pub fn share(&self, choice: StandardChoice) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.counts[choice.ordinal()] as f64 / total as f64,
+        }
+    }
+
+    /// The choice given to most of the manuscripts, if its share is over [`SKEW_THRESHOLD`].
+    ///
+    /// It hints that the item may not tell the manuscripts apart.
+    pub fn skewed(&self) -> Option<StandardChoice> {
+        if self.total() < SKEW_MIN_REVIEWS {
+            return None;
+        }
+
+        StandardChoice::ALL
+            .into_iter()
+            .find(|c| self.share(*c) >= SKEW_THRESHOLD)
+    }
+}
+
+/// The statistics of the submissions and the reviews.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Dashboard {
+    pub by_type: BTreeMap<String, usize>,
+    pub by_difficulty: BTreeMap<Difficulty, usize>,
+
+    pub reviewed: usize,
+    pub pending: usize,
+
+    /// The groups and the items of the reviewed manuscripts, following the layout.
+    pub groups: Vec<GroupDistribution>,
+    pub items: Vec<ItemDistribution>,
+}
+
+impl Dashboard {
+    /// Summarize the manuscripts and the reviews. The groups and the items
+    /// follow `layout`, usually the sheet of a blank review of the rubric.
+    pub fn new(
+        manuscripts: &ManuscriptDatabase,
+        layout: &ReviewSheet,
+        reviews: &HashMap<ManuscriptId, ReviewSheet>,
+    ) -> Self {
+        let mut by_type = BTreeMap::new();
+        let mut by_difficulty = BTreeMap::new();
+        for (_, manuscript) in manuscripts.iter() {
+            *by_type.entry(manuscript.type_.clone()).or_default() += 1;
+            *by_difficulty.entry(manuscript.difficulty).or_default() += 1;
+        }
+
+        let reviewed = manuscripts
+            .iter()
+            .filter_map(|(id, _)| reviews.get(id).filter(|r| r.reviewed))
+            .collect::<Vec<_>>();
+
+        let groups = layout
+            .groups
+            .iter()
+            .map(|group| GroupDistribution {
+                name: group.name.clone(),
+                scores: reviewed
+                    .iter()
+                    .filter_map(|r| Some(r.group(group.key)?.score))
+                    .collect(),
+            })
+            .collect();
+
+        let items = layout
+            .groups
+            .iter()
+            .flat_map(|group| {
+                group.items.iter().map(|item| {
+                    let mut counts = [0; 4];
+                    for choice in reviewed.iter().filter_map(|r| {
+                        let reviewed = r.group(group.key)?;
+                        Some(reviewed.items.iter().find(|i| i.key == item.key)?.choice)
+                    }) {
+                        counts[choice.ordinal()] += 1;
+                    }
+
+                    ItemDistribution {
+                        group: group.name.clone(),
+                        name: item.name.clone(),
+                        counts,
+                    }
+                })
+            })
+            .collect();
+
+        Self {
+            by_type,
+            by_difficulty,
+            reviewed: reviewed.len(),
+            pending: manuscripts.len() - reviewed.len(),
+            groups,
+            items,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::Dashboard;
+    use crate::types::{
+        rank::{sheet::ReviewSheet, sitcon_gdsc, Item, StandardChoice},
+        Difficulty, Manuscript, ManuscriptDatabase,
+    };
+
+    #[test]
+    fn test_dashboard() {
+        let manuscripts: ManuscriptDatabase = (0..6)
+            .map(|i| Manuscript {
+                difficulty: if i % 2 == 0 {
+                    Difficulty::Easy
+                } else {
+                    Difficulty::Hard
+                },
+                ..Manuscript::fixture(
+                    &format!("Talk {i}"),
+                    &format!("Speaker {i}"),
+                    if i < 4 { "Talk" } else { "Lightning" },
+                )
+            })
+            .collect::<Vec<_>>()
+            .into();
+
+        // all but the last are reviewed, one with "No" on FLOSS.
+        let reviews = manuscripts
+            .iter()
+            .enumerate()
+            .map(|(i, (id, _))| {
+                let mut rank = sitcon_gdsc::Group {
+                    reviewed: i < 5,
+                    ..Default::default()
+                };
+                if i == 0 {
+                    *rank.subject.floss_related.choice_mut() = StandardChoice::No;
+                }
+                (*id, ReviewSheet::of(&rank))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let layout = ReviewSheet::of(&sitcon_gdsc::Group::default());
+        let dashboard = Dashboard::new(&manuscripts, &layout, &reviews);
+
+        assert_eq!(dashboard.by_type["Talk"], 4);
+        assert_eq!(dashboard.by_difficulty[&Difficulty::Hard], 3);
+        assert_eq!((dashboard.reviewed, dashboard.pending), (5, 1));
+        assert_eq!(dashboard.groups.len(), layout.groups.len());
+        assert!(dashboard.groups.iter().all(|g| g.scores.len() == 5));
+
+        let floss = dashboard
+            .items
+            .iter()
+            .find(|i| i.name == "和開源相關")
+            .unwrap();
+        assert_eq!(floss.counts, [1, 0, 0, 4]);
+        assert_eq!(floss.skewed(), Some(StandardChoice::Full));
+
+        let histogram = dashboard.groups[0].histogram(0.5);
+        assert_eq!(histogram.iter().map(|(_, n)| n).sum::<usize>(), 5);
+    }
+}
@@ -0,0 +1,160 @@
+//! The dump of the reviews in memory, for the reviewer to inspect or copy.
+//!
+//! The YAML dump is the versioned document as saved (see [`versioned`]),
+//! the JSON dump is the same document in JSON, and the summary lists the
+//! reviews of the manuscripts in a human-readable form.
+
+use std::fmt::Write;
+
+use super::{
+    sheet::ReviewSheet,
+    versioned::{self, Versioned},
+    GroupMetaDatabase, IterableMetaGroup,
+};
+use crate::types::{Manuscript, ManuscriptDatabase};
+
+/// The format of the dump.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DumpFormat {
+    #[default]
+    Yaml,
+    Json,
+    Summary,
+}
+
+impl DumpFormat {
+    pub const ALL: [DumpFormat; 3] = [DumpFormat::Yaml, DumpFormat::Json, DumpFormat::Summary];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DumpFormat::Yaml => "YAML",
+            DumpFormat::Json => "JSON",
+            DumpFormat::Summary => "Summary",
+        }
+    }
+
+    /// The file extension of the dump.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DumpFormat::Yaml => "yaml",
+            DumpFormat::Json => "json",
+            DumpFormat::Summary => "txt",
+        }
+    }
+}
+
+/// Dump the reviews. The summary shows the titles by `title`, so they can be blinded.
+pub fn dump<M: IterableMetaGroup + Versioned + serde::Serialize>(
+    format: DumpFormat,
+    manuscripts: &ManuscriptDatabase,
+    rank: &GroupMetaDatabase<M>,
+    title: impl Fn(&Manuscript) -> String,
+) -> Result<String, Error> {
+    Ok(match format {
+        DumpFormat::Yaml => versioned::serialize(rank)?,
+        DumpFormat::Json => serde_json::to_string_pretty(&versioned::to_value(rank)?)?,
+        DumpFormat::Summary => summary(manuscripts, rank, title),
+    })
+}
+
+/// The reviews of the manuscripts, in the order of the manuscripts.
+fn summary<M: IterableMetaGroup>(
+    manuscripts: &ManuscriptDatabase,
+    rank: &GroupMetaDatabase<M>,
+    title: impl Fn(&Manuscript) -> String,
+) -> String {
+    let reviewed = manuscripts
+        .iter()
+        .filter(|(id, _)| rank.get(id).is_some_and(|m| m.reviewed()))
+        .count();
+
+    let mut out = String::new();
+    // Writing to a String never fails.
+    let _ = writeln!(out, "Reviewed: {reviewed} / {}", manuscripts.len());
+
+    for (id, manuscript) in manuscripts.iter() {
+        let _ = writeln!(
+            out,
+            "\n{} ({}, {})",
+            title(manuscript),
+            manuscript.type_,
+            manuscript.difficulty
+        );
+
+        let Some(meta) = rank.get(id) else {
+            let _ = writeln!(out, "  Not reviewed yet");
+            continue;
+        };
+        let sheet = ReviewSheet::of(meta);
+        let _ = writeln!(
+            out,
+            "  {} Total: {:.1}",
+            if sheet.reviewed { "✅" } else { "❌" },
+            sheet.total
+        );
+
+        for group in &sheet.groups {
+            let _ = writeln!(out, "  {}: {:.1}", group.name, group.score);
+            for item in &group.items {
+                let _ = write!(out, "    {}: {}", item.name, item.choice);
+                match &item.comment {
+                    Some(comment) if !comment.is_empty() => {
+                        let _ = writeln!(out, " — {comment}");
+                    }
+                    _ => out.push('\n'),
+                }
+            }
+            if !group.comment.is_empty() {
+                let _ = writeln!(out, "    Comment: {}", group.comment);
+            }
+        }
+    }
+
+    out
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("failed to serialize the reviews: {0}")]
+    Versioned(#[from] versioned::Error),
+
+    #[error("failed to serialize the reviews as JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dump, DumpFormat};
+    use crate::types::{
+        rank::{sitcon_gdsc, versioned, GroupMetaDatabase},
+        Manuscript, ManuscriptDatabase,
+    };
+
+    #[test]
+    fn test_dump() {
+        let manuscript = Manuscript::fixture("Rust 入門", "Pan", "Talk");
+        let manuscripts = ManuscriptDatabase::from(vec![manuscript]);
+        let id = *manuscripts.first().unwrap();
+        let mut rank = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        rank.insert(
+            id,
+            sitcon_gdsc::Group {
+                reviewed: true,
+                ..Default::default()
+            },
+        );
+        let title = |m: &Manuscript| m.title.clone();
+
+        let yaml = dump(DumpFormat::Yaml, &manuscripts, &rank, title).unwrap();
+        assert!(versioned::deserialize::<sitcon_gdsc::Group>(&yaml).unwrap()[&id].reviewed);
+
+        let json = dump(DumpFormat::Json, &manuscripts, &rank, title).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let review = json["data"].as_object().unwrap().values().next().unwrap();
+        assert_eq!(review["reviewed"], serde_json::Value::Bool(true));
+
+        let summary = dump(DumpFormat::Summary, &manuscripts, &rank, title).unwrap();
+        assert!(summary.starts_with("Reviewed: 1 / 1"));
+        assert!(summary.contains("Rust 入門 (Talk, Easy)"));
+    }
+}
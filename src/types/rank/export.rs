@@ -0,0 +1,330 @@
+//! The portable export of the reviews of a reviewer.
+//!
+//! The reviews are exported as:
+//!
+//! ```yaml
+//! reviewer: <reviewer name>
+//! rubric: <rubric id>
+//! rubric_version: 1
+//! exported_at: <seconds since the Unix epoch>
+//! reviews:
+//!   <manuscript id>:
+//!     title: <manuscript title, for readability only>
+//!     rank: <meta group>
+//!     history: <the changes of the review, if any>
+//! ```
+
+use std::collections::HashMap;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_with::{serde_as, Map};
+use serde_yaml::{Mapping, Value};
+
+use super::{
+    changelog::{ChangeEntry, ChangeLog},
+    versioned, GroupMetaDatabase, Rubric,
+};
+use crate::types::{timestamp::Timestamp, ManuscriptDatabase, ManuscriptId};
+
+/// The exported review of a manuscript.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExportedReview<T> {
+    /// The title of the manuscript. It is ignored when importing.
+    pub title: String,
+    pub rank: T,
+
+    /// The changes of the review. It is ignored when importing.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<ChangeEntry>,
+}
+
+/// The exported reviews of a reviewer.
+///
+/// `T` is the meta group, or a reference to it when exporting.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: DeserializeOwned"))]
+pub struct ReviewExport<T> {
+    pub reviewer: String,
+
+    /// The ID of the rubric.
+    pub rubric: String,
+
+    /// The version of the serialized meta group.
+    pub rubric_version: u32,
+
+    pub exported_at: Timestamp,
+
+    /// The reviews in the order of the manuscripts.
+    #[serde_as(as = "Map<_, _>")]
+    pub reviews: Vec<(ManuscriptId, ExportedReview<T>)>,
+}
+
+impl<'a, R: Rubric> ReviewExport<&'a R> {
+    /// Export the reviews in `rank`.
+    ///
+    /// The reviews are ordered as the manuscripts, and the reviews of the
+    /// manuscripts not in `manuscripts` are not exported.
+    pub fn new(
+        reviewer: &str,
+        manuscripts: &ManuscriptDatabase,
+        rank: &'a GroupMetaDatabase<R>,
+    ) -> Self {
+        let reviews = manuscripts
+            .iter()
+            .filter_map(|(id, manuscript)| {
+                let review = ExportedReview {
+                    title: manuscript.title.clone(),
+                    rank: rank.get(id)?,
+                    history: Vec::new(),
+                };
+
+                Some((*id, review))
+            })
+            .collect();
+
+        Self {
+            reviewer: reviewer.to_owned(),
+            rubric: R::ID.to_owned(),
+            rubric_version: R::VERSION,
+            exported_at: Timestamp::now(),
+            reviews,
+        }
+    }
+}
+
+impl<T> ReviewExport<T> {
+    /// Include the change history of the reviews.
+    pub fn with_history(mut self, log: &ChangeLog) -> Self {
+        for (id, review) in &mut self.reviews {
+            review.history = log.entries(id).to_vec();
+        }
+
+        self
+    }
+}
+
+impl<T: Serialize> ReviewExport<T> {
+    pub fn to_yaml(&self) -> Result<String, Error> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// The suggested file name of this export.
+    pub fn file_name(&self) -> String {
+        let reviewer = self
+            .reviewer
+            .trim()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect::<String>();
+
+        format!(
+            "review-{}-{}-{}.yaml",
+            if reviewer.is_empty() {
+                "anonymous"
+            } else {
+                &reviewer
+            },
+            self.rubric,
+            self.exported_at.file_stamp(),
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct ExportHeader {
+    rubric: String,
+}
+
+/// Read the rubric ID of an export without reading the reviews.
+pub fn peek_rubric(document: &str) -> Result<String, Error> {
+    let ExportHeader { rubric } = serde_yaml::from_str(document)?;
+    Ok(rubric)
+}
+
+/// How to import the reviews.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImportMode {
+    /// Replace all the current reviews with the imported ones.
+    Restore,
+
+    /// Import the reviews of the manuscripts not reviewed yet,
+    /// keeping the current reviewed ones.
+    Merge,
+}
+
+/// The result of an import.
+#[derive(Default, PartialEq, Eq, Debug)]
+pub struct ImportSummary {
+    /// The number of the imported reviews.
+    pub imported: usize,
+
+    /// The number of the imported reviews ignored
+    /// as the manuscript has been reviewed.
+    pub kept: usize,
+
+    /// The titles of the manuscripts not in the database.
+    pub unknown: Vec<String>,
+}
+
+impl<R: Rubric> ReviewExport<R> {
+    /// Read an export of the rubric `R`, migrating it if needed.
+    pub fn from_yaml(document: &str) -> Result<Self, Error> {
+        let raw: ReviewExport<Value> = serde_yaml::from_str(document)?;
+
+        if raw.rubric != R::ID {
+            return Err(Error::RubricMismatch {
+                found: raw.rubric,
+                expected: R::ID,
+            });
+        }
+
+        let mut titles = HashMap::with_capacity(raw.reviews.len());
+        let mut data = Mapping::with_capacity(raw.reviews.len());
+        for (id, review) in raw.reviews {
+            titles.insert(id, (review.title, review.history));
+            data.insert(serde_yaml::to_value(id)?, review.rank);
+        }
+
+        let data = versioned::migrate::<R>(raw.rubric_version, Value::Mapping(data))?;
+        let Value::Mapping(data) = data else {
+            return Err(Error::Yaml(serde::de::Error::custom(
+                "the migrated reviews are not a mapping",
+            )));
+        };
+
+        let reviews = data
+            .into_iter()
+            .map(|(id, rank)| {
+                let id = serde_yaml::from_value(id)?;
+                let (title, history) = titles.remove(&id).unwrap_or_default();
+                let review = ExportedReview {
+                    title,
+                    rank: serde_yaml::from_value(rank)?,
+                    history,
+                };
+
+                Ok((id, review))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self {
+            reviewer: raw.reviewer,
+            rubric: raw.rubric,
+            rubric_version: R::VERSION,
+            exported_at: raw.exported_at,
+            reviews,
+        })
+    }
+
+    /// Import the reviews to `rank`.
+    ///
+    /// The reviews of the manuscripts not in `manuscripts` are ignored.
+    pub fn import(
+        self,
+        manuscripts: &ManuscriptDatabase,
+        rank: &mut GroupMetaDatabase<R>,
+        mode: ImportMode,
+    ) -> ImportSummary {
+        let mut summary = ImportSummary::default();
+
+        if mode == ImportMode::Restore {
+            rank.clear();
+        }
+
+        for (id, review) in self.reviews {
+            if !manuscripts.contains_key(&id) {
+                summary.unknown.push(review.title);
+                continue;
+            }
+
+            if mode == ImportMode::Merge && rank.get(&id).is_some_and(|r| r.reviewed()) {
+                summary.kept += 1;
+                continue;
+            }
+
+            rank.insert(id, review.rank);
+            summary.imported += 1;
+        }
+
+        summary
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("invalid export: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("failed to migrate the export: {0}")]
+    Versioned(#[from] versioned::Error),
+
+    #[error("the export is of the rubric `{found}`, but the current rubric is `{expected}`")]
+    RubricMismatch {
+        found: String,
+        expected: &'static str,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImportMode, ReviewExport};
+    use crate::types::{
+        rank::{sitcon_gdsc, GroupMetaDatabase, MutableMetaGroup},
+        Manuscript, ManuscriptDatabase,
+    };
+
+    fn manuscripts() -> ManuscriptDatabase {
+        ["A", "B", "C"]
+            .into_iter()
+            .map(|title| {
+                Manuscript::fixture(title, &format!("speaker {title}"), "general (30mins)")
+            })
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    #[test]
+    fn test_round_trip_and_merge() {
+        let manuscripts = manuscripts();
+        let ids = manuscripts.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+
+        let mut exported = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        for id in &ids[..2] {
+            let mut group = sitcon_gdsc::Group::default();
+            *group.reviewed_mut() = true;
+            exported.insert(*id, group);
+        }
+
+        let document = ReviewExport::new("Reviewer 1", &manuscripts, &exported)
+            .to_yaml()
+            .unwrap();
+        assert!(document.contains("title: A"));
+
+        let mut current = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        let mut reviewed = sitcon_gdsc::Group::default();
+        *reviewed.reviewed_mut() = true;
+        current.insert(ids[0], reviewed);
+        current.insert(ids[2], sitcon_gdsc::Group::default());
+
+        let import = ReviewExport::<sitcon_gdsc::Group>::from_yaml(&document).unwrap();
+        assert_eq!(import.reviewer, "Reviewer 1");
+
+        let summary = import.import(&manuscripts, &mut current, ImportMode::Merge);
+        assert_eq!((summary.imported, summary.kept), (1, 1));
+        assert_eq!(current.len(), 3);
+
+        let import = ReviewExport::<sitcon_gdsc::Group>::from_yaml(&document).unwrap();
+        let summary = import.import(&manuscripts, &mut current, ImportMode::Restore);
+        assert_eq!((summary.imported, summary.kept), (2, 0));
+        assert_eq!(current.len(), 2);
+    }
+
+    #[test]
+    fn test_rubric_mismatch() {
+        let document =
+            "reviewer: r\nrubric: other\nrubric_version: 1\nexported_at: 0\nreviews: {}\n";
+
+        assert!(ReviewExport::<sitcon_gdsc::Group>::from_yaml(document).is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! The structured filter of the manuscript list.
+//!
+//! Every condition is optional; a manuscript is listed if it meets all the
+//! active conditions. The filter is persisted, so it is kept across restarts.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::IterableMetaGroup;
+use crate::types::{tag::ManuscriptTags, Difficulty, Manuscript};
+
+/// The filter by the reviewed state.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ReviewedFilter {
+    #[default]
+    All,
+    Reviewed,
+    Unreviewed,
+}
+
+impl ReviewedFilter {
+    pub const ALL: [ReviewedFilter; 3] = [
+        ReviewedFilter::All,
+        ReviewedFilter::Reviewed,
+        ReviewedFilter::Unreviewed,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReviewedFilter::All => "All",
+            ReviewedFilter::Reviewed => "Reviewed",
+            ReviewedFilter::Unreviewed => "Unreviewed",
+        }
+    }
+}
+
+/// The filter of the manuscript list.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct ManuscriptFilter {
+    pub reviewed: ReviewedFilter,
+
+    /// List the manuscripts of these difficulties. Empty means any.
+    pub difficulties: BTreeSet<Difficulty>,
+
+    /// List the manuscripts of these talk types. Empty means any.
+    pub types: BTreeSet<String>,
+
+    /// The total score range. The manuscripts without a review are not
+    /// listed if either bound is set.
+    pub min_score: Option<f64>,
+    pub max_score: Option<f64>,
+
+    /// List the manuscripts with all of these tags.
+    pub tags: BTreeSet<String>,
+
+    /// List the manuscripts I have commented on only.
+    pub commented_only: bool,
+}
+
+impl ManuscriptFilter {
+    /// Is any condition active?
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Clear all the conditions.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Does the manuscript meet all the conditions?
+    pub fn matches<M: IterableMetaGroup>(
+        &self,
+        manuscript: &Manuscript,
+        review: Option<&M>,
+        tags: Option<&ManuscriptTags>,
+    ) -> bool {
+        let reviewed = review.is_some_and(|r| r.reviewed());
+        let listed = match self.reviewed {
+            ReviewedFilter::All => true,
+            ReviewedFilter::Reviewed => reviewed,
+            ReviewedFilter::Unreviewed => !reviewed,
+        };
+        if !listed {
+            return false;
+        }
+
+        if !self.difficulties.is_empty() && !self.difficulties.contains(&manuscript.difficulty) {
+            return false;
+        }
+        if !self.types.is_empty() && !self.types.contains(&manuscript.type_) {
+            return false;
+        }
+
+        if self.min_score.is_some() || self.max_score.is_some() {
+            let Some(total) = review.map(|r| r.total_score()) else {
+                return false;
+            };
+            if self.min_score.is_some_and(|min| total < min)
+                || self.max_score.is_some_and(|max| total > max)
+            {
+                return false;
+            }
+        }
+
+        if !self
+            .tags
+            .iter()
+            .all(|t| tags.is_some_and(|m| m.contains(t)))
+        {
+            return false;
+        }
+
+        if self.commented_only && !review.is_some_and(has_comment) {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// Is there any comment in the review?
+fn has_comment<M: IterableMetaGroup>(review: &M) -> bool {
+    review.groups().iter().any(|(_, group)| {
+        !group.comment().trim().is_empty()
+            || group
+                .items()
+                .iter()
+                .any(|(_, item)| item.comment().is_some_and(|c| !c.trim().is_empty()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ManuscriptFilter, ReviewedFilter};
+    use crate::types::{
+        rank::{sitcon_gdsc, CommentableItemGroup, IterableMetaGroup},
+        tag::ManuscriptTags,
+        Difficulty, Manuscript,
+    };
+
+    #[test]
+    fn test_matches() {
+        let manuscript = Manuscript::fixture("Rust 入門", "Pan", "Talk");
+        let mut review = sitcon_gdsc::Group {
+            reviewed: true,
+            ..Default::default()
+        };
+        let mut tags = ManuscriptTags::default();
+        tags.insert("rust");
+
+        let mut filter = ManuscriptFilter::default();
+        assert!(!filter.is_active());
+        assert!(filter.matches(&manuscript, None::<&sitcon_gdsc::Group>, None));
+
+        filter.reviewed = ReviewedFilter::Reviewed;
+        filter.difficulties.insert(Difficulty::Easy);
+        filter.types.insert("Talk".to_owned());
+        filter.tags.insert("rust".to_owned());
+        assert!(filter.is_active());
+        assert!(filter.matches(&manuscript, Some(&review), Some(&tags)));
+        assert!(!filter.matches(&manuscript, None::<&sitcon_gdsc::Group>, Some(&tags)));
+        assert!(!filter.matches(&manuscript, Some(&review), None));
+
+        filter.commented_only = true;
+        assert!(!filter.matches(&manuscript, Some(&review), Some(&tags)));
+        review.subject.comment_mut().push_str("Good");
+        assert!(filter.matches(&manuscript, Some(&review), Some(&tags)));
+
+        let total = review.total_score();
+        filter.min_score = Some(total + 1.0);
+        assert!(!filter.matches(&manuscript, Some(&review), Some(&tags)));
+        filter.min_score = Some(total - 1.0);
+        filter.max_score = Some(total);
+        assert!(filter.matches(&manuscript, Some(&review), Some(&tags)));
+
+        filter.clear();
+        filter.reviewed = ReviewedFilter::Unreviewed;
+        assert!(!filter.matches(&manuscript, Some(&review), Some(&tags)));
+    }
+}
@@ -0,0 +1,169 @@
+//! The leaderboard: the manuscripts ranked by their scores.
+
+use std::{cmp::Ordering, collections::HashMap};
+
+use super::sheet::ReviewSheet;
+use crate::types::{Difficulty, ManuscriptDatabase, ManuscriptId};
+
+/// The column to sort the leaderboard by.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum SortKey {
+    Title,
+    Type,
+    Difficulty,
+
+    /// The score of the group at this index.
+    Group(usize),
+
+    #[default]
+    Total,
+    Reviewed,
+}
+
+/// A manuscript in the leaderboard.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LeaderboardRow {
+    pub id: ManuscriptId,
+    pub title: String,
+    pub type_: String,
+    pub difficulty: Difficulty,
+
+    /// The scores of the groups, or `None` if the manuscript has no review.
+    pub scores: Vec<Option<f64>>,
+    pub total: Option<f64>,
+    pub reviewed: bool,
+}
+
+/// The manuscripts with their scores.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Leaderboard {
+    /// The names of the groups.
+    pub groups: Vec<String>,
+    pub rows: Vec<LeaderboardRow>,
+}
+
+impl Leaderboard {
+    /// Tabulate the reviews. The groups follow `layout`, like [`ReviewSheet::group`].
+    pub fn new(
+        manuscripts: &ManuscriptDatabase,
+        layout: &ReviewSheet,
+        reviews: &HashMap<ManuscriptId, ReviewSheet>,
+    ) -> Self {
+        let rows = manuscripts
+            .iter()
+            .map(|(id, manuscript)| {
+                let review = reviews.get(id);
+
+                LeaderboardRow {
+                    id: *id,
+                    title: manuscript.title.clone(),
+                    type_: manuscript.type_.clone(),
+                    difficulty: manuscript.difficulty,
+                    scores: layout
+                        .groups
+                        .iter()
+                        .map(|g| Some(review?.group(g.key)?.score))
+                        .collect(),
+                    total: review.map(|r| r.total),
+                    reviewed: review.is_some_and(|r| r.reviewed),
+                }
+            })
+            .collect();
+
+        Self {
+            groups: layout.groups.iter().map(|g| g.name.clone()).collect(),
+            rows,
+        }
+    }
+
+    /// Sort the rows by the column. The manuscripts without a score are always the last,
+    /// and the ties keep the order of the manuscripts.
+    pub fn sort(&mut self, key: SortKey, descending: bool) {
+        let order = |ordering: Ordering| {
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        };
+        let by_score = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => order(a.total_cmp(&b)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+
+        self.rows.sort_by(|a, b| match key {
+            SortKey::Title => order(a.title.cmp(&b.title)),
+            SortKey::Type => order(a.type_.cmp(&b.type_)),
+            SortKey::Difficulty => order(a.difficulty.cmp(&b.difficulty)),
+            SortKey::Group(i) => by_score(
+                a.scores.get(i).copied().flatten(),
+                b.scores.get(i).copied().flatten(),
+            ),
+            SortKey::Total => by_score(a.total, b.total),
+            SortKey::Reviewed => order(a.reviewed.cmp(&b.reviewed)),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{Leaderboard, SortKey};
+    use crate::types::{
+        rank::{sheet::ReviewSheet, sitcon_gdsc, Item, StandardChoice},
+        Difficulty, Manuscript, ManuscriptDatabase,
+    };
+
+    #[test]
+    fn test_sort() {
+        let manuscripts: ManuscriptDatabase = [
+            ("B", Difficulty::Hard),
+            ("A", Difficulty::Easy),
+            ("C", Difficulty::Medium),
+        ]
+        .into_iter()
+        .map(|(title, difficulty)| Manuscript {
+            difficulty,
+            ..Manuscript::fixture(title, "", "")
+        })
+        .collect::<Vec<_>>()
+        .into();
+        let ids = manuscripts.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+
+        let full = sitcon_gdsc::Group {
+            reviewed: true,
+            ..Default::default()
+        };
+        let mut poor = sitcon_gdsc::Group::default();
+        *poor.subject.floss_related.choice_mut() = StandardChoice::No;
+        let reviews = HashMap::from([
+            (ids[0], ReviewSheet::of(&poor)),
+            (ids[1], ReviewSheet::of(&full)),
+        ]);
+
+        let mut leaderboard = Leaderboard::new(&manuscripts, &ReviewSheet::of(&full), &reviews);
+        let order = |leaderboard: &Leaderboard| {
+            leaderboard
+                .rows
+                .iter()
+                .map(|r| r.title.clone())
+                .collect::<Vec<_>>()
+        };
+
+        leaderboard.sort(SortKey::Total, true);
+        assert_eq!(order(&leaderboard), ["A", "B", "C"]);
+        // the manuscript without a score is still the last.
+        leaderboard.sort(SortKey::Total, false);
+        assert_eq!(order(&leaderboard), ["B", "A", "C"]);
+        leaderboard.sort(SortKey::Group(0), false);
+        assert_eq!(order(&leaderboard), ["B", "A", "C"]);
+        leaderboard.sort(SortKey::Difficulty, true);
+        assert_eq!(order(&leaderboard), ["B", "C", "A"]);
+        leaderboard.sort(SortKey::Reviewed, true);
+        assert_eq!(order(&leaderboard), ["A", "B", "C"]);
+        assert!(leaderboard.rows[0].reviewed);
+    }
+}
@@ -0,0 +1,108 @@
+//! The registry of the rubrics.
+//!
+//! To add a rubric, implement [`Rubric`] for its meta group, register it in
+//! [`RUBRICS`], and register its review app in `crate::ui::registry`.
+
+use serde_json::Value;
+
+use super::{
+    export::{self, ReviewExport},
+    sheet::{ReviewSheet, ReviewerSheets},
+    sitcon_gdsc, sync, Rubric,
+};
+use crate::types::ManuscriptId;
+
+type ReadExportFn = fn(&str) -> Result<ReviewerSheets, export::Error>;
+type BlankSheetFn = fn() -> ReviewSheet;
+type CheckReviewFn = fn(&ManuscriptId, &Value, u32) -> Result<(), sync::Error>;
+
+/// A registered rubric.
+pub struct RubricEntry {
+    /// The ID of this rubric. See [`Rubric::ID`].
+    pub id: &'static str,
+
+    /// The name of this rubric. See [`Rubric::NAME`].
+    pub name: &'static str,
+
+    read_export: ReadExportFn,
+    blank_sheet: BlankSheetFn,
+    check_review: CheckReviewFn,
+}
+
+impl RubricEntry {
+    pub const fn of<R: Rubric>() -> Self {
+        Self {
+            id: R::ID,
+            name: R::NAME,
+            read_export: read_export_of::<R>,
+            blank_sheet: blank_sheet_of::<R>,
+            check_review: check_review_of::<R>,
+        }
+    }
+
+    /// Read an export of this rubric as the review sheets.
+    pub fn read_export(&self, document: &str) -> Result<ReviewerSheets, export::Error> {
+        (self.read_export)(document)
+    }
+
+    /// The sheet of a blank review, which lists the groups and the items of this rubric.
+    pub fn blank_sheet(&self) -> ReviewSheet {
+        (self.blank_sheet)()
+    }
+
+    /// Check that the serialized review of `id` at `rubric_version` is one of this rubric.
+    pub fn check_review(
+        &self,
+        id: &ManuscriptId,
+        review: &Value,
+        rubric_version: u32,
+    ) -> Result<(), sync::Error> {
+        (self.check_review)(id, review, rubric_version)
+    }
+}
+
+fn read_export_of<R: Rubric>(document: &str) -> Result<ReviewerSheets, export::Error> {
+    ReviewExport::<R>::from_yaml(document).map(ReviewerSheets::from)
+}
+
+fn blank_sheet_of<R: Rubric>() -> ReviewSheet {
+    ReviewSheet::of(&R::default())
+}
+
+fn check_review_of<R: Rubric>(
+    id: &ManuscriptId,
+    review: &Value,
+    rubric_version: u32,
+) -> Result<(), sync::Error> {
+    sync::read_review::<R>(id, review, rubric_version).map(drop)
+}
+
+/// The available rubrics. The first one is the default.
+pub const RUBRICS: &[RubricEntry] = &[RubricEntry::of::<sitcon_gdsc::Group>()];
+
+/// Find the rubric by its ID.
+pub fn find(id: &str) -> Option<&'static RubricEntry> {
+    RUBRICS.iter().find(|r| r.id == id)
+}
+
+/// The default rubric.
+pub fn default_rubric() -> &'static RubricEntry {
+    &RUBRICS[0]
+}
+
+/// Read an export of any registered rubric as the review sheets.
+pub fn read_export(document: &str) -> Result<ReviewerSheets, Error> {
+    let id = export::peek_rubric(document)?;
+    let rubric = find(&id).ok_or(Error::UnknownRubric(id))?;
+
+    Ok(rubric.read_export(document)?)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("unknown rubric `{0}`; available: {}", RUBRICS.iter().map(|r| r.id).collect::<Vec<_>>().join(", "))]
+    UnknownRubric(String),
+
+    #[error(transparent)]
+    Export(#[from] export::Error),
+}
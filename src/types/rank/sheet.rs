@@ -0,0 +1,109 @@
+//! The review sheet: a review flattened from its meta group.
+//!
+//! The sheets of any rubric have the same shape, so the reviews
+//! of several reviewers can be compared without knowing the rubric.
+
+use std::collections::HashMap;
+
+use super::{export::ReviewExport, IterableMetaGroup, Rubric, StandardChoice};
+use crate::types::{timestamp::Timestamp, ManuscriptId};
+
+/// An item of a [`GroupSheet`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct ItemSheet {
+    /// The serialized key of this item.
+    pub key: &'static str,
+    pub name: String,
+    pub choice: StandardChoice,
+    pub comment: Option<String>,
+}
+
+/// A group of a [`ReviewSheet`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct GroupSheet {
+    /// The serialized key of this group.
+    pub key: &'static str,
+    pub name: String,
+    pub score: f64,
+    pub comment: String,
+    pub items: Vec<ItemSheet>,
+}
+
+/// The review of a manuscript.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReviewSheet {
+    pub reviewed: bool,
+    pub total: f64,
+    pub groups: Vec<GroupSheet>,
+}
+
+impl ReviewSheet {
+    /// Flatten the meta group.
+    pub fn of<M: IterableMetaGroup + ?Sized>(rank: &M) -> Self {
+        let groups = rank
+            .groups()
+            .into_iter()
+            .map(|(key, group)| GroupSheet {
+                key,
+                name: group.name().to_owned(),
+                score: group.score(),
+                comment: group.comment().to_owned(),
+                items: group
+                    .items()
+                    .into_iter()
+                    .map(|(key, item)| ItemSheet {
+                        key,
+                        name: item.name().to_owned(),
+                        choice: item.choice(),
+                        comment: item.comment().map(ToOwned::to_owned),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            reviewed: rank.reviewed(),
+            total: rank.total_score(),
+            groups,
+        }
+    }
+
+    /// The group of `key`.
+    pub fn group(&self, key: &str) -> Option<&GroupSheet> {
+        self.groups.iter().find(|g| g.key == key)
+    }
+}
+
+/// The reviews of a reviewer.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReviewerSheets {
+    pub reviewer: String,
+
+    /// The ID of the rubric.
+    pub rubric: String,
+
+    pub exported_at: Timestamp,
+    pub reviews: HashMap<ManuscriptId, ReviewSheet>,
+}
+
+impl ReviewerSheets {
+    /// The review of the manuscript, if it has been reviewed.
+    pub fn reviewed(&self, id: &ManuscriptId) -> Option<&ReviewSheet> {
+        self.reviews.get(id).filter(|r| r.reviewed)
+    }
+}
+
+impl<R: Rubric> From<ReviewExport<R>> for ReviewerSheets {
+    fn from(export: ReviewExport<R>) -> Self {
+        Self {
+            reviewer: export.reviewer,
+            rubric: export.rubric,
+            exported_at: export.exported_at,
+            reviews: export
+                .reviews
+                .into_iter()
+                .map(|(id, review)| (id, ReviewSheet::of(&review.rank)))
+                .collect(),
+        }
+    }
+}
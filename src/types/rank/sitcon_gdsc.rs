@@ -2,11 +2,14 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::{MetaGroup, MutableMetaGroup};
+use super::{
+    versioned::{Migration, Versioned},
+    IterableItemGroup, IterableMetaGroup, MetaGroup, MutableMetaGroup, Rubric,
+};
 
 /// 主題相關：和學生、社群以及程式相關的議題，且與 FLOSS（自由/開放原始碼軟體）相關。
 pub mod subject {
-    use crate::types::rank::{Item, ItemGroup, StandardChoice};
+    use crate::types::rank::{Item, ItemGroup, ScoreBreakdown, StandardChoice};
 
     super::new_rank!(StudentRelated, "和學生相關", None);
     super::new_rank!(CommunityRelated, "和社群相關", None);
@@ -30,50 +33,56 @@ pub mod subject {
         }
 
         fn score(&self) -> f64 {
-            let topic_score = [
-                self.student_related.choice(),
-                self.community_related.choice(),
-                self.coding_related.choice(),
-            ]
-            .iter()
-            .map(|c| match c {
+            self.breakdown().total()
+        }
+
+        fn score_breakdown(&self) -> Option<ScoreBreakdown> {
+            Some(self.breakdown())
+        }
+    }
+
+    impl Group {
+        fn breakdown(&self) -> ScoreBreakdown {
+            let topic_points = |c| match c {
                 StandardChoice::Full => 3.5,
                 StandardChoice::Partial => 3.0,
                 StandardChoice::Maybe => 1.5,
                 StandardChoice::No => 0.0,
-            })
-            .sum::<f64>()
-            .min(7.0);
-
-            let floss_score = {
-                match self.floss_related.choice() {
-                    StandardChoice::Full => 3.0,
-                    StandardChoice::Partial => 1.5,
-                    StandardChoice::Maybe => 0.0,
-                    StandardChoice::No => 0.0,
-                }
             };
 
-            topic_score + floss_score
-        }
-
-        fn score_description(&self) -> Option<String> {
-            let result = format!(
-                "和學生{stu}、社群{com}以及程式{cod}相關的議題，且與 FLOSS（自由/開放原始碼軟體）相關{fls}。",
-                stu=self.student_related.choice().as_emoji(),
-                com=self.community_related.choice().as_emoji(),
-                cod=self.coding_related.choice().as_emoji(),
-                fls=self.floss_related.choice().as_emoji(),
-            );
+            let floss_points = |c| match c {
+                StandardChoice::Full => 3.0,
+                StandardChoice::Partial => 1.5,
+                StandardChoice::Maybe => 0.0,
+                StandardChoice::No => 0.0,
+            };
 
-            Some(result)
+            ScoreBreakdown::default()
+                .item(
+                    &self.student_related,
+                    topic_points(self.student_related.choice()),
+                )
+                .item(
+                    &self.community_related,
+                    topic_points(self.community_related.choice()),
+                )
+                .item(
+                    &self.coding_related,
+                    topic_points(self.coding_related.choice()),
+                )
+                .cap("學生、社群、程式最多 7 分", 7.0)
+                .part()
+                .item(
+                    &self.floss_related,
+                    floss_points(self.floss_related.choice()),
+                )
         }
     }
 }
 
 /// 表達能力：提供的資料是否有條理、文句暢通，以及提供資料之完整度。完整的資料能讓審稿委員更清楚了解演講細節。
 pub mod expressive {
-    use crate::types::rank::{Item, ItemGroup, StandardChoice};
+    use crate::types::rank::{Item, ItemGroup, ScoreBreakdown, StandardChoice};
 
     super::new_rank!(Organized, "資料有條理", None);
     super::new_rank!(Fluent, "文句暢通", None);
@@ -91,38 +100,36 @@ pub mod expressive {
         }
 
         fn score(&self) -> f64 {
-            // 三點各佔 3.3%，四捨五入
+            self.breakdown().total()
+        }
+
+        fn score_breakdown(&self) -> Option<ScoreBreakdown> {
+            Some(self.breakdown())
+        }
+    }
 
-            [
-                self.organized.choice(),
-                self.fluent.choice(),
-                self.completeness.choice(),
-            ]
-            .into_iter()
-            .map(|c| match c {
+    impl Group {
+        fn breakdown(&self) -> ScoreBreakdown {
+            // 三點各佔 3.3%，四捨五入
+            let points = |c| match c {
                 StandardChoice::Full => 3.3,
                 StandardChoice::Partial => 2.5,
                 StandardChoice::Maybe => 1.5,
                 StandardChoice::No => 0.0,
-            })
-            .sum::<f64>()
-            .round()
-        }
+            };
 
-        fn score_description(&self) -> Option<String> {
-            Some(format!(
-                "提供的資料是否有條理 {org}、文句暢通 {flu}，以及提供資料之完整度 {com}。",
-                org = self.organized.choice.as_emoji(),
-                flu = self.fluent.choice.as_emoji(),
-                com = self.completeness.choice.as_emoji()
-            ))
+            ScoreBreakdown::default()
+                .item(&self.organized, points(self.organized.choice()))
+                .item(&self.fluent, points(self.fluent.choice()))
+                .item(&self.completeness, points(self.completeness.choice()))
+                .round("四捨五入")
         }
     }
 }
 
 /// 稿件內容：知識分享、經驗案例、想法觀點是否獨特等。另外也包括稿件的結構及資料完整性是否充足。
 pub mod content {
-    use crate::types::rank::{Item, ItemGroup, StandardChoice};
+    use crate::types::rank::{Item, ItemGroup, ScoreBreakdown, StandardChoice};
 
     super::new_rank!(Knowledges, "知識分享", None);
     super::new_rank!(Experiences, "經驗案例", None);
@@ -142,26 +149,26 @@ pub mod content {
         }
 
         fn score(&self) -> f64 {
-            // 知識、經驗、獨特性 3 選 2 5%，三者都有 7%
+            self.breakdown().total()
+        }
 
-            let direction_score = [
-                self.knowledges.choice(),
-                self.experiences.choice(),
-                self.uniqueness.choice(),
-            ]
-            .into_iter()
-            .map(|c| match c {
+        fn score_breakdown(&self) -> Option<ScoreBreakdown> {
+            Some(self.breakdown())
+        }
+    }
+
+    impl Group {
+        fn breakdown(&self) -> ScoreBreakdown {
+            // 知識、經驗、獨特性 3 選 2 5%，三者都有 7%
+            let direction_points = |c| match c {
                 StandardChoice::Full => 2.5,
                 StandardChoice::Partial => 2.0,
                 StandardChoice::Maybe => 1.0,
                 StandardChoice::No => 0.0,
-            })
-            .sum::<f64>()
-            .round()
-            .min(7.0);
+            };
 
             // 結構佔 2%
-            let structure_score = match self.structure.choice() {
+            let structure_points = match self.structure.choice() {
                 StandardChoice::Full => 2.0,
                 StandardChoice::Partial => 1.0,
                 StandardChoice::Maybe => 0.5,
@@ -169,28 +176,33 @@ pub mod content {
             };
 
             // 資料如果非常充足且受到評審喜歡，可以多給 1%
-            let completeness_score = match self.completeness.choice() {
+            let completeness_points = match self.completeness.choice() {
                 StandardChoice::Full => 1.0,
                 _ => 0.0,
             };
 
-            direction_score + structure_score + completeness_score
-        }
-
-        fn score_description(&self) -> Option<String> {
-            Some(format!(
-                "知識分享 {know}、經驗案例 {exp}、想法觀點是否獨特 {uniq} 等。另外也包括稿件的結構 {stru} 及資料完整性是否充足 {com}。",
-                know = self.knowledges.choice.as_emoji(),
-                exp = self.experiences.choice.as_emoji(),
-                uniq = self.uniqueness.choice.as_emoji(),
-                stru = self.structure.choice.as_emoji(),
-                com = self.completeness.choice.as_emoji()
-            ))
+            ScoreBreakdown::default()
+                .item(&self.knowledges, direction_points(self.knowledges.choice()))
+                .item(
+                    &self.experiences,
+                    direction_points(self.experiences.choice()),
+                )
+                .item(&self.uniqueness, direction_points(self.uniqueness.choice()))
+                .round("知識、經驗、獨特性四捨五入")
+                .cap("知識、經驗、獨特性最多 7 分", 7.0)
+                .part()
+                .item(&self.structure, structure_points)
+                .item(&self.completeness, completeness_points)
         }
     }
 }
 
+/// The review of a manuscript.
+///
+/// The missing keys are defaulted, but the unknown keys are rejected, so a
+/// renamed key fails to load and the document is backed up.
 #[derive(Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Group {
     /// Have we completed the review of this group?
     pub reviewed: bool,
@@ -212,10 +224,45 @@ impl MutableMetaGroup for Group {
     }
 }
 
+impl IterableMetaGroup for Group {
+    fn groups(&self) -> Vec<(&'static str, &dyn IterableItemGroup)> {
+        vec![
+            ("subject", &self.subject),
+            ("content", &self.content),
+            ("expressive", &self.expressive),
+        ]
+    }
+
+    fn groups_mut(&mut self) -> Vec<(&'static str, &mut dyn IterableItemGroup)> {
+        vec![
+            ("subject", &mut self.subject),
+            ("content", &mut self.content),
+            ("expressive", &mut self.expressive),
+        ]
+    }
+}
+
+impl Rubric for Group {
+    const ID: &'static str = "sitcon-gdsc";
+    const NAME: &'static str = "SITCON@GDSC (Pan rules)";
+}
+
+impl Versioned for Group {
+    const VERSION: u32 = 1;
+
+    fn migrations() -> &'static [Migration] {
+        &[
+            // 0 → 1: the data is unchanged; only the document is versioned.
+            Ok,
+        ]
+    }
+}
+
 macro_rules! new_rank {
     ($name:ident, $display_name:expr, $description:expr) => {
         ::paste::paste! {
             #[derive(Default, ::serde::Serialize, ::serde::Deserialize, Hash, Eq, PartialEq, Debug)]
+            #[serde(default, deny_unknown_fields)]
             pub struct $name {
                 comment: Option<String>,
                 choice: crate::types::rank::StandardChoice,
@@ -254,6 +301,7 @@ macro_rules! new_group {
     ($($entries:ident),+) => {
         ::paste::paste! {
             #[derive(Default, ::serde::Serialize, ::serde::Deserialize, Hash, Eq, PartialEq, Debug)]
+            #[serde(default, deny_unknown_fields)]
             pub struct Group {
                 $(
                     pub [< $entries:snake >]: $entries,
@@ -273,6 +321,24 @@ macro_rules! new_group {
                     &mut self.comment
                 }
             }
+
+            impl crate::types::rank::IterableItemGroup for Group {
+                fn items(&self) -> Vec<(&'static str, &dyn crate::types::rank::Item)> {
+                    vec![
+                        $(
+                            (stringify!([< $entries:snake >]), &self.[< $entries:snake >]),
+                        )+
+                    ]
+                }
+
+                fn items_mut(&mut self) -> Vec<(&'static str, &mut dyn crate::types::rank::Item)> {
+                    vec![
+                        $(
+                            (stringify!([< $entries:snake >]), &mut self.[< $entries:snake >]),
+                        )+
+                    ]
+                }
+            }
         }
     }
 }
@@ -0,0 +1,208 @@
+//! The snapshots of the reviews, taken before they are reset or replaced.
+//!
+//! A [`Snapshot`] keeps the versioned document of the ranks (see
+//! [`versioned`]), so it can be restored after the app is restarted, even
+//! if the rubric has been migrated since.
+//!
+//! A partial snapshot keeps some reviews only, such as the one reset alone,
+//! and restoring it puts back those reviews only. A protected snapshot is
+//! taken before the reviewer resets or restores all the reviews.
+//!
+//! The newest [`SNAPSHOT_LIMIT`] snapshots of every kind – partial,
+//! protected, and the others taken before the syncs, the imports, the merges
+//! and so on – are kept, so neither the resets of single reviews nor the
+//! routine snapshots ever push out the snapshot before a reset.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{
+    versioned::{self, Versioned},
+    GroupMetaDatabase, MetaGroup,
+};
+use crate::types::timestamp::Timestamp;
+
+/// The number of the snapshots to keep.
+pub const SNAPSHOT_LIMIT: usize = 20;
+
+/// The reviews as of a moment.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Snapshot {
+    pub taken_at: Timestamp,
+
+    /// Why the snapshot was taken, such as `Before resetting all the reviews`.
+    pub reason: String,
+
+    /// The number of the reviewed manuscripts.
+    pub reviewed: usize,
+
+    /// The number of the reviews.
+    pub total: usize,
+
+    /// The versioned document of the ranks.
+    pub rank: String,
+
+    /// Are these some reviews only, rather than all of them?
+    #[serde(default)]
+    pub partial: bool,
+
+    /// Is it taken before resetting or restoring all the reviews?
+    #[serde(default)]
+    pub protected: bool,
+}
+
+impl Snapshot {
+    pub fn take<M: MetaGroup + Versioned + Serialize>(
+        rank: &GroupMetaDatabase<M>,
+        reason: &str,
+        at: Timestamp,
+    ) -> Result<Self, versioned::Error> {
+        Ok(Self {
+            taken_at: at,
+            reason: reason.to_owned(),
+            reviewed: rank.values().filter(|m| m.reviewed()).count(),
+            total: rank.len(),
+            rank: versioned::serialize(rank)?,
+            partial: false,
+            protected: false,
+        })
+    }
+
+    /// Take a snapshot of some reviews only.
+    pub fn take_partial<M: MetaGroup + Versioned + Serialize>(
+        rank: &GroupMetaDatabase<M>,
+        reason: &str,
+        at: Timestamp,
+    ) -> Result<Self, versioned::Error> {
+        Ok(Self {
+            partial: true,
+            ..Self::take(rank, reason, at)?
+        })
+    }
+
+    /// The kind of the snapshot, which only pushes out the snapshots of its kind.
+    fn kind(&self) -> (bool, bool) {
+        (self.partial, self.protected)
+    }
+
+    pub fn restore<M: MetaGroup + Versioned + DeserializeOwned>(
+        &self,
+    ) -> Result<GroupMetaDatabase<M>, versioned::Error> {
+        versioned::deserialize(&self.rank)
+    }
+}
+
+/// The snapshots, from the oldest.
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq, Debug)]
+pub struct SnapshotHistory(Vec<Snapshot>);
+
+impl SnapshotHistory {
+    /// Keep a snapshot. The oldest one of the same kind (partial, protected
+    /// or neither) is dropped if there are too many.
+    pub fn push(&mut self, snapshot: Snapshot) {
+        let kind = snapshot.kind();
+        self.0.push(snapshot);
+
+        if self.0.iter().filter(|s| s.kind() == kind).count() > SNAPSHOT_LIMIT {
+            if let Some(oldest) = self.0.iter().position(|s| s.kind() == kind) {
+                self.0.remove(oldest);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Snapshot> {
+        (index < self.0.len()).then(|| self.0.remove(index))
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Snapshot> {
+        self.0.get(index)
+    }
+
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Snapshot, SnapshotHistory, SNAPSHOT_LIMIT};
+    use crate::types::{
+        rank::{sitcon_gdsc, GroupMetaDatabase},
+        timestamp::Timestamp,
+        ManuscriptId,
+    };
+
+    #[test]
+    fn test_snapshot() {
+        let id = ManuscriptId([1; 32]);
+        let mut rank = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        rank.insert(
+            id,
+            sitcon_gdsc::Group {
+                reviewed: true,
+                ..Default::default()
+            },
+        );
+        rank.insert(ManuscriptId([2; 32]), Default::default());
+
+        let mut history = SnapshotHistory::default();
+        for at in 0..SNAPSHOT_LIMIT as u64 + 1 {
+            history.push(Snapshot::take(&rank, "Before resetting", Timestamp(at)).unwrap());
+        }
+        assert_eq!(history.len(), SNAPSHOT_LIMIT);
+        assert_eq!(history.get(0).unwrap().taken_at, Timestamp(1));
+
+        // The partial snapshots do not push out the full ones.
+        rank.remove(&ManuscriptId([2; 32]));
+        for at in 100..100 + SNAPSHOT_LIMIT as u64 + 1 {
+            history.push(
+                Snapshot::take_partial(&rank, "Before resetting one", Timestamp(at)).unwrap(),
+            );
+        }
+        assert_eq!(history.len(), SNAPSHOT_LIMIT * 2);
+        assert_eq!(history.get(0).unwrap().taken_at, Timestamp(1));
+        assert!(history.get(SNAPSHOT_LIMIT).unwrap().partial);
+        assert_eq!(
+            history.get(SNAPSHOT_LIMIT).unwrap().taken_at,
+            Timestamp(101)
+        );
+
+        let snapshot = history.remove(0).unwrap();
+        assert_eq!((snapshot.reviewed, snapshot.total), (1, 2));
+        assert!(!snapshot.partial);
+        let restored = snapshot.restore::<sitcon_gdsc::Group>().unwrap();
+        assert_eq!(restored.len(), 2);
+        assert!(restored[&id].reviewed);
+        assert!(history.remove(SNAPSHOT_LIMIT * 2).is_none());
+    }
+
+    #[test]
+    fn test_keep_reset_snapshot() {
+        let mut rank = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        rank.insert(ManuscriptId([1; 32]), Default::default());
+
+        let mut history = SnapshotHistory::default();
+        history.push(Snapshot {
+            protected: true,
+            ..Snapshot::take(&rank, "Before resetting all the reviews", Timestamp(0)).unwrap()
+        });
+
+        // The syncs do not push out the snapshot before the reset.
+        for at in 1..=SNAPSHOT_LIMIT as u64 * 2 {
+            history.push(Snapshot::take(&rank, "Before syncing", Timestamp(at)).unwrap());
+        }
+        assert_eq!(history.len(), SNAPSHOT_LIMIT + 1);
+        assert!(history.get(0).unwrap().protected);
+        assert_eq!(
+            history.get(1).unwrap().taken_at,
+            Timestamp(SNAPSHOT_LIMIT as u64 + 1)
+        );
+    }
+}
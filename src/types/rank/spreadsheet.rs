@@ -0,0 +1,205 @@
+//! The spreadsheet of the scores and the comments, for the committee.
+//!
+//! It has a row per manuscript, and is written as CSV or XLSX. The CSV text
+//! cells which a spreadsheet app would run as formulas are prefixed with `'`.
+
+use std::collections::HashMap;
+
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+
+use super::sheet::ReviewSheet;
+use crate::types::{ManuscriptDatabase, ManuscriptId};
+
+/// The byte order mark, without which Excel reads a UTF-8 CSV as ANSI
+/// and garbles the CJK text.
+const UTF8_BOM: &str = "\u{feff}";
+
+/// The leading characters with which a spreadsheet app reads a CSV cell as a formula.
+const FORMULA_PREFIXES: [char; 4] = ['=', '+', '-', '@'];
+
+/// The width of the text columns in the XLSX output, in characters.
+const TEXT_COLUMN_WIDTH: f64 = 24.0;
+
+/// A cell of a [`Spreadsheet`].
+#[derive(Clone, PartialEq, Debug)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+
+    /// The cell of a manuscript which has not been reviewed.
+    Empty,
+}
+
+/// The scores and the comments of every manuscript.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Spreadsheet {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Spreadsheet {
+    /// Tabulate the reviews of the manuscripts.
+    ///
+    /// The columns of the groups and the items follow `layout`,
+    /// usually the sheet of a blank review of the rubric.
+    pub fn new(
+        manuscripts: &ManuscriptDatabase,
+        layout: &ReviewSheet,
+        reviews: &HashMap<ManuscriptId, ReviewSheet>,
+    ) -> Self {
+        let mut header = ["Title", "Speaker", "Type", "Difficulty", "Reviewed"]
+            .map(String::from)
+            .to_vec();
+        for group in &layout.groups {
+            for item in &group.items {
+                header.push(format!("{} / {}", group.name, item.name));
+            }
+            header.push(format!("{} score", group.name));
+            header.push(format!("{} comment", group.name));
+        }
+        header.push("Total".to_owned());
+
+        let rows = manuscripts
+            .iter()
+            .map(|(id, manuscript)| {
+                let review = reviews.get(id);
+
+                let mut row = vec![
+                    Cell::Text(manuscript.title.clone()),
+                    Cell::Text(manuscript.author.name.clone()),
+                    Cell::Text(manuscript.type_.clone()),
+                    Cell::Text(manuscript.difficulty.to_string()),
+                    Cell::Text(if review.is_some_and(|r| r.reviewed) {
+                        "Yes".to_owned()
+                    } else {
+                        "No".to_owned()
+                    }),
+                ];
+                for group in &layout.groups {
+                    let reviewed = review.and_then(|r| r.group(group.key));
+
+                    for item in &group.items {
+                        row.push(
+                            reviewed
+                                .and_then(|g| g.items.iter().find(|i| i.key == item.key))
+                                .map_or(Cell::Empty, |i| Cell::Text(i.choice.as_ref().to_owned())),
+                        );
+                    }
+                    row.push(reviewed.map_or(Cell::Empty, |g| Cell::Number(g.score)));
+                    row.push(reviewed.map_or(Cell::Empty, |g| Cell::Text(g.comment.clone())));
+                }
+                row.push(review.map_or(Cell::Empty, |r| Cell::Number(r.total)));
+
+                row
+            })
+            .collect();
+
+        Self { header, rows }
+    }
+
+    /// Write as CSV, with a byte order mark for Excel.
+    pub fn to_csv(&self) -> Result<String, csv::Error> {
+        let mut writer = csv::Writer::from_writer(UTF8_BOM.as_bytes().to_vec());
+
+        writer.write_record(&self.header)?;
+        for row in &self.rows {
+            writer.write_record(row.iter().map(|cell| match cell {
+                Cell::Text(text) => escape_formula(text),
+                Cell::Number(number) => number.to_string(),
+                Cell::Empty => String::new(),
+            }))?;
+        }
+
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| csv::Error::from(e.into_error()))?;
+        // Only strings have been written.
+        Ok(String::from_utf8(bytes).expect("valid UTF-8"))
+    }
+
+    /// Write as an XLSX workbook.
+    pub fn to_xlsx(&self) -> Result<Vec<u8>, XlsxError> {
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("Reviews")?;
+        let bold = Format::new().set_bold();
+
+        for (column, title) in (0..).zip(&self.header) {
+            worksheet.write_string_with_format(0, column, title, &bold)?;
+            worksheet.set_column_width(column, TEXT_COLUMN_WIDTH)?;
+        }
+        for (row, cells) in (1..).zip(&self.rows) {
+            for (column, cell) in (0..).zip(cells) {
+                match cell {
+                    Cell::Text(text) => {
+                        worksheet.write_string(row, column, text)?;
+                    }
+                    Cell::Number(number) => {
+                        worksheet.write_number(row, column, *number)?;
+                    }
+                    Cell::Empty => {}
+                }
+            }
+        }
+        worksheet.set_freeze_panes(1, 1)?;
+
+        workbook.save_to_buffer()
+    }
+}
+
+/// Prefix the text read as a formula with `'`, so a comment such as
+/// `=HYPERLINK(…)` is shown as it is rather than run.
+fn escape_formula(text: &str) -> String {
+    if text.starts_with(FORMULA_PREFIXES) {
+        format!("'{text}")
+    } else {
+        text.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{Cell, Spreadsheet};
+    use crate::types::{
+        rank::{sheet::ReviewSheet, sitcon_gdsc, CommentableItemGroup},
+        Manuscript, ManuscriptDatabase,
+    };
+
+    #[test]
+    fn test_spreadsheet() {
+        let manuscripts: ManuscriptDatabase = ["開源", "教育"]
+            .iter()
+            .map(|title| Manuscript::fixture(title, "小明", "Talk"))
+            .collect::<Vec<_>>()
+            .into();
+        let (id, _) = manuscripts.iter().next().unwrap();
+
+        let mut rank = sitcon_gdsc::Group::default();
+        *rank.subject.comment_mut() = "很好, \"值得\"一聽".to_owned();
+        *rank.content.comment_mut() = "=HYPERLINK(\"http://evil.example\")".to_owned();
+        let layout = ReviewSheet::of(&sitcon_gdsc::Group::default());
+        let reviews = HashMap::from([(*id, ReviewSheet::of(&rank))]);
+
+        let spreadsheet = Spreadsheet::new(&manuscripts, &layout, &reviews);
+        assert_eq!(spreadsheet.rows.len(), 2);
+        assert!(spreadsheet
+            .rows
+            .iter()
+            .all(|row| row.len() == spreadsheet.header.len()));
+        assert_eq!(
+            spreadsheet.rows[0].last(),
+            Some(&Cell::Number(ReviewSheet::of(&rank).total))
+        );
+        assert_eq!(spreadsheet.rows[1].last(), Some(&Cell::Empty));
+
+        let csv = spreadsheet.to_csv().unwrap();
+        assert!(csv.starts_with("\u{feff}Title,"));
+        assert!(csv.contains("\"很好, \"\"值得\"\"一聽\""));
+        assert!(csv.contains("\"'=HYPERLINK(\"\"http://evil.example\"\")\""));
+        assert!(!csv.contains(",=HYPERLINK"));
+
+        let xlsx = spreadsheet.to_xlsx().unwrap();
+        assert!(xlsx.starts_with(b"PK"));
+    }
+}
@@ -0,0 +1,532 @@
+//! The synchronization of the reviews with a sync server.
+//!
+//! The server stores the reviews of every reviewer of every rubric, and
+//! every review (the meta group of a manuscript) carries a revision.
+//! A client sends the reviews changed since the last sync, with the
+//! revision each change is based on:
+//!
+//! - If the base is the current revision on the server, the change is accepted.
+//! - Otherwise the review has been changed by another client in the meantime.
+//!   It is a [`Conflict`], and the last writer wins: the change edited later
+//!   is kept. A change carries the time the review was last edited on the
+//!   client (see [`EditTimes`]), not the time of the sync, so a stale edit
+//!   synced late does not overwrite a newer one.
+//!
+//! Either way, the server replies with all the reviews of the reviewer,
+//! which the client adopts as the new base.
+//!
+//! The API is:
+//!
+//! - `GET /api/reviews/<rubric>`: the reviews of every reviewer, as [`Reviewers`].
+//! - `POST /api/sync/<rubric>/<reviewer>`: a [`SyncRequest`], replied with a [`SyncResponse`].
+//!   A change of an unknown manuscript, or one which is not a review of the
+//!   rubric at the version, rejects the whole request.
+
+use std::{
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{serde_as, Map};
+
+use super::{versioned, GroupMetaDatabase, Rubric};
+use crate::types::{timestamp::Timestamp, ManuscriptId};
+
+/// A review stored in the sync server.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct StoredReview {
+    /// Increased on every accepted change.
+    pub revision: u64,
+    pub updated_at: Timestamp,
+
+    /// The version of the serialized meta group.
+    pub rubric_version: u32,
+    pub rank: Value,
+}
+
+/// A review changed by a client.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Change {
+    /// The revision this change is based on; 0 if the review is new.
+    pub base_revision: u64,
+    /// The time the review was last edited on the client.
+    pub updated_at: Timestamp,
+    pub rank: Value,
+}
+
+/// The changes a client sends to the server.
+#[serde_as]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SyncRequest {
+    /// The version of the serialized meta groups.
+    pub rubric_version: u32,
+
+    #[serde_as(as = "Map<_, _>")]
+    pub changes: Vec<(ManuscriptId, Change)>,
+}
+
+/// Which change won a conflict.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Winner {
+    /// The change of the client overwrote the one on the server.
+    Local,
+
+    /// The change on the server is newer and kept.
+    Server,
+}
+
+/// A review changed by both the client and the server since the base revision.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Conflict {
+    pub id: ManuscriptId,
+    pub winner: Winner,
+    pub local_updated_at: Timestamp,
+    pub server_updated_at: Timestamp,
+}
+
+/// The reply of the server to a [`SyncRequest`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct SyncResponse {
+    /// All the reviews of the reviewer on the server.
+    pub reviews: HashMap<ManuscriptId, StoredReview>,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// The reviews of a reviewer on the server.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct ReviewerStore(HashMap<ManuscriptId, StoredReview>);
+
+impl ReviewerStore {
+    /// Apply the changes of a client, the last writer wins.
+    pub fn apply(&mut self, request: SyncRequest) -> SyncResponse {
+        let mut conflicts = Vec::new();
+
+        for (id, change) in request.changes {
+            let accepted = StoredReview {
+                revision: change.base_revision + 1,
+                updated_at: change.updated_at,
+                rubric_version: request.rubric_version,
+                rank: change.rank,
+            };
+
+            let Some(stored) = self.0.get_mut(&id) else {
+                self.0.insert(id, accepted);
+                continue;
+            };
+            if stored.revision == change.base_revision {
+                *stored = accepted;
+                continue;
+            }
+
+            // The tie goes to the server, which has been seen by the other clients.
+            let winner = if change.updated_at > stored.updated_at {
+                Winner::Local
+            } else {
+                Winner::Server
+            };
+            conflicts.push(Conflict {
+                id,
+                winner,
+                local_updated_at: change.updated_at,
+                server_updated_at: stored.updated_at,
+            });
+
+            if winner == Winner::Local {
+                *stored = StoredReview {
+                    revision: stored.revision + 1,
+                    ..accepted
+                };
+            }
+        }
+
+        SyncResponse {
+            reviews: self.0.clone(),
+            conflicts,
+        }
+    }
+}
+
+impl Deref for ReviewerStore {
+    type Target = HashMap<ManuscriptId, StoredReview>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// The reviews of every reviewer of a rubric.
+pub type Reviewers = HashMap<String, ReviewerStore>;
+
+/// The database of the sync server: the rubric ID to its [`Reviewers`].
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SyncDatabase(HashMap<String, Reviewers>);
+
+impl SyncDatabase {
+    /// The reviews of a reviewer of a rubric.
+    pub fn reviewer_mut(&mut self, rubric: &str, reviewer: &str) -> &mut ReviewerStore {
+        self.0
+            .entry(rubric.to_owned())
+            .or_default()
+            .entry(reviewer.to_owned())
+            .or_default()
+    }
+}
+
+impl Deref for SyncDatabase {
+    type Target = HashMap<String, Reviewers>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// The reviews as of the last sync, on the client.
+///
+/// A review is changed if it differs from its base.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SyncBase(HashMap<ManuscriptId, (u64, Value)>);
+
+impl Deref for SyncBase {
+    type Target = HashMap<ManuscriptId, (u64, Value)>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for SyncBase {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// The time every review was last edited on the client.
+pub type EditTimes = HashMap<ManuscriptId, Timestamp>;
+
+/// The serialized reviews of a client, taken when a sync starts.
+pub type Snapshot = HashMap<ManuscriptId, Value>;
+
+/// Take the snapshot of the reviews in `rank`.
+pub fn snapshot<R: Rubric>(rank: &GroupMetaDatabase<R>) -> Result<Snapshot, Error> {
+    rank.iter()
+        .map(|(id, review)| Ok((*id, serde_json::to_value(review)?)))
+        .collect()
+}
+
+impl SyncBase {
+    /// The reviews in `snapshot` changed since the last sync, stamped with their edit times.
+    ///
+    /// A review without an edit time, such as one edited before the times
+    /// were recorded, is stamped with `now`.
+    pub fn changes(
+        &self,
+        snapshot: &Snapshot,
+        rubric_version: u32,
+        edited_at: &EditTimes,
+        now: Timestamp,
+    ) -> SyncRequest {
+        let mut changes = Vec::new();
+
+        for (id, review) in snapshot {
+            let base_revision = match self.0.get(id) {
+                Some((_, base)) if base == review => continue,
+                Some((revision, _)) => *revision,
+                None => 0,
+            };
+            changes.push((
+                *id,
+                Change {
+                    base_revision,
+                    updated_at: edited_at.get(id).copied().unwrap_or(now),
+                    rank: review.clone(),
+                },
+            ));
+        }
+
+        SyncRequest {
+            rubric_version,
+            changes,
+        }
+    }
+
+    /// Adopt the reviews on the server, migrating them if needed.
+    ///
+    /// `snapshot` is the one the request was made of. The reviews edited
+    /// since then are kept, and will be sent on the next sync.
+    /// The reviews not on the server are left untouched, and so are those
+    /// on the server which can't be read, which are skipped.
+    pub fn adopt<R: Rubric>(
+        &mut self,
+        response: &SyncResponse,
+        rank: &mut GroupMetaDatabase<R>,
+        snapshot: &Snapshot,
+    ) -> Adopted {
+        let mut adopted = Adopted::default();
+
+        for (id, stored) in &response.reviews {
+            match self.adopt_review(id, stored, rank, snapshot) {
+                Ok(true) => adopted.replaced.push(*id),
+                Ok(false) => {}
+                Err(e) => adopted.skipped.push((*id, e)),
+            }
+        }
+
+        adopted
+    }
+
+    /// Adopt a review on the server. Return whether it replaced the one in `rank`.
+    fn adopt_review<R: Rubric>(
+        &mut self,
+        id: &ManuscriptId,
+        stored: &StoredReview,
+        rank: &mut GroupMetaDatabase<R>,
+        snapshot: &Snapshot,
+    ) -> Result<bool, Error> {
+        let review = read_review::<R>(id, &stored.rank, stored.rubric_version)?;
+        let current = rank.get(id).map(serde_json::to_value).transpose()?;
+        self.0
+            .insert(*id, (stored.revision, serde_json::to_value(&review)?));
+
+        if current.as_ref() == snapshot.get(id) {
+            rank.insert(*id, review);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+/// The result of [`SyncBase::adopt`].
+#[derive(Debug, Default)]
+pub struct Adopted {
+    /// The IDs of the reviews replaced with the ones on the server.
+    pub replaced: Vec<ManuscriptId>,
+
+    /// The reviews on the server which can't be read.
+    pub skipped: Vec<(ManuscriptId, Error)>,
+}
+
+/// Read a serialized review of the rubric `R` at `rubric_version`, migrating it if needed.
+pub fn read_review<R: Rubric>(
+    id: &ManuscriptId,
+    review: &Value,
+    rubric_version: u32,
+) -> Result<R, Error> {
+    if rubric_version == R::VERSION {
+        return Ok(serde_json::from_value(review.clone())?);
+    }
+
+    // The migrations work on the data of the whole database.
+    let mut data = serde_yaml::Mapping::new();
+    data.insert(serde_yaml::to_value(id)?, serde_yaml::to_value(review)?);
+
+    let data = versioned::migrate::<R>(rubric_version, data.into())?;
+    let mut migrated: GroupMetaDatabase<R> = serde_yaml::from_value(data)?;
+    Ok(migrated.remove(id).unwrap_or_default())
+}
+
+/// Percent-encode `segment` to put it in the path of an URL.
+pub fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Decode a percent-encoded path segment.
+///
+/// Return `None` if it is not valid.
+pub fn decode_path_segment(segment: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(segment.len());
+    let mut input = segment.bytes();
+
+    while let Some(b) = input.next() {
+        if b == b'%' {
+            let hex = [input.next()?, input.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(b);
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("failed to (de)serialize the review: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("failed to convert the review: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error(transparent)]
+    Versioned(#[from] versioned::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_path_segment, encode_path_segment, snapshot, Change, EditTimes, ReviewerStore,
+        SyncBase, SyncRequest, Winner,
+    };
+    use crate::types::{
+        rank::{
+            sitcon_gdsc, versioned::Versioned, CommentableItemGroup, GroupMetaDatabase,
+            MutableMetaGroup,
+        },
+        timestamp::Timestamp,
+        ManuscriptId,
+    };
+
+    #[test]
+    fn test_sync() {
+        let id = ManuscriptId([0; 32]);
+        let version = sitcon_gdsc::Group::VERSION;
+        let mut server = ReviewerStore::default();
+
+        // Two clients of the same reviewer, with the edit times of their reviews.
+        let mut laptop = (
+            SyncBase::default(),
+            GroupMetaDatabase::new(),
+            EditTimes::new(),
+        );
+        let mut desktop = (
+            SyncBase::default(),
+            GroupMetaDatabase::<sitcon_gdsc::Group>::new(),
+            EditTimes::new(),
+        );
+        laptop.1.insert(id, sitcon_gdsc::Group::default());
+        laptop.2.insert(id, Timestamp(5));
+
+        let mut sync =
+            |(base, rank, edited_at): &mut (SyncBase, GroupMetaDatabase<_>, EditTimes), now| {
+                let snapshot = snapshot(rank).unwrap();
+                let response =
+                    server.apply(base.changes(&snapshot, version, edited_at, Timestamp(now)));
+                for id in base.adopt(&response, rank, &snapshot).replaced {
+                    edited_at.insert(id, response.reviews[&id].updated_at);
+                }
+
+                response
+            };
+
+        assert_eq!(sync(&mut laptop, 10).reviews[&id].revision, 1);
+        sync(&mut desktop, 11);
+        assert!(desktop.1.contains_key(&id));
+        assert_eq!(desktop.2[&id], Timestamp(5));
+
+        let response = {
+            *laptop.1.get_mut(&id).unwrap().reviewed_mut() = true;
+            laptop.2.insert(id, Timestamp(18));
+            sync(&mut laptop, 20)
+        };
+        assert!(response.conflicts.is_empty());
+        assert_eq!(response.reviews[&id].revision, 2);
+        assert_eq!(response.reviews[&id].updated_at, Timestamp(18));
+
+        // The laptop edits offline; the desktop edits later and syncs first.
+        *laptop.1.get_mut(&id).unwrap().content.comment_mut() = "laptop".into();
+        laptop.2.insert(id, Timestamp(22));
+        *desktop.1.get_mut(&id).unwrap().content.comment_mut() = "desktop".into();
+        desktop.2.insert(id, Timestamp(25));
+
+        let response = sync(&mut desktop, 30);
+        assert_eq!(response.conflicts.len(), 1);
+        assert_eq!(response.conflicts[0].winner, Winner::Local);
+        assert_eq!(response.reviews[&id].revision, 3);
+
+        // The stale edit of the laptop, synced last, loses to the newer one.
+        let response = sync(&mut laptop, 40);
+        assert_eq!(response.conflicts[0].winner, Winner::Server);
+        assert_eq!(response.conflicts[0].local_updated_at, Timestamp(22));
+        assert_eq!(laptop.1[&id].content.comment(), "desktop");
+        assert_eq!(laptop.2[&id], Timestamp(25));
+    }
+
+    #[test]
+    fn test_edited_while_syncing() {
+        let id = ManuscriptId([0; 32]);
+        let mut server = ReviewerStore::default();
+        let mut base = SyncBase::default();
+        let mut rank = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        rank.insert(id, sitcon_gdsc::Group::default());
+
+        let snapshot = snapshot(&rank).unwrap();
+        let request = base.changes(
+            &snapshot,
+            sitcon_gdsc::Group::VERSION,
+            &EditTimes::new(),
+            Timestamp(10),
+        );
+        *rank.get_mut(&id).unwrap().reviewed_mut() = true;
+        base.adopt(&server.apply(request), &mut rank, &snapshot);
+
+        // The edit is kept, and sent on the next sync.
+        assert!(rank[&id].reviewed);
+        let snapshot = super::snapshot(&rank).unwrap();
+        let request = base.changes(
+            &snapshot,
+            sitcon_gdsc::Group::VERSION,
+            &EditTimes::new(),
+            Timestamp(11),
+        );
+        assert_eq!(request.changes[0].1.base_revision, 1);
+    }
+
+    #[test]
+    fn test_skip_unreadable() {
+        let (good, bad) = (ManuscriptId([0; 32]), ManuscriptId([1; 32]));
+        let mut server = ReviewerStore::default();
+        let request = SyncRequest {
+            rubric_version: sitcon_gdsc::Group::VERSION,
+            changes: vec![
+                (
+                    bad,
+                    Change {
+                        base_revision: 0,
+                        updated_at: Timestamp(1),
+                        rank: serde_json::json!({ "reviewed": "maybe" }),
+                    },
+                ),
+                (
+                    good,
+                    Change {
+                        base_revision: 0,
+                        updated_at: Timestamp(1),
+                        rank: serde_json::to_value(sitcon_gdsc::Group::default()).unwrap(),
+                    },
+                ),
+            ],
+        };
+
+        let mut base = SyncBase::default();
+        let mut rank = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        let adopted = base.adopt(&server.apply(request), &mut rank, &Default::default());
+
+        // The readable review is adopted despite the other.
+        assert_eq!(adopted.replaced, [good]);
+        assert_eq!(adopted.skipped.len(), 1);
+        assert_eq!(adopted.skipped[0].0, bad);
+        assert!(rank.contains_key(&good) && !rank.contains_key(&bad));
+        assert!(!base.contains_key(&bad));
+    }
+
+    #[test]
+    fn test_path_segment() {
+        let encoded = encode_path_segment("評審 A/B");
+
+        assert!(encoded
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '%'));
+        assert_eq!(decode_path_segment(&encoded).unwrap(), "評審 A/B");
+        assert_eq!(decode_path_segment("%E8"), None);
+        assert_eq!(decode_path_segment("%4"), None);
+    }
+}
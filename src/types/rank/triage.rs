@@ -0,0 +1,182 @@
+//! The triage of the manuscripts the reviewers disagree on.
+
+use super::{aggregate::Stats, sheet::ReviewerSheets};
+use crate::types::{ManuscriptDatabase, ManuscriptId};
+
+/// How to sort the manuscripts to triage.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TriageOrder {
+    /// By the standard deviation of the total scores.
+    #[default]
+    TotalSpread,
+
+    /// By the mean disagreement of the items.
+    ItemDisagreement,
+}
+
+/// An item of a manuscript the reviewers disagree on.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DisputedItem {
+    pub group_key: &'static str,
+    pub item_key: &'static str,
+
+    /// The difference of the highest and the lowest choice in ordinal.
+    pub range: usize,
+}
+
+/// How much the reviewers disagree on a manuscript.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Disagreement {
+    pub id: ManuscriptId,
+
+    /// The statistics of the total scores.
+    pub total: Stats,
+
+    /// The mean of the ordinal ranges of all the items.
+    pub item_disagreement: f64,
+
+    /// The items the reviewers chose differently, the most disputed first.
+    pub disputed_items: Vec<DisputedItem>,
+}
+
+impl Disagreement {
+    /// The disputed item of `group_key` and `item_key`.
+    pub fn disputed(&self, group_key: &str, item_key: &str) -> Option<&DisputedItem> {
+        self.disputed_items
+            .iter()
+            .find(|d| d.group_key == group_key && d.item_key == item_key)
+    }
+}
+
+/// Measure the disagreement on the manuscripts reviewed by at least 2 reviewers,
+/// the most disagreed first.
+pub fn triage(
+    manuscripts: &ManuscriptDatabase,
+    sheets: &[ReviewerSheets],
+    order: TriageOrder,
+) -> Vec<Disagreement> {
+    let mut disagreements = manuscripts
+        .iter()
+        .filter_map(|(id, _)| {
+            let reviews = sheets
+                .iter()
+                .filter_map(|s| s.reviewed(id))
+                .collect::<Vec<_>>();
+            if reviews.len() < 2 {
+                return None;
+            }
+
+            let mut ranges = Vec::new();
+            let mut disputed_items = Vec::new();
+            for group in &reviews[0].groups {
+                for item in &group.items {
+                    let choices = reviews
+                        .iter()
+                        .filter_map(|r| r.group(group.key))
+                        .filter_map(|g| g.items.iter().find(|i| i.key == item.key))
+                        .map(|i| i.choice.ordinal())
+                        .collect::<Vec<_>>();
+
+                    let (Some(min), Some(max)) = (choices.iter().min(), choices.iter().max())
+                    else {
+                        continue;
+                    };
+
+                    let range = max - min;
+                    ranges.push(range as f64);
+                    if range > 0 {
+                        disputed_items.push(DisputedItem {
+                            group_key: group.key,
+                            item_key: item.key,
+                            range,
+                        });
+                    }
+                }
+            }
+            disputed_items.sort_by_key(|d| std::cmp::Reverse(d.range));
+
+            Some(Disagreement {
+                id: *id,
+                total: Stats::of(reviews.iter().map(|r| r.total))?,
+                item_disagreement: if ranges.is_empty() {
+                    0.0
+                } else {
+                    ranges.iter().sum::<f64>() / ranges.len() as f64
+                },
+                disputed_items,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    disagreements.sort_by(|a, b| match order {
+        TriageOrder::TotalSpread => b.total.std_dev.total_cmp(&a.total.std_dev),
+        TriageOrder::ItemDisagreement => b.item_disagreement.total_cmp(&a.item_disagreement),
+    });
+
+    disagreements
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{triage, TriageOrder};
+    use crate::types::{
+        rank::{
+            sheet::{ReviewSheet, ReviewerSheets},
+            sitcon_gdsc, Item, MutableMetaGroup, StandardChoice,
+        },
+        timestamp::Timestamp,
+        Manuscript, ManuscriptDatabase,
+    };
+
+    #[test]
+    fn test_triage() {
+        let manuscripts: ManuscriptDatabase = ["A", "B", "C"]
+            .into_iter()
+            .map(|title| Manuscript::fixture(title, title, ""))
+            .collect::<Vec<_>>()
+            .into();
+        let ids = manuscripts.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+
+        // Bob disagrees on B a lot, and on A a little; C is reviewed by Alice only.
+        let sheets = ["Alice", "Bob"].map(|reviewer| {
+            let reviews = ids
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| reviewer == "Alice" || *i < 2)
+                .map(|(i, id)| {
+                    let mut rank = sitcon_gdsc::Group::default();
+                    *rank.reviewed_mut() = true;
+
+                    if reviewer == "Bob" {
+                        *rank.expressive.fluent.choice_mut() = StandardChoice::Partial;
+                        if i == 1 {
+                            *rank.content.structure.choice_mut() = StandardChoice::No;
+                            *rank.expressive.organized.choice_mut() = StandardChoice::No;
+                        }
+                    }
+
+                    (*id, ReviewSheet::of(&rank))
+                })
+                .collect::<HashMap<_, _>>();
+
+            ReviewerSheets {
+                reviewer: reviewer.into(),
+                rubric: "sitcon-gdsc".into(),
+                exported_at: Timestamp(0),
+                reviews,
+            }
+        });
+
+        let by_items = triage(&manuscripts, &sheets, TriageOrder::ItemDisagreement);
+        assert_eq!(by_items.len(), 2);
+        assert_eq!(by_items[0].id, ids[1]);
+        assert_eq!(by_items[0].disputed_items[0].range, 3);
+        assert_eq!(by_items[1].disputed_items.len(), 1);
+        assert!(by_items[1].disputed("expressive", "fluent").is_some());
+
+        let by_total = triage(&manuscripts, &sheets, TriageOrder::TotalSpread);
+        assert_eq!(by_total[0].id, ids[1]);
+    }
+}
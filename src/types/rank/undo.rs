@@ -0,0 +1,296 @@
+//! The undo history of the edits to the reviews.
+//!
+//! A [`Command`] records the meta groups of the manuscripts before and after
+//! an edit, serialized as YAML values, so the history works with any rubric.
+//! Undoing a command puts the `before` values back; redoing puts the `after`
+//! values back.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_yaml::Value;
+
+use super::{GroupMetaDatabase, MetaGroup};
+use crate::types::ManuscriptId;
+
+/// The number of the commands to keep.
+pub const UNDO_LIMIT: usize = 200;
+
+/// The meta group of a manuscript before and after an edit.
+///
+/// `None` means the manuscript has no meta group.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Change {
+    pub id: ManuscriptId,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// What a command did.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CommandKind {
+    /// An edit to the review of a manuscript: a choice, a comment or the reviewed state.
+    Edit,
+
+    /// The reset of all the reviews.
+    Reset,
+
+    /// The restore of a snapshot.
+    Restore,
+
+    /// The import of an export file.
+    Import,
+
+    /// The merge of the reviews edited on another device.
+    Merge,
+
+    /// The reviews received from the sync server.
+    Sync,
+
+    /// The reviews of an opened workspace.
+    OpenWorkspace,
+
+    /// The restore of the autosaved unsaved reviews.
+    Recover,
+}
+
+/// A step in the undo history.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Command {
+    pub kind: CommandKind,
+    pub changes: Vec<Change>,
+}
+
+impl Command {
+    /// The command which undoes this command.
+    pub fn reversed(&self) -> Self {
+        Self {
+            kind: self.kind,
+            changes: self
+                .changes
+                .iter()
+                .map(|c| Change {
+                    id: c.id,
+                    before: c.after.clone(),
+                    after: c.before.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// The manuscript to show after undoing or redoing this command, if it edits one.
+    pub fn manuscript(&self) -> Option<ManuscriptId> {
+        match (self.kind, self.changes.as_slice()) {
+            (CommandKind::Edit, [change]) => Some(change.id),
+            _ => None,
+        }
+    }
+}
+
+/// Serialize a meta group for a [`Change`].
+pub fn snapshot<M: Serialize>(meta: Option<&M>) -> Result<Option<Value>, Error> {
+    Ok(meta.map(serde_yaml::to_value).transpose()?)
+}
+
+/// The undo and redo stacks.
+#[derive(Default, Debug)]
+pub struct UndoHistory {
+    undo: Vec<Command>,
+    redo: Vec<Command>,
+}
+
+impl UndoHistory {
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// The command to undo next.
+    pub fn last(&self) -> Option<&Command> {
+        self.undo.last()
+    }
+
+    /// The command to redo next.
+    pub fn next(&self) -> Option<&Command> {
+        self.redo.last()
+    }
+
+    /// Record a command. The changes which change nothing are dropped.
+    pub fn record(&mut self, kind: CommandKind, changes: Vec<Change>) {
+        let changes = changes
+            .into_iter()
+            .filter(|c| c.before != c.after)
+            .collect::<Vec<_>>();
+        if changes.is_empty() {
+            return;
+        }
+
+        self.redo.clear();
+        self.undo.push(Command { kind, changes });
+        if self.undo.len() > UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Record an edit, merging it into the last command if that edits the same
+    /// manuscript, so typing a comment is undone at once rather than by letter.
+    pub fn record_edit(&mut self, change: Change, merge: bool) {
+        if let Some(Command {
+            kind: CommandKind::Edit,
+            changes,
+        }) = self.undo.last_mut().filter(|_| merge)
+        {
+            if let [last] = changes.as_mut_slice() {
+                if last.id == change.id && self.redo.is_empty() {
+                    last.after = change.after;
+                    if last.before == last.after {
+                        self.undo.pop();
+                    }
+                    return;
+                }
+            }
+        }
+
+        self.record(CommandKind::Edit, vec![change]);
+    }
+
+    /// Undo the last command. Return the undone command.
+    pub fn undo<M: MetaGroup + DeserializeOwned>(
+        &mut self,
+        rank: &mut GroupMetaDatabase<M>,
+    ) -> Result<Option<&Command>, Error> {
+        let Some(command) = self.undo.pop() else {
+            return Ok(None);
+        };
+
+        if let Err(e) = apply(rank, command.changes.iter().map(|c| (c.id, &c.before))) {
+            self.undo.push(command);
+            return Err(e);
+        }
+
+        self.redo.push(command);
+        Ok(self.redo.last())
+    }
+
+    /// Redo the last undone command. Return the redone command.
+    pub fn redo<M: MetaGroup + DeserializeOwned>(
+        &mut self,
+        rank: &mut GroupMetaDatabase<M>,
+    ) -> Result<Option<&Command>, Error> {
+        let Some(command) = self.redo.pop() else {
+            return Ok(None);
+        };
+
+        if let Err(e) = apply(rank, command.changes.iter().map(|c| (c.id, &c.after))) {
+            self.redo.push(command);
+            return Err(e);
+        }
+
+        self.undo.push(command);
+        Ok(self.undo.last())
+    }
+}
+
+/// Put the meta groups back. Nothing is changed if any of them fails to deserialize.
+fn apply<'a, M: MetaGroup + DeserializeOwned>(
+    rank: &mut GroupMetaDatabase<M>,
+    values: impl Iterator<Item = (ManuscriptId, &'a Option<Value>)>,
+) -> Result<(), Error> {
+    let metas = values
+        .map(|(id, value)| {
+            let meta = value
+                .as_ref()
+                .map(|v| serde_yaml::from_value::<M>(v.clone()))
+                .transpose()?;
+            Ok((id, meta))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    for (id, meta) in metas {
+        match meta {
+            Some(meta) => rank.insert(id, meta),
+            None => rank.remove(&id),
+        };
+    }
+
+    Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("failed to (de)serialize the review: {0}")]
+    Serde(#[from] serde_yaml::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{snapshot, Change, CommandKind, UndoHistory};
+    use crate::types::{
+        rank::{sitcon_gdsc, GroupMetaDatabase, Item, StandardChoice},
+        ManuscriptId,
+    };
+
+    #[test]
+    fn test_undo_redo() {
+        let (a, b) = (ManuscriptId([1; 32]), ManuscriptId([2; 32]));
+        let mut rank = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        let mut history = UndoHistory::default();
+
+        // a: Full → No, then typing a comment in two frames, merged into one edit.
+        rank.insert(a, Default::default());
+        let before = snapshot(rank.get(&a)).unwrap();
+        *rank.get_mut(&a).unwrap().subject.floss_related.choice_mut() = StandardChoice::No;
+        let after = snapshot(rank.get(&a)).unwrap();
+        history.record_edit(
+            Change {
+                id: a,
+                before,
+                after: after.clone(),
+            },
+            false,
+        );
+        rank.get_mut(&a).unwrap().reviewed = true;
+        history.record_edit(
+            Change {
+                id: a,
+                before: after,
+                after: snapshot(rank.get(&a)).unwrap(),
+            },
+            true,
+        );
+
+        // reset everything, including b.
+        rank.insert(b, Default::default());
+        let changes = [a, b]
+            .into_iter()
+            .map(|id| Change {
+                id,
+                before: snapshot(rank.get(&id)).unwrap(),
+                after: None,
+            })
+            .collect();
+        history.record(CommandKind::Reset, changes);
+        rank.clear();
+
+        let undone = history.undo(&mut rank).unwrap().unwrap();
+        assert_eq!(undone.kind, CommandKind::Reset);
+        assert_eq!(rank.len(), 2);
+        assert!(rank[&a].reviewed);
+
+        let undone = history.undo(&mut rank).unwrap().unwrap();
+        assert_eq!(undone.manuscript(), Some(a));
+        assert!(!rank[&a].reviewed);
+        assert_eq!(
+            *rank.get_mut(&a).unwrap().subject.floss_related.choice_mut(),
+            StandardChoice::Full
+        );
+        assert!(!history.can_undo());
+
+        history.redo(&mut rank).unwrap();
+        assert!(rank[&a].reviewed);
+        history.redo(&mut rank).unwrap();
+        assert!(rank.is_empty());
+        assert!(!history.can_redo());
+    }
+}
@@ -0,0 +1,271 @@
+//! The versioned document of the ranks, and the migrations between versions.
+//!
+//! The ranks are stored as:
+//!
+//! ```yaml
+//! version: 1
+//! data:
+//!   <manuscript id>: <meta group>
+//! ```
+//!
+//! The legacy document without `version` is treated as version 0,
+//! which is the bare `data` mapping.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+
+use super::{GroupMetaDatabase, MetaGroup};
+
+/// Upgrade the `data` of a document by one version.
+///
+/// Return the reason if the data can't be migrated.
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+/// A meta group whose serialized form is versioned.
+pub trait Versioned {
+    /// The current version of the serialized form.
+    const VERSION: u32;
+
+    /// The migrations of the serialized form.
+    ///
+    /// The `n`-th migration upgrades the data of version `n` to `n + 1`,
+    /// so there should be [`Versioned::VERSION`] migrations.
+    fn migrations() -> &'static [Migration];
+}
+
+#[derive(Serialize)]
+struct DocumentRef<'a, T> {
+    version: u32,
+    data: &'a T,
+}
+
+#[derive(Deserialize)]
+struct DocumentHeader {
+    version: u32,
+}
+
+/// Serialize the ranks to a versioned document.
+pub fn serialize<M: MetaGroup + Versioned + Serialize>(
+    rank: &GroupMetaDatabase<M>,
+) -> Result<String, Error> {
+    Ok(serde_yaml::to_string(&DocumentRef {
+        version: M::VERSION,
+        data: rank,
+    })?)
+}
+
+/// Serialize the ranks to a versioned document, as a value to embed in another document.
+pub fn to_value<M: MetaGroup + Versioned + Serialize>(
+    rank: &GroupMetaDatabase<M>,
+) -> Result<Value, Error> {
+    Ok(serde_yaml::to_value(DocumentRef {
+        version: M::VERSION,
+        data: rank,
+    })?)
+}
+
+/// Deserialize the ranks from a versioned document,
+/// migrating it to the current version if needed.
+pub fn deserialize<M: MetaGroup + Versioned + DeserializeOwned>(
+    document: &str,
+) -> Result<GroupMetaDatabase<M>, Error> {
+    from_value(serde_yaml::from_str(document)?)
+}
+
+/// Deserialize the ranks from a versioned document embedded in another document.
+/// See [`deserialize`].
+pub fn from_value<M: MetaGroup + Versioned + DeserializeOwned>(
+    document: Value,
+) -> Result<GroupMetaDatabase<M>, Error> {
+    let (version, data) = split_document(document)?;
+    let data = migrate::<M>(version, data)?;
+
+    Ok(serde_yaml::from_value(data)?)
+}
+
+/// Migrate the data of `version` to [`Versioned::VERSION`].
+pub fn migrate<M: Versioned>(mut version: u32, mut data: Value) -> Result<Value, Error> {
+    if version > M::VERSION {
+        return Err(Error::TooNew {
+            found: version,
+            supported: M::VERSION,
+        });
+    }
+
+    while version < M::VERSION {
+        let migration = M::migrations()
+            .get(version as usize)
+            .ok_or(Error::MissingMigration(version))?;
+
+        tracing::info!(
+            "migrating the rank from version {version} to {}",
+            version + 1
+        );
+        data = migration(data).map_err(|reason| Error::Migration {
+            from: version,
+            reason,
+        })?;
+        version += 1;
+    }
+
+    Ok(data)
+}
+
+/// Split a document to its version and data.
+fn split_document(document: Value) -> Result<(u32, Value), Error> {
+    match document {
+        Value::Mapping(mut m) if m.contains_key("version") => {
+            let DocumentHeader { version } = serde_yaml::from_value(Value::Mapping(m.clone()))?;
+            let data = m
+                .remove("data")
+                .unwrap_or_else(|| Value::Mapping(Mapping::new()));
+
+            Ok((version, data))
+        }
+        // An empty document.
+        Value::Null => Ok((0, Value::Mapping(Mapping::new()))),
+        // The legacy document.
+        legacy => Ok((0, legacy)),
+    }
+}
+
+/// Rename an item of a group in every meta group of `data`.
+///
+/// It is a helper for writing the [`Migration`] of renaming a rank item.
+pub fn rename_item(mut data: Value, group: &str, from: &str, to: &str) -> Result<Value, String> {
+    let entries = data
+        .as_mapping_mut()
+        .ok_or_else(|| "the data is not a mapping".to_owned())?;
+
+    for (id, meta) in entries.iter_mut() {
+        let Some(group) = meta.get_mut(group).and_then(Value::as_mapping_mut) else {
+            continue;
+        };
+
+        if let Some(item) = group.remove(from) {
+            if group.insert(Value::from(to), item).is_some() {
+                return Err(format!("{id:?}: item `{to}` already exists"));
+            }
+        }
+    }
+
+    Ok(data)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("failed to (de)serialize the document: {0}")]
+    Serde(#[from] serde_yaml::Error),
+
+    #[error("the document version {found} is newer than the supported version {supported}")]
+    TooNew { found: u32, supported: u32 },
+
+    #[error("no migration available for version {0}")]
+    MissingMigration(u32),
+
+    #[error("failed to migrate from version {from}: {reason}")]
+    Migration { from: u32, reason: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::{Migration, Versioned};
+    use crate::types::{
+        rank::{GroupMetaDatabase, MetaGroup},
+        ManuscriptId,
+    };
+
+    #[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+    struct Meta {
+        reviewed: bool,
+        subject: Subject,
+    }
+
+    #[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+    struct Subject {
+        floss: u32,
+    }
+
+    impl MetaGroup for Meta {
+        fn reviewed(&self) -> bool {
+            self.reviewed
+        }
+    }
+
+    impl Versioned for Meta {
+        const VERSION: u32 = 2;
+
+        fn migrations() -> &'static [Migration] {
+            &[Ok, |data| {
+                super::rename_item(data, "subject", "open_source", "floss")
+            }]
+        }
+    }
+
+    const ID: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+    #[test]
+    fn test_roundtrip() {
+        let mut rank = GroupMetaDatabase::<Meta>::new();
+        rank.insert(
+            ManuscriptId([0; 32]),
+            Meta {
+                reviewed: true,
+                subject: Subject { floss: 3 },
+            },
+        );
+
+        let document = super::serialize(&rank).unwrap();
+        assert!(document.starts_with("version: 2\n"));
+
+        let deserialized = super::deserialize::<Meta>(&document).unwrap();
+        assert_eq!(
+            deserialized.get(&ManuscriptId([0; 32])),
+            rank.get(&ManuscriptId([0; 32]))
+        );
+    }
+
+    #[test]
+    fn test_migrate_legacy() {
+        let legacy = format!("{ID}:\n  reviewed: true\n  subject:\n    open_source: 3\n");
+
+        let deserialized = super::deserialize::<Meta>(&legacy).unwrap();
+        assert_eq!(
+            deserialized.get(&ManuscriptId([0; 32])),
+            Some(&Meta {
+                reviewed: true,
+                subject: Subject { floss: 3 },
+            })
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(super::deserialize::<Meta>("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_too_new() {
+        let document = "version: 3\ndata: {}\n";
+
+        assert!(matches!(
+            super::deserialize::<Meta>(document),
+            Err(super::Error::TooNew {
+                found: 3,
+                supported: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let document = format!("version: 2\ndata:\n  {ID}:\n    reviewed: maybe\n");
+
+        assert!(matches!(
+            super::deserialize::<Meta>(&document),
+            Err(super::Error::Serde(_))
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+//! The reviewer-defined tags and the shortlist of manuscripts.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::{Deref, DerefMut},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{letter::Decision, ManuscriptId};
+
+/// The tags of a manuscript.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct ManuscriptTags {
+    /// The free-form labels, such as `AI` or `needs-follow-up`.
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+
+    /// Is this manuscript on the shortlist?
+    #[serde(default)]
+    pub starred: bool,
+
+    /// The decision of the committee, which the feedback letters tell.
+    #[serde(default, skip_serializing_if = "Decision::is_undecided")]
+    pub decision: Decision,
+
+    /// The private note of the reviewer. It is never shared with the author.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+impl ManuscriptTags {
+    /// Add a tag. The tag is trimmed before inserting.
+    ///
+    /// Return `false` if the tag is empty or already exists.
+    pub fn insert(&mut self, tag: &str) -> bool {
+        let tag = tag.trim();
+
+        if tag.is_empty() {
+            return false;
+        }
+
+        self.tags.insert(tag.to_owned())
+    }
+
+    /// Remove a tag.
+    pub fn remove(&mut self, tag: &str) -> bool {
+        self.tags.remove(tag)
+    }
+
+    /// Does this manuscript have this tag?
+    pub fn contains(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    /// Is there nothing worth storing?
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && !self.starred
+            && self.decision.is_undecided()
+            && self.note.is_empty()
+    }
+}
+
+/// The database of manuscript id to its tags.
+///
+/// It contains a [`HashMap`] to store the mapping of
+/// [`ManuscriptId`] to [`ManuscriptTags`].
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct TagDatabase(HashMap<ManuscriptId, ManuscriptTags>);
+
+impl TagDatabase {
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    /// Get the tags of the manuscript, or insert an empty one.
+    pub fn get_or_default(&mut self, id: ManuscriptId) -> &mut ManuscriptTags {
+        self.0.entry(id).or_default()
+    }
+
+    /// Does the manuscript have this tag?
+    pub fn has_tag(&self, id: &ManuscriptId, tag: &str) -> bool {
+        self.0.get(id).is_some_and(|t| t.contains(tag))
+    }
+
+    /// Is the manuscript on the shortlist?
+    pub fn is_starred(&self, id: &ManuscriptId) -> bool {
+        self.0.get(id).is_some_and(|t| t.starred)
+    }
+
+    /// All the tags in use, with the number of manuscripts using it.
+    pub fn all_tags(&self) -> BTreeMap<&str, usize> {
+        let mut tags = BTreeMap::new();
+
+        for tag in self.0.values().flat_map(|t| t.tags.iter()) {
+            *tags.entry(tag.as_str()).or_insert(0) += 1;
+        }
+
+        tags
+    }
+
+    /// Suggest the tags in use for autocompletion.
+    ///
+    /// The tags containing `input` (case-insensitive) are returned,
+    /// the most used first. The tags in `exclude` are skipped.
+    pub fn suggest(&self, input: &str, exclude: Option<&ManuscriptTags>) -> Vec<&str> {
+        let input = input.trim().to_lowercase();
+
+        let mut suggestions = self
+            .all_tags()
+            .into_iter()
+            .filter(|(tag, _)| !exclude.is_some_and(|e| e.contains(tag)))
+            .filter(|(tag, _)| tag.to_lowercase().contains(&input))
+            .collect::<Vec<_>>();
+
+        // stable sort: the tags with the same count keep the name order.
+        suggestions.sort_by(|(_, a), (_, b)| b.cmp(a));
+        suggestions.into_iter().map(|(tag, _)| tag).collect()
+    }
+
+    /// Remove the entries with nothing to store.
+    pub fn compact(&mut self) {
+        self.0.retain(|_, t| !t.is_empty());
+    }
+}
+
+impl Deref for TagDatabase {
+    type Target = HashMap<ManuscriptId, ManuscriptTags>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for TagDatabase {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ManuscriptTags, TagDatabase};
+    use crate::types::{letter::Decision, ManuscriptId};
+
+    fn id(n: u8) -> ManuscriptId {
+        ManuscriptId([n; 32])
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut tags = ManuscriptTags::default();
+
+        assert!(tags.insert("  AI "));
+        assert!(tags.contains("AI"));
+        assert!(!tags.contains("  AI "));
+        assert!(!tags.insert("AI"));
+        assert!(!tags.insert("AI\n"));
+        assert!(!tags.insert("   "));
+        assert!(tags.insert("ai"));
+        assert_eq!(tags.tags.len(), 2);
+
+        assert!(tags.remove("AI"));
+        assert!(!tags.remove("AI"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let mut tags = TagDatabase::new();
+        for (n, labels) in [
+            (1, &["Web", "AI"][..]),
+            (2, &["web3", "AI"]),
+            (3, &["Webassembly", "AI", "Rust"]),
+            (4, &["Webassembly"]),
+        ] {
+            for label in labels {
+                tags.get_or_default(id(n)).insert(label);
+            }
+        }
+
+        // The most used first; the same count keeps the name order.
+        assert_eq!(
+            tags.suggest("", None),
+            ["AI", "Webassembly", "Rust", "Web", "web3"]
+        );
+        // Both the prefix and the middle match, case-insensitively.
+        assert_eq!(tags.suggest(" WEB", None), ["Webassembly", "Web", "web3"]);
+        assert_eq!(tags.suggest("ssem", None), ["Webassembly"]);
+        assert!(tags.suggest("Go", None).is_empty());
+
+        // The tags already on the manuscript are not suggested.
+        assert_eq!(
+            tags.suggest("web", tags.get(&id(1))),
+            ["Webassembly", "web3"]
+        );
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut tags = TagDatabase::new();
+        tags.get_or_default(id(1)).insert("AI");
+        tags.get_or_default(id(2)).starred = true;
+        tags.get_or_default(id(3)).decision = Decision::Accepted;
+        tags.get_or_default(id(4)).note = "Ask about the demo".into();
+        tags.get_or_default(id(5));
+        let removed = tags.get_or_default(id(6));
+        removed.insert("AI");
+        removed.remove("AI");
+
+        tags.compact();
+        let mut kept = tags.keys().copied().collect::<Vec<_>>();
+        kept.sort_by_key(|id| id.0);
+        assert_eq!(kept, [id(1), id(2), id(3), id(4)]);
+    }
+}
@@ -0,0 +1,114 @@
+//! The timestamp of the events in this tool.
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_family = "wasm"))]
+use std::time;
+
+#[cfg(target_family = "wasm")]
+use web_time as time;
+
+/// The seconds since the Unix epoch (UTC).
+#[derive(
+    Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug,
+)]
+#[serde(transparent)]
+pub struct Timestamp(pub u64);
+
+/// The civil date and time in UTC.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DateTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl Timestamp {
+    /// The current time.
+    pub fn now() -> Self {
+        let since_epoch = time::SystemTime::now()
+            .duration_since(time::SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+
+        Self(since_epoch.as_secs())
+    }
+
+    /// The seconds elapsed from `earlier` to this timestamp.
+    pub fn seconds_since(&self, earlier: Timestamp) -> u64 {
+        self.0.saturating_sub(earlier.0)
+    }
+
+    /// Convert to the civil date and time in UTC.
+    pub fn to_date_time(&self) -> DateTime {
+        let days = (self.0 / 86400) as i64;
+        let secs = (self.0 % 86400) as u32;
+
+        // Howard Hinnant's `civil_from_days` algorithm.
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        DateTime {
+            year,
+            month,
+            day,
+            hour: secs / 3600,
+            minute: secs % 3600 / 60,
+            second: secs % 60,
+        }
+    }
+
+    /// The compact stamp for the file names, such as `20230614-235959`.
+    pub fn file_stamp(&self) -> String {
+        let DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        } = self.to_date_time();
+
+        format!("{year:04}{month:02}{day:02}-{hour:02}{minute:02}{second:02}")
+    }
+}
+
+impl std::fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        } = self.to_date_time();
+
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02} UTC"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timestamp;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Timestamp(0).to_string(), "1970-01-01 00:00:00 UTC");
+        assert_eq!(Timestamp(951782400).to_string(), "2000-02-29 00:00:00 UTC");
+        assert_eq!(Timestamp(1686787199).to_string(), "2023-06-14 23:59:59 UTC");
+        assert_eq!(Timestamp(1686787199).file_stamp(), "20230614-235959");
+    }
+}
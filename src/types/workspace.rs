@@ -0,0 +1,316 @@
+//! The workspace file: everything of the review of an event in one file.
+//!
+//! The workspace is stored as:
+//!
+//! ```yaml
+//! workspace_version: 1
+//! name: <the name of the event>
+//! rubric: <rubric id>
+//! saved_at: <seconds since the Unix epoch>
+//! manuscripts:
+//!   path: <the CSV of the manuscripts, relative to the workspace file>
+//!   # or a copy of the manuscripts:
+//!   # embedded: [<manuscript>, …]
+//! reviews: <the versioned document of the ranks>
+//! tags: <the tags, the shortlist and the notes>
+//! history: <the change history of the reviews>
+//! ```
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+use super::{
+    deserialize::{deserialize, Format},
+    rank::{changelog::ChangeLog, versioned, GroupMetaDatabase, Rubric},
+    tag::TagDatabase,
+    timestamp::Timestamp,
+    Manuscript, ManuscriptDatabase,
+};
+
+/// The current version of the workspace file.
+pub const WORKSPACE_VERSION: u32 = 1;
+
+/// The CSV of the manuscripts read on startup.
+pub const DEFAULT_MANUSCRIPTS_PATH: &str = "manuscripts.csv";
+
+/// Where the manuscripts of a workspace come from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ManuscriptSource {
+    /// The path to the CSV of the manuscripts, in the SITCON@GDSC format.
+    ///
+    /// The relative path is resolved against the directory of the workspace file.
+    Path(String),
+
+    /// A copy of the manuscripts, so the workspace works on its own.
+    Embedded(Vec<Manuscript>),
+}
+
+impl ManuscriptSource {
+    /// Embed a copy of the manuscripts, in their order.
+    pub fn embed(manuscripts: &ManuscriptDatabase) -> Self {
+        Self::Embedded(
+            manuscripts
+                .iter()
+                .map(|(_, m)| Manuscript::clone(m))
+                .collect(),
+        )
+    }
+
+    /// Resolve a relative path against `dir`, the directory of the workspace file.
+    pub fn resolve(self, dir: Option<&Path>) -> Self {
+        match (self, dir) {
+            (Self::Path(path), Some(dir)) => Self::Path(dir.join(path).display().to_string()),
+            (source, _) => source,
+        }
+    }
+
+    /// Load the manuscripts. `dir` is the directory of the workspace file, if known.
+    pub fn load(&self, dir: Option<&Path>) -> Result<Vec<Manuscript>, Error> {
+        match self {
+            Self::Embedded(manuscripts) => Ok(manuscripts.clone()),
+            Self::Path(path) => {
+                let path = match dir {
+                    Some(dir) => dir.join(path),
+                    None => Path::new(path).to_path_buf(),
+                };
+                let file = std::fs::File::open(&path)
+                    .map_err(|e| Error::Io(path.display().to_string(), e))?;
+
+                Ok(deserialize(Format::SitconGdsc, file)?)
+            }
+        }
+    }
+}
+
+/// The workspace open in the app, reopened on startup.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OpenWorkspace {
+    /// The prefix of the storage keys of the state of this event,
+    /// so the state of every event is kept apart.
+    pub namespace: String,
+
+    /// The manuscripts, with a relative path resolved.
+    pub manuscripts: ManuscriptSource,
+}
+
+impl OpenWorkspace {
+    /// Open the workspace identified by `identity`, its path or else its name.
+    ///
+    /// The same workspace opened again gets the same namespace.
+    pub fn new(identity: &str, manuscripts: ManuscriptSource) -> Self {
+        use sha2::{Digest, Sha256};
+
+        let digest = Sha256::digest(identity.as_bytes());
+        let namespace = digest[..8]
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+
+        Self {
+            namespace: format!("workspace.{namespace}"),
+            manuscripts,
+        }
+    }
+}
+
+/// The review workspace of an event.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Workspace {
+    pub workspace_version: u32,
+
+    /// The name of the event, such as `SITCON 2024`.
+    pub name: String,
+
+    /// The ID of the rubric.
+    pub rubric: String,
+
+    pub saved_at: Timestamp,
+    pub manuscripts: ManuscriptSource,
+
+    /// The versioned document of the ranks. See [`versioned`].
+    pub reviews: Value,
+
+    /// The tags, the shortlist and the private notes.
+    #[serde(default)]
+    pub tags: TagDatabase,
+
+    /// The change history of the reviews.
+    #[serde(default)]
+    pub history: ChangeLog,
+}
+
+impl Workspace {
+    /// Bundle the reviews of a rubric into a workspace.
+    pub fn new<R: Rubric>(
+        name: &str,
+        manuscripts: ManuscriptSource,
+        rank: &GroupMetaDatabase<R>,
+        tags: &TagDatabase,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            workspace_version: WORKSPACE_VERSION,
+            name: name.to_owned(),
+            rubric: R::ID.to_owned(),
+            saved_at: Timestamp::now(),
+            manuscripts,
+            reviews: versioned::to_value(rank)?,
+            tags: tags.clone(),
+            history: ChangeLog::default(),
+        })
+    }
+
+    pub fn from_yaml(document: &str) -> Result<Self, Error> {
+        let workspace: Self = serde_yaml::from_str(document)?;
+
+        if workspace.workspace_version > WORKSPACE_VERSION {
+            return Err(Error::TooNew {
+                found: workspace.workspace_version,
+                supported: WORKSPACE_VERSION,
+            });
+        }
+
+        Ok(workspace)
+    }
+
+    pub fn to_yaml(&self) -> Result<String, Error> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// The ranks of the workspace, which must be of the rubric `R`.
+    pub fn rank<R: Rubric>(&self) -> Result<GroupMetaDatabase<R>, Error> {
+        if self.rubric != R::ID {
+            return Err(Error::RubricMismatch {
+                expected: R::ID,
+                found: self.rubric.clone(),
+            });
+        }
+
+        Ok(versioned::from_value(self.reviews.clone())?)
+    }
+
+    /// The suggested file name of this workspace.
+    pub fn file_name(&self) -> String {
+        let name = self
+            .name
+            .trim()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect::<String>();
+
+        if name.is_empty() {
+            "workspace.yaml".to_owned()
+        } else {
+            format!("{name}.yaml")
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("failed to (de)serialize the workspace: {0}")]
+    Serde(#[from] serde_yaml::Error),
+
+    #[error("the workspace version {found} is newer than the supported version {supported}")]
+    TooNew { found: u32, supported: u32 },
+
+    #[error("the workspace is of the rubric `{found}`, not `{expected}`")]
+    RubricMismatch {
+        expected: &'static str,
+        found: String,
+    },
+
+    #[error(transparent)]
+    Versioned(#[from] versioned::Error),
+
+    #[error("failed to read the manuscripts from {0}: {1}")]
+    Io(String, std::io::Error),
+
+    #[error("failed to parse the manuscripts: {0}")]
+    Csv(#[from] csv::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Error, ManuscriptSource, OpenWorkspace, Workspace};
+    use crate::types::{
+        rank::{sitcon_gdsc, GroupMetaDatabase},
+        tag::TagDatabase,
+        Difficulty, Manuscript, ManuscriptDatabase,
+    };
+
+    #[test]
+    fn test_round_trip() {
+        let manuscripts: ManuscriptDatabase = vec![Manuscript {
+            difficulty: Difficulty::Medium,
+            ..Manuscript::fixture("Rust in the browser", "Speaker", "Talk")
+        }]
+        .into();
+        let id = *manuscripts.first().unwrap();
+
+        let mut rank = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        rank.insert(
+            id,
+            sitcon_gdsc::Group {
+                reviewed: true,
+                ..Default::default()
+            },
+        );
+        let mut tags = TagDatabase::new();
+        tags.get_or_default(id).note = "Ask about the demo".into();
+
+        let workspace = Workspace::new(
+            "SITCON 2024",
+            ManuscriptSource::embed(&manuscripts),
+            &rank,
+            &tags,
+        )
+        .unwrap();
+        assert_eq!(workspace.file_name(), "SITCON-2024.yaml");
+
+        let opened = Workspace::from_yaml(&workspace.to_yaml().unwrap()).unwrap();
+        assert_eq!(opened.name, "SITCON 2024");
+        assert_eq!(opened.tags, tags);
+        assert!(opened.rank::<sitcon_gdsc::Group>().unwrap()[&id].reviewed);
+
+        let reopened: ManuscriptDatabase = opened.manuscripts.load(None).unwrap().into();
+        assert_eq!(reopened.first(), Some(&id));
+
+        let mut newer = opened;
+        newer.workspace_version += 1;
+        assert!(matches!(
+            Workspace::from_yaml(&newer.to_yaml().unwrap()),
+            Err(Error::TooNew { .. })
+        ));
+    }
+
+    #[test]
+    fn test_open_workspace() {
+        let source = ManuscriptSource::Path("manuscripts.csv".into());
+        assert_eq!(
+            source.clone().resolve(Some(Path::new("events"))),
+            ManuscriptSource::Path(
+                Path::new("events")
+                    .join("manuscripts.csv")
+                    .display()
+                    .to_string()
+            )
+        );
+        assert_eq!(source.clone().resolve(None), source);
+
+        let open = OpenWorkspace::new("events/sitcon.yaml", source.clone());
+        assert!(open.namespace.starts_with("workspace."));
+        assert_eq!(
+            OpenWorkspace::new("events/sitcon.yaml", source.clone()).namespace,
+            open.namespace
+        );
+        assert_ne!(
+            OpenWorkspace::new("events/coscup.yaml", source).namespace,
+            open.namespace
+        );
+    }
+}
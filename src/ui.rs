@@ -2,87 +2,649 @@
 //!
 //! Note that it is pretty dirty and need to be refactored.
 
+mod autosave;
+mod cache;
 mod components;
+mod file;
 mod fonts;
+mod http;
+mod registry;
 mod state;
 
-use std::collections::hash_map::Entry;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    rc::Rc,
+};
 
 use eframe::egui;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::types::{
-    rank::{GroupMetaDatabase, MetaGroup},
+    assignment::Assignment,
+    blind::{identity_leaks, BlindLog, IdentityLeak},
+    pairwise::ComparisonDatabase,
+    rank::{
+        changelog::ChangeLog,
+        crdt::{ReplicaId, ReviewSet},
+        filter::ManuscriptFilter,
+        registry::{self as rubrics, RubricEntry},
+        snapshot::SnapshotHistory,
+        versioned, GroupMetaDatabase, MetaGroup, Rubric,
+    },
+    tag::TagDatabase,
+    timestamp::Timestamp,
+    workspace::{OpenWorkspace, Workspace},
     ManuscriptDatabase, ManuscriptId, RcManuscript,
 };
 
-use self::{components::rank::RankExt, fonts::create_font_def};
+use self::{
+    autosave::Autosave,
+    components::{
+        aggregate::{AggregateExt, AggregateState},
+        dashboard::{DashboardExt, DashboardState},
+        dump::{DumpExt, DumpState},
+        export::{ExportExt, ExportState},
+        leaderboard::{LeaderboardExt, LeaderboardState},
+        letters::{LetterExt, LetterState},
+        pairwise::{PairwiseExt, PairwiseState},
+        planner::PlannerState,
+        program::{ProgramExt, ProgramState},
+        rank::RankExt,
+        recovery::RecoveryExt,
+        snapshots::{SnapshotExt, SnapshotState},
+        sync::{SyncExt, SyncState},
+        undo::{UndoExt, UndoState},
+        workspace::{WorkspaceRequest, WorkspaceState},
+    },
+    fonts::create_font_def,
+};
 
+/// The legacy storage key of the ranks, before the rubrics are selectable.
 const RANK_KEY: &str = "rank";
+const TAGS_KEY: &str = "tags";
+const BLIND_KEY: &str = "blind";
+const RUBRIC_KEY: &str = "rubric";
+const REVIEWER_KEY: &str = "reviewer";
+const ROSTER_KEY: &str = "roster";
+const ASSIGNMENT_KEY: &str = "assignment";
+const SYNC_SERVER_KEY: &str = "sync_server";
+const SYNC_TOKEN_KEY: &str = "sync_token";
+const REPLICA_KEY: &str = "replica";
+const LETTER_TEMPLATES_KEY: &str = "letter_templates";
+const LETTER_FROM_KEY: &str = "letter_from";
+const PROGRAM_KEY: &str = "program";
+const OPEN_WORKSPACE_KEY: &str = "open_workspace";
+const WORKSPACE_FILE_KEY: &str = "workspace_file";
 
-/// The Review Tool application.
+/// The storage key of the state of an event, in the namespace of its workspace.
 ///
-/// RG means a RankGroup such as [`crate::types::rank::sitcon_gdsc::Group`].
+/// The state of the manuscripts read on startup is in no namespace.
+fn scoped(namespace: Option<&str>, key: &str) -> String {
+    match namespace {
+        Some(namespace) => format!("{namespace}.{key}"),
+        None => key.to_owned(),
+    }
+}
+
+/// The storage key of the ranks of a rubric.
+fn rank_key(rubric_id: &str) -> String {
+    format!("{RANK_KEY}.{rubric_id}")
+}
+
+/// The storage key of the pairwise comparisons of a rubric.
+fn comparisons_key(rubric_id: &str) -> String {
+    format!("comparisons.{rubric_id}")
+}
+
+/// The storage key of the blind review log of a rubric.
+fn blind_log_key(rubric_id: &str) -> String {
+    format!("blind_log.{rubric_id}")
+}
+
+/// The storage key of the reviews as of the last sync of a rubric.
+fn sync_base_key(rubric_id: &str) -> String {
+    format!("sync_base.{rubric_id}")
+}
+
+/// The storage key of the edit times of the reviews of a rubric.
+fn edit_times_key(rubric_id: &str) -> String {
+    format!("edit_times.{rubric_id}")
+}
+
+/// The storage key of the mergeable reviews of a rubric.
+fn review_set_key(rubric_id: &str) -> String {
+    format!("review_set.{rubric_id}")
+}
+
+/// The storage key of the autosaved unsaved reviews of a rubric.
+fn recovery_key(rubric_id: &str) -> String {
+    format!("recovery.{rubric_id}")
+}
+
+/// The storage key of the change history of the reviews of a rubric.
+fn changelog_key(rubric_id: &str) -> String {
+    format!("changelog.{rubric_id}")
+}
+
+/// The storage key of the snapshots of the reviews of a rubric.
+fn snapshots_key(rubric_id: &str) -> String {
+    format!("snapshots.{rubric_id}")
+}
+
+/// The storage key of the filter of the manuscript list of a rubric.
+fn filter_key(rubric_id: &str) -> String {
+    format!("filter.{rubric_id}")
+}
+
+/// The main view of the app.
+#[derive(PartialEq, Eq, Clone, Copy, Default)]
+pub(crate) enum View {
+    /// Score the manuscripts with the rubric.
+    #[default]
+    Review,
+
+    /// Rank the manuscripts by their scores.
+    Leaderboard,
+
+    /// The statistics of the submissions and my scoring.
+    Dashboard,
+
+    /// Compare the manuscripts in pairs.
+    Pairwise,
+
+    /// Aggregate the reviews of several reviewers.
+    Aggregate,
+
+    /// Build the program from the accepted talks.
+    Program,
+}
+
+/// The Review Tool.
+///
+/// It hosts the [`ReviewToolApp`] of the selected rubric,
+/// and rebuilds it when another rubric or workspace is selected.
+pub struct ReviewTool {
+    /// The manuscripts read on startup, shown when no workspace is open.
+    default_manuscripts: Rc<ManuscriptDatabase>,
+    manuscripts: Rc<ManuscriptDatabase>,
+    app: Box<dyn RubricApp>,
+}
+
+impl ReviewTool {
+    /// Create the review tool.
+    ///
+    /// If `rubric` is `None`, the last selected rubric is used.
+    /// The workspace open when the app was closed is reopened.
+    pub fn new(
+        cc: &eframe::CreationContext<'_>,
+        manuscripts: ManuscriptDatabase,
+        rubric: Option<&str>,
+    ) -> Result<Self, Error> {
+        let default_manuscripts = Rc::new(manuscripts);
+        let (open, manuscripts) = match reopen_workspace(cc.storage) {
+            Some((open, manuscripts)) => (Some(open), manuscripts),
+            None => (None, default_manuscripts.clone()),
+        };
+        let rubric = match rubric {
+            Some(id) => rubrics::find(id).ok_or_else(|| Error::UnknownRubric(id.to_owned()))?,
+            None => cc
+                .storage
+                .and_then(|storage| storage.get_string(RUBRIC_KEY))
+                .and_then(|id| rubrics::find(&id))
+                .unwrap_or_else(rubrics::default_rubric),
+        };
+
+        cc.egui_ctx.set_fonts(create_font_def());
+
+        Ok(Self {
+            app: registry::build(rubric, cc.storage, manuscripts.clone(), open)?,
+            default_manuscripts,
+            manuscripts,
+        })
+    }
+
+    fn switch_rubric(&mut self, rubric: &'static RubricEntry, frame: &mut eframe::Frame) {
+        // Store the reviews of the current rubric before leaving.
+        if let Some(storage) = frame.storage_mut() {
+            eframe::App::save(self, storage);
+        }
+
+        match registry::build(
+            rubric,
+            frame.storage(),
+            self.manuscripts.clone(),
+            self.app.open_workspace(),
+        ) {
+            Ok(app) => {
+                tracing::info!("switched to the rubric `{}`", rubric.id);
+                self.app = app;
+            }
+            Err(e) => {
+                tracing::error!("failed to switch to the rubric `{}`: {e}", rubric.id);
+            }
+        }
+    }
+
+    fn open_workspace(&mut self, request: WorkspaceRequest, frame: &mut eframe::Frame) {
+        // Store the reviews of the current workspace before leaving.
+        if let Some(storage) = frame.storage_mut() {
+            eframe::App::save(self, storage);
+        }
+
+        let WorkspaceRequest {
+            rubric,
+            manuscripts,
+            workspace,
+            path,
+            open,
+        } = request;
+        let manuscripts = Rc::new(manuscripts);
+
+        // The state of the workspace is in its own namespace.
+        match registry::build(rubric, frame.storage(), manuscripts.clone(), Some(open)).and_then(
+            |mut app| {
+                app.load_workspace(workspace, path)?;
+                Ok(app)
+            },
+        ) {
+            Ok(app) => {
+                tracing::info!("opened a workspace of the rubric `{}`", rubric.id);
+                self.app = app;
+                self.manuscripts = manuscripts;
+
+                // Reopen it on the next startup.
+                if let Some(storage) = frame.storage_mut() {
+                    eframe::App::save(self, storage);
+                }
+            }
+            Err(e) => {
+                tracing::error!("failed to open the workspace: {e}");
+                self.app.workspace_failed(&e);
+            }
+        }
+    }
+
+    /// Return to the manuscripts read on startup.
+    fn close_workspace(&mut self, frame: &mut eframe::Frame) {
+        if let Some(storage) = frame.storage_mut() {
+            eframe::App::save(self, storage);
+        }
+
+        let rubric = rubrics::find(self.app.rubric_id()).unwrap_or_else(rubrics::default_rubric);
+        match registry::build(
+            rubric,
+            frame.storage(),
+            self.default_manuscripts.clone(),
+            None,
+        ) {
+            Ok(app) => {
+                tracing::info!("closed the workspace");
+                self.app = app;
+                self.manuscripts = self.default_manuscripts.clone();
+
+                if let Some(storage) = frame.storage_mut() {
+                    eframe::App::save(self, storage);
+                }
+            }
+            Err(e) => {
+                tracing::error!("failed to close the workspace: {e}");
+            }
+        }
+    }
+}
+
+/// Reopen the workspace open when the app was closed, with its manuscripts.
+fn reopen_workspace(
+    storage: Option<&dyn eframe::Storage>,
+) -> Option<(OpenWorkspace, Rc<ManuscriptDatabase>)> {
+    let open = retrieve_yaml::<Option<OpenWorkspace>>(storage, OPEN_WORKSPACE_KEY)?;
+
+    match open.manuscripts.load(None) {
+        Ok(manuscripts) if !manuscripts.is_empty() => Some((open, Rc::new(manuscripts.into()))),
+        Ok(_) => {
+            tracing::warn!("the open workspace has no manuscripts; it is closed");
+            None
+        }
+        Err(e) => {
+            tracing::warn!("failed to reopen the workspace; it is closed: {e}");
+            None
+        }
+    }
+}
+
+impl eframe::App for ReviewTool {
+    fn update(&mut self, ctx: &eframe::egui::Context, frame: &mut eframe::Frame) {
+        self.app.update(ctx, frame);
+
+        if let Some(rubric) = self.app.take_rubric_request() {
+            self.switch_rubric(rubric, frame);
+        }
+        if let Some(request) = self.app.take_workspace_request() {
+            self.open_workspace(request, frame);
+        }
+        if self.app.take_close_request() {
+            self.close_workspace(frame);
+        }
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        storage.set_string(RUBRIC_KEY, self.app.rubric_id().to_owned());
+        self.app.save(storage);
+    }
+}
+
+/// The [`ReviewToolApp`] whose rubric is erased.
+pub(crate) trait RubricApp: eframe::App {
+    /// The ID of the rubric of this app.
+    fn rubric_id(&self) -> &'static str;
+
+    /// Take the rubric the user requested to switch to.
+    fn take_rubric_request(&mut self) -> Option<&'static RubricEntry>;
+
+    /// Take the workspace the user requested to open.
+    fn take_workspace_request(&mut self) -> Option<WorkspaceRequest>;
+
+    /// Take the request to close the workspace.
+    fn take_close_request(&mut self) -> bool;
+
+    /// The workspace open in this app.
+    fn open_workspace(&self) -> Option<OpenWorkspace>;
+
+    /// Replace the reviews and the tags with the workspace.
+    fn load_workspace(&mut self, workspace: Workspace, path: Option<String>) -> Result<(), Error>;
+
+    /// Tell the user the workspace failed to open.
+    fn workspace_failed(&mut self, error: &Error);
+}
+
+impl<R: Rubric> RubricApp for ReviewToolApp<R> {
+    fn rubric_id(&self) -> &'static str {
+        self.rubric_id
+    }
+
+    fn take_rubric_request(&mut self) -> Option<&'static RubricEntry> {
+        self.rubric_request.take()
+    }
+
+    fn take_workspace_request(&mut self) -> Option<WorkspaceRequest> {
+        self.workspace.take_request()
+    }
+
+    fn take_close_request(&mut self) -> bool {
+        self.workspace.take_close_request()
+    }
+
+    fn open_workspace(&self) -> Option<OpenWorkspace> {
+        self.workspace.open.clone()
+    }
+
+    fn load_workspace(&mut self, workspace: Workspace, path: Option<String>) -> Result<(), Error> {
+        ReviewToolApp::load_workspace(self, workspace, path)
+    }
+
+    fn workspace_failed(&mut self, error: &Error) {
+        self.workspace.failed(error);
+    }
+}
+
+/// The review app of a rubric.
+///
+/// M means a [`MetaGroup`] such as [`crate::types::rank::sitcon_gdsc::Group`].
 pub struct ReviewToolApp<M: MetaGroup> {
-    manuscripts: ManuscriptDatabase,
+    manuscripts: Rc<ManuscriptDatabase>,
     rank_groups: GroupMetaDatabase<M>,
+    /// Bumped on every change of the reviews, to invalidate the values computed from them.
+    reviews_revision: u64,
+    tags: TagDatabase,
+    comparisons: ComparisonDatabase,
+    blind_log: BlindLog,
+    /// The change history of every review.
+    changelog: ChangeLog,
+
+    /// The ID of this installation, which tags the edits to merge.
+    replica: ReplicaId,
+    /// The reviews with the history of the edits, to merge the offline edits.
+    review_set: ReviewSet,
 
+    /// The fields which may reveal the speaker, of every manuscript.
+    identity_leaks: HashMap<ManuscriptId, Vec<IdentityLeak>>,
+
+    view: View,
+    /// Hide the speaker identity.
+    blind: bool,
     current_selected: ManuscriptId,
     state: state::State,
+    rank_backup: RankBackup,
+    autosave: Autosave,
+    undo: UndoState,
+    snapshots: SnapshotState,
+
+    /// The ID of the rubric of this app.
+    rubric_id: &'static str,
+    /// The storage namespace of the state of the event. See [`scoped`].
+    namespace: Option<String>,
+    /// The rubric the user requested to switch to.
+    rubric_request: Option<&'static RubricEntry>,
+    workspace: WorkspaceState,
+
+    /// The input buffer of the tag editor.
+    tag_input: String,
+    /// The filter of the manuscript list.
+    filter: ManuscriptFilter,
+    /// Show the manuscripts on the shortlist only.
+    starred_only: bool,
+
+    /// The reviewers assigned to every manuscript.
+    assignment: Assignment,
+    /// Show the manuscripts assigned to the reviewer only.
+    assigned_only: bool,
+
+    pairwise: PairwiseState,
+    leaderboard: LeaderboardState,
+    dashboard: DashboardState,
+    dump: DumpState,
+    export: ExportState,
+    aggregate: AggregateState,
+    planner: PlannerState,
+    sync: SyncState,
+    letters: LetterState,
+    program: ProgramState,
+    /// The result of the last spreadsheet export.
+    spreadsheet_message: Option<Result<String, String>>,
 }
 
-impl<M: MetaGroup> ReviewToolApp<M> {
-    pub fn new(
-        cc: &eframe::CreationContext<'_>,
-        manuscripts: ManuscriptDatabase,
+impl<R: Rubric> ReviewToolApp<R> {
+    /// Create the review app, with the state of the event of the `open` workspace.
+    pub(crate) fn new(
+        storage: Option<&dyn eframe::Storage>,
+        manuscripts: Rc<ManuscriptDatabase>,
+        open: Option<OpenWorkspace>,
     ) -> Result<Self, Error> {
         let first_manuscript = *manuscripts.first().ok_or(Error::NoManuscript)?;
-        let rank = Self::retrieve_rank(cc, &manuscripts);
+        let namespace = open.as_ref().map(|open| open.namespace.clone());
+        let key = |key: &str| scoped(namespace.as_deref(), key);
 
-        cc.egui_ctx.set_fonts(create_font_def());
+        let RetrievedRank { rank, unloadable } =
+            Self::retrieve_rank(storage, namespace.as_deref(), &manuscripts);
+        let autosave = Autosave::new(
+            versioned::serialize(&rank).ok(),
+            retrieve_yaml(storage, &key(&recovery_key(R::ID))),
+        );
+        let tags = retrieve_yaml(storage, &key(TAGS_KEY));
+        let comparisons = retrieve_yaml(storage, &key(&comparisons_key(R::ID)));
+        let blind_log = retrieve_yaml(storage, &key(&blind_log_key(R::ID)));
+        let identity_leaks = manuscripts
+            .iter()
+            .map(|(id, m)| (*id, identity_leaks(m)))
+            .collect();
 
         Ok(Self {
             rank_groups: rank,
+            reviews_revision: 0,
+            tags,
+            comparisons,
+            blind_log,
+            changelog: retrieve_yaml(storage, &key(&changelog_key(R::ID))),
+            replica: retrieve_yaml(storage, REPLICA_KEY),
+            review_set: retrieve_yaml(storage, &key(&review_set_key(R::ID))),
+            identity_leaks,
             manuscripts,
+            view: View::default(),
+            blind: retrieve_yaml(storage, BLIND_KEY),
             current_selected: first_manuscript,
             state: state::State::default(),
+            rank_backup: unloadable.map_or(RankBackup::None, RankBackup::Pending),
+            autosave,
+            undo: UndoState::default(),
+            snapshots: SnapshotState::new(retrieve_yaml::<SnapshotHistory>(
+                storage,
+                &key(&snapshots_key(R::ID)),
+            )),
+            rubric_id: R::ID,
+            rubric_request: None,
+            workspace: WorkspaceState::new(retrieve_yaml(storage, &key(WORKSPACE_FILE_KEY)), open),
+            tag_input: String::new(),
+            filter: retrieve_yaml(storage, &key(&filter_key(R::ID))),
+            starred_only: false,
+            assignment: retrieve_yaml(storage, &key(ASSIGNMENT_KEY)),
+            assigned_only: true,
+            pairwise: PairwiseState::default(),
+            leaderboard: LeaderboardState::default(),
+            dashboard: DashboardState::default(),
+            dump: DumpState::default(),
+            export: ExportState::new(retrieve_yaml(storage, REVIEWER_KEY)),
+            aggregate: AggregateState::default(),
+            planner: PlannerState::new(retrieve_yaml(storage, ROSTER_KEY)),
+            sync: SyncState::new(
+                retrieve_yaml(storage, SYNC_SERVER_KEY),
+                retrieve_yaml(storage, SYNC_TOKEN_KEY),
+                retrieve_yaml(storage, &key(&sync_base_key(R::ID))),
+                retrieve_yaml(storage, &key(&edit_times_key(R::ID))),
+            ),
+            letters: LetterState::new(
+                retrieve_yaml(storage, LETTER_TEMPLATES_KEY),
+                retrieve_yaml(storage, LETTER_FROM_KEY),
+            ),
+            program: ProgramState::new(retrieve_yaml(storage, &key(PROGRAM_KEY))),
+            spreadsheet_message: None,
+            namespace,
         })
     }
 }
 
+/// Retrieve the YAML value of `key` from the storage.
+fn retrieve_yaml<T: DeserializeOwned + Default>(
+    storage: Option<&dyn eframe::Storage>,
+    key: &str,
+) -> T {
+    storage
+        .and_then(|storage| storage.get_string(key))
+        .and_then(|t| {
+            serde_yaml::from_str::<'_, T>(&t).map_or_else(
+                |e| {
+                    tracing::warn!("failed to deserialize {key}: {}", e);
+                    None
+                },
+                Some,
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Store the value to `key` of the storage as YAML.
+fn store_yaml<T: Serialize>(storage: &mut dyn eframe::Storage, key: &str, value: &T) {
+    match serde_yaml::to_string(value) {
+        Ok(serialized) => {
+            storage.set_string(key, serialized);
+        }
+        Err(e) => {
+            tracing::error!("failed to serialize {key}: {e}");
+        }
+    }
+}
+
+/// The rank retrieved from the storage.
+struct RetrievedRank<M: MetaGroup> {
+    rank: GroupMetaDatabase<M>,
+
+    /// The raw document which failed to load.
+    unloadable: Option<String>,
+}
+
+/// The backup of the rank document which failed to load.
+///
+/// The document is backed up on the next save, so the
+/// unloadable reviews won't be overwritten silently.
+pub(crate) enum RankBackup {
+    /// Nothing to back up.
+    None,
+
+    /// The raw document to back up on the next save.
+    Pending(String),
+
+    /// The document has been backed up to this storage key.
+    Done(String),
+}
+
 trait RetrieveRankExt<M: MetaGroup> {
     fn retrieve_rank(
-        cc: &eframe::CreationContext<'_>,
+        storage: Option<&dyn eframe::Storage>,
+        namespace: Option<&str>,
         manuscripts: &ManuscriptDatabase,
-    ) -> GroupMetaDatabase<M>;
+    ) -> RetrievedRank<M>;
 }
 
 impl<M: MetaGroup> RetrieveRankExt<M> for ReviewToolApp<M> {
     default fn retrieve_rank(
-        _cc: &eframe::CreationContext<'_>,
+        _storage: Option<&dyn eframe::Storage>,
+        _namespace: Option<&str>,
         _manuscripts: &ManuscriptDatabase,
-    ) -> GroupMetaDatabase<M> {
-        GroupMetaDatabase::new()
+    ) -> RetrievedRank<M> {
+        RetrievedRank {
+            rank: GroupMetaDatabase::new(),
+            unloadable: None,
+        }
     }
 }
 
-impl<M: MetaGroup + DeserializeOwned> RetrieveRankExt<M> for ReviewToolApp<M> {
+impl<R: Rubric> RetrieveRankExt<R> for ReviewToolApp<R> {
     fn retrieve_rank(
-        cc: &eframe::CreationContext<'_>,
+        storage: Option<&dyn eframe::Storage>,
+        namespace: Option<&str>,
         manuscripts: &ManuscriptDatabase,
-    ) -> GroupMetaDatabase<M> {
-        cc.storage
-            .and_then(|storage| storage.get_string(RANK_KEY))
-            .and_then(|r| {
-                serde_yaml::from_str::<'_, GroupMetaDatabase<M>>(&r).map_or_else(
-                    |e| {
-                        tracing::warn!("failed to deserialize rank: {}", e);
-                        None
-                    },
-                    Some,
-                )
-            })
-            .unwrap_or(GroupMetaDatabase::with_capacity(manuscripts.len()))
+    ) -> RetrievedRank<R> {
+        let document = storage.and_then(|storage| {
+            storage
+                .get_string(&scoped(namespace, &rank_key(R::ID)))
+                .or_else(|| {
+                    // The ranks of the default rubric were stored in the legacy key.
+                    (namespace.is_none() && R::ID == rubrics::default_rubric().id)
+                        .then(|| storage.get_string(RANK_KEY))
+                        .flatten()
+                })
+        });
+
+        let Some(document) = document else {
+            return RetrievedRank {
+                rank: GroupMetaDatabase::with_capacity(manuscripts.len()),
+                unloadable: None,
+            };
+        };
+
+        match versioned::deserialize::<R>(&document) {
+            Ok(rank) => RetrievedRank {
+                rank,
+                unloadable: None,
+            },
+            Err(e) => {
+                tracing::warn!("failed to deserialize rank: {}", e);
+
+                RetrievedRank {
+                    rank: GroupMetaDatabase::with_capacity(manuscripts.len()),
+                    unloadable: Some(document),
+                }
+            }
+        }
     }
 }
 
@@ -98,27 +660,73 @@ impl<M: MetaGroup + Default> ReviewToolApp<M> {
     pub(crate) fn get_current_rank_or_set_default(&mut self) -> &mut M {
         match self.rank_groups.entry(self.current_selected) {
             Entry::Occupied(entry) => entry.into_mut(),
-            Entry::Vacant(entry) => entry.insert(M::default()),
+            Entry::Vacant(entry) => {
+                self.reviews_revision += 1;
+                entry.insert(M::default())
+            }
         }
     }
 }
 
-impl<M: MetaGroup + Serialize> eframe::App for ReviewToolApp<M> {
+impl<R: Rubric> eframe::App for ReviewToolApp<R> {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        if self.rank_groups.is_empty() {
-            storage.set_string(RANK_KEY, String::new());
-        } else {
-            let serialized_rank = serde_yaml::to_string(&self.rank_groups);
-
-            match serialized_rank {
-                Ok(serialized_rank) => {
-                    storage.set_string(RANK_KEY, serialized_rank);
-                }
-                Err(e) => {
-                    tracing::error!("failed to serialize rank: {e}");
-                }
+        let namespace = self.namespace.clone();
+        let key = |key: &str| scoped(namespace.as_deref(), key);
+
+        // Back up the unloadable document before overwriting it.
+        if let RankBackup::Pending(document) = &self.rank_backup {
+            let key = format!("{}.backup.{}", key(&rank_key(R::ID)), Timestamp::now().0);
+
+            storage.set_string(&key, document.clone());
+            tracing::warn!("the unloadable rank has been backed up to `{key}`");
+            self.rank_backup = RankBackup::Done(key);
+        }
+
+        match versioned::serialize(&self.rank_groups) {
+            Ok(serialized_rank) => {
+                storage.set_string(&key(&rank_key(R::ID)), serialized_rank.clone());
+                self.autosave.saved(serialized_rank);
             }
+            Err(e) => {
+                tracing::error!("failed to serialize rank: {e}");
+            }
+        }
+
+        if let Err(e) = self
+            .review_set
+            .record(&self.replica, &self.rank_groups, Timestamp::now())
+        {
+            tracing::error!("failed to record the edits: {e}");
         }
+        // Keep the recovery copy found on startup until the reviewer decides.
+        store_yaml(storage, &key(&recovery_key(R::ID)), &self.autosave.found);
+        store_yaml(storage, &key(&review_set_key(R::ID)), &self.review_set);
+        store_yaml(storage, REPLICA_KEY, &self.replica);
+
+        self.tags.compact();
+        store_yaml(storage, &key(TAGS_KEY), &self.tags);
+        store_yaml(storage, &key(&comparisons_key(R::ID)), &self.comparisons);
+        store_yaml(storage, &key(&blind_log_key(R::ID)), &self.blind_log);
+        store_yaml(storage, &key(&changelog_key(R::ID)), &self.changelog);
+        store_yaml(
+            storage,
+            &key(&snapshots_key(R::ID)),
+            &self.snapshots.history,
+        );
+        store_yaml(storage, &key(&filter_key(R::ID)), &self.filter);
+        store_yaml(storage, BLIND_KEY, &self.blind);
+        store_yaml(storage, REVIEWER_KEY, &self.export.reviewer);
+        store_yaml(storage, ROSTER_KEY, &self.planner.roster());
+        store_yaml(storage, &key(ASSIGNMENT_KEY), &self.assignment);
+        store_yaml(storage, SYNC_SERVER_KEY, &self.sync.server);
+        store_yaml(storage, SYNC_TOKEN_KEY, &self.sync.token);
+        store_yaml(storage, &key(&sync_base_key(R::ID)), &self.sync.base);
+        store_yaml(storage, &key(&edit_times_key(R::ID)), &self.sync.edited_at);
+        store_yaml(storage, LETTER_TEMPLATES_KEY, &self.letters.templates);
+        store_yaml(storage, LETTER_FROM_KEY, &self.letters.from);
+        store_yaml(storage, &key(PROGRAM_KEY), &self.program.program);
+        store_yaml(storage, &key(WORKSPACE_FILE_KEY), &self.workspace.file);
+        store_yaml(storage, OPEN_WORKSPACE_KEY, &self.workspace.open);
 
         storage.flush();
         tracing::info!("data has been stored");
@@ -131,10 +739,43 @@ impl<M: MetaGroup> eframe::App for ReviewToolApp<M> {
 
     fn update(&mut self, ctx: &eframe::egui::Context, frame: &mut eframe::Frame) {
         egui::CentralPanel::default().show(ctx, |ui| {
+            self.undo_tick(ctx);
             self.header(ui, frame);
+            self.export_window(ctx);
+            self.planner_window(ctx);
+            self.sync_window(ctx);
+            self.letter_window(ctx);
+            self.recovery_window(ctx);
+            self.snapshot_window(ctx);
+            self.dump_window(ctx);
+            self.autosave_tick(ctx, frame);
 
             ui.separator();
 
+            match self.view {
+                View::Review => {}
+                View::Leaderboard => {
+                    self.leaderboard(ui);
+                    return;
+                }
+                View::Dashboard => {
+                    self.dashboard(ui);
+                    return;
+                }
+                View::Pairwise => {
+                    self.pairwise(ui);
+                    return;
+                }
+                View::Aggregate => {
+                    self.aggregate(ui);
+                    return;
+                }
+                View::Program => {
+                    self.program(ui);
+                    return;
+                }
+            }
+
             egui::Grid::new("review-ui")
                 .num_columns(2)
                 .min_col_width(256.0)
@@ -177,4 +818,143 @@ impl<M: MetaGroup> eframe::App for ReviewToolApp<M> {
 pub enum Error {
     #[error("expected at least 1 manuscript; nothing given.")]
     NoManuscript,
+
+    #[error("unknown rubric `{0}`; available: {}", rubrics::RUBRICS.iter().map(|r| r.id).collect::<Vec<_>>().join(", "))]
+    UnknownRubric(String),
+
+    #[error(transparent)]
+    Export(#[from] crate::types::rank::export::Error),
+
+    #[error(transparent)]
+    Workspace(#[from] crate::types::workspace::Error),
+
+    #[error("failed to keep a snapshot of the reviews, so they are not replaced")]
+    Snapshot,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, rc::Rc};
+
+    use super::{rank_key, reopen_workspace, RankBackup, ReviewToolApp};
+    use crate::types::{
+        pairwise::Outcome,
+        rank::{sitcon_gdsc, undo::CommandKind, versioned, GroupMetaDatabase, Rubric},
+        tag::TagDatabase,
+        workspace::{ManuscriptSource, OpenWorkspace},
+        Manuscript, ManuscriptDatabase,
+    };
+
+    #[derive(Default)]
+    struct MemoryStorage(HashMap<String, String>);
+
+    impl eframe::Storage for MemoryStorage {
+        fn get_string(&self, key: &str) -> Option<String> {
+            self.0.get(key).cloned()
+        }
+
+        fn set_string(&mut self, key: &str, value: String) {
+            self.0.insert(key.to_owned(), value);
+        }
+
+        fn flush(&mut self) {}
+    }
+
+    #[test]
+    fn test_back_up_renamed_key() {
+        let manuscripts =
+            ManuscriptDatabase::from(vec![Manuscript::fixture("Rust 入門", "Pan", "Talk")]);
+        let id = *manuscripts.first().unwrap();
+        let mut rank = GroupMetaDatabase::<sitcon_gdsc::Group>::new();
+        rank.insert(
+            id,
+            sitcon_gdsc::Group {
+                reviewed: true,
+                ..Default::default()
+            },
+        );
+        let key = rank_key(sitcon_gdsc::Group::ID);
+        let document = versioned::serialize(&rank)
+            .unwrap()
+            .replace("subject:", "topic:");
+
+        let mut storage = MemoryStorage::default();
+        eframe::Storage::set_string(&mut storage, &key, document.clone());
+
+        let mut app =
+            ReviewToolApp::<sitcon_gdsc::Group>::new(Some(&storage), Rc::new(manuscripts), None)
+                .unwrap();
+        assert!(app.rank_groups.is_empty());
+        assert!(matches!(app.rank_backup, RankBackup::Pending(_)));
+
+        eframe::App::save(&mut app, &mut storage);
+        let backup = storage
+            .0
+            .iter()
+            .find(|(k, _)| k.starts_with(&format!("{key}.backup.")))
+            .map(|(_, v)| v);
+        assert_eq!(backup, Some(&document));
+    }
+
+    #[test]
+    fn test_workspace_namespace() {
+        type App = ReviewToolApp<sitcon_gdsc::Group>;
+
+        let manuscripts = Rc::new(ManuscriptDatabase::from(vec![Manuscript::fixture(
+            "Rust 入門",
+            "Pan",
+            "Talk",
+        )]));
+        let id = *manuscripts.first().unwrap();
+        let open = OpenWorkspace::new("sitcon.yaml", ManuscriptSource::embed(&manuscripts));
+        let mut storage = MemoryStorage::default();
+
+        let mut app = App::new(Some(&storage), manuscripts.clone(), None).unwrap();
+        app.tags.get_or_default(id).note = "startup".into();
+        app.comparisons.record(id, id, "subject", Outcome::Tie);
+        eframe::App::save(&mut app, &mut storage);
+        assert!(reopen_workspace(Some(&storage)).is_none());
+
+        // The workspace starts with its own state, and is reopened on startup.
+        let mut app = App::new(Some(&storage), manuscripts.clone(), Some(open.clone())).unwrap();
+        assert_eq!(app.tags, TagDatabase::new());
+        assert_eq!(app.comparisons.of_group(None).count(), 0);
+        app.tags.get_or_default(id).note = "workspace".into();
+        eframe::App::save(&mut app, &mut storage);
+
+        let (reopened, reopened_manuscripts) = reopen_workspace(Some(&storage)).unwrap();
+        assert_eq!(reopened, open);
+        assert_eq!(reopened_manuscripts.first(), Some(&id));
+
+        let mut app = App::new(Some(&storage), manuscripts.clone(), None).unwrap();
+        assert_eq!(app.tags.get_or_default(id).note, "startup");
+        assert_eq!(app.comparisons.of_group(None).count(), 1);
+
+        let mut app = App::new(Some(&storage), manuscripts, Some(open)).unwrap();
+        assert_eq!(app.tags.get_or_default(id).note, "workspace");
+    }
+
+    #[test]
+    fn test_invalidate_cached_sheets() {
+        let manuscripts =
+            ManuscriptDatabase::from(vec![Manuscript::fixture("Rust 入門", "Pan", "Talk")]);
+        let id = *manuscripts.first().unwrap();
+        let mut app =
+            ReviewToolApp::<sitcon_gdsc::Group>::new(None, Rc::new(manuscripts), None).unwrap();
+
+        let sheets = app.reviewer_sheets();
+        assert!(Rc::ptr_eq(&sheets, &app.reviewer_sheets()));
+        assert!(sheets[0].reviews.is_empty());
+
+        app.change_reviews(CommandKind::Edit, |rank| {
+            rank.insert(
+                id,
+                sitcon_gdsc::Group {
+                    reviewed: true,
+                    ..Default::default()
+                },
+            )
+        });
+        assert!(app.reviewer_sheets()[0].reviews[&id].reviewed);
+    }
 }
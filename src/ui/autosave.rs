@@ -0,0 +1,269 @@
+//! Track the unsaved changes of the reviews, and autosave them to a recovery copy.
+//!
+//! The reviews are compared with the saved ones every [`CHECK_INTERVAL`].
+//! The unsaved reviews are autosaved once they have been unchanged for
+//! [`IDLE_DELAY`], or every [`MAX_DELAY`] while the reviewer keeps editing.
+//! The recovery copy is cleared on save, so a recovery copy found on startup
+//! means the app was closed with unsaved reviews.
+
+#[cfg(not(target_family = "wasm"))]
+use std::time;
+
+#[cfg(target_family = "wasm")]
+use web_time as time;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::timestamp::Timestamp;
+
+/// How often the reviews are compared with the saved ones.
+pub(crate) const CHECK_INTERVAL: time::Duration = time::Duration::from_secs(1);
+
+/// Autosave after the reviews have been unchanged for this long.
+const IDLE_DELAY: time::Duration = time::Duration::from_secs(3);
+
+/// Autosave at least this often while the reviews keep changing.
+const MAX_DELAY: time::Duration = time::Duration::from_secs(30);
+
+/// The autosaved copy of the unsaved reviews.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct Recovery {
+    pub(crate) saved_at: Timestamp,
+
+    /// The number of the reviewed manuscripts.
+    pub(crate) reviewed: usize,
+
+    /// The versioned document of the ranks.
+    pub(crate) rank: String,
+}
+
+/// What to do with the recovery copy in the storage.
+pub(crate) enum AutosaveAction {
+    Write(Recovery),
+    Clear,
+}
+
+/// The autosave of the reviews.
+pub(crate) struct Autosave {
+    /// The document of the ranks as saved.
+    saved: Option<String>,
+    /// The document of the ranks as checked last.
+    last: Option<String>,
+    /// The document in the recovery copy.
+    recovered: Option<String>,
+
+    checked_at: time::Instant,
+    changed_at: time::Instant,
+    /// The first change not autosaved yet.
+    pending_since: Option<time::Instant>,
+
+    /// Clear the recovery copy on the next check.
+    clear: bool,
+
+    /// The recovery copy found on startup, waiting for the reviewer to restore or discard.
+    pub(crate) found: Option<Recovery>,
+    /// The error of restoring the recovery copy.
+    pub(crate) message: Option<String>,
+}
+
+impl Autosave {
+    /// Start tracking from the `saved` document. The recovery copy
+    /// is offered only if it differs from the saved document.
+    pub(crate) fn new(saved: Option<String>, found: Option<Recovery>) -> Self {
+        let now = time::Instant::now();
+
+        Self {
+            found: found.filter(|r| Some(&r.rank) != saved.as_ref()),
+            last: saved.clone(),
+            saved,
+            recovered: None,
+            checked_at: now,
+            changed_at: now,
+            pending_since: None,
+            clear: false,
+            message: None,
+        }
+    }
+
+    /// Are there changes not saved yet?
+    pub(crate) fn is_dirty(&self) -> bool {
+        self.last != self.saved
+    }
+
+    /// Have the unsaved changes been autosaved?
+    pub(crate) fn has_recovery(&self) -> bool {
+        self.recovered.is_some()
+    }
+
+    /// The reviews have been saved as `document`.
+    pub(crate) fn saved(&mut self, document: String) {
+        self.last = Some(document.clone());
+        self.saved = Some(document);
+        self.recovered = None;
+        self.pending_since = None;
+    }
+
+    /// Discard the recovery copy found on startup.
+    pub(crate) fn discard(&mut self) {
+        self.found = None;
+        self.message = None;
+        self.clear = true;
+    }
+
+    /// Check the reviews at `now`, and tell what to do with the recovery copy.
+    ///
+    /// `current` serializes the current reviews, with the number of the reviewed ones.
+    /// It is called at most once per [`CHECK_INTERVAL`].
+    pub(crate) fn tick(
+        &mut self,
+        now: time::Instant,
+        current: impl FnOnce() -> Option<(String, usize)>,
+    ) -> Option<AutosaveAction> {
+        if std::mem::take(&mut self.clear) {
+            return Some(AutosaveAction::Clear);
+        }
+        if now.duration_since(self.checked_at) < CHECK_INTERVAL {
+            return None;
+        }
+        self.checked_at = now;
+
+        let (document, reviewed) = current()?;
+        if self.last.as_ref() != Some(&document) {
+            self.last = Some(document.clone());
+            self.changed_at = self.checked_at;
+            self.pending_since.get_or_insert(self.checked_at);
+        }
+
+        // Keep the recovery copy found on startup until the reviewer decides.
+        if self.found.is_some() {
+            return None;
+        }
+        if !self.is_dirty() {
+            self.pending_since = None;
+            // The changes have been undone, so there is nothing to recover.
+            return self.recovered.take().map(|_| AutosaveAction::Clear);
+        }
+
+        let pending_since = self.pending_since?;
+        if now.duration_since(self.changed_at) < IDLE_DELAY
+            && now.duration_since(pending_since) < MAX_DELAY
+        {
+            return None;
+        }
+
+        self.pending_since = None;
+        self.recovered = Some(document.clone());
+        Some(AutosaveAction::Write(Recovery {
+            saved_at: Timestamp::now(),
+            reviewed,
+            rank: document,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{time, Autosave, AutosaveAction, Recovery};
+    use crate::types::timestamp::Timestamp;
+
+    fn seconds(start: time::Instant, seconds: u64) -> time::Instant {
+        start + time::Duration::from_secs(seconds)
+    }
+
+    fn document(document: &str) -> impl FnOnce() -> Option<(String, usize)> + '_ {
+        move || Some((document.to_owned(), 0))
+    }
+
+    fn written(action: Option<AutosaveAction>) -> Option<String> {
+        match action {
+            Some(AutosaveAction::Write(recovery)) => Some(recovery.rank),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_idle_delay() {
+        let mut autosave = Autosave::new(Some("saved".into()), None);
+        let start = time::Instant::now();
+
+        assert!(autosave.tick(seconds(start, 1), document("a")).is_none());
+        assert!(autosave.is_dirty());
+        // Checked at most once per interval.
+        assert!(autosave
+            .tick(seconds(start, 1), || unreachable!())
+            .is_none());
+        assert!(autosave.tick(seconds(start, 3), document("a")).is_none());
+        assert_eq!(
+            written(autosave.tick(seconds(start, 4), document("a"))).as_deref(),
+            Some("a")
+        );
+        assert!(autosave.has_recovery());
+        // Autosaved once until the next change.
+        assert!(autosave.tick(seconds(start, 10), document("a")).is_none());
+    }
+
+    #[test]
+    fn test_max_delay() {
+        let mut autosave = Autosave::new(Some("saved".into()), None);
+        let start = time::Instant::now();
+
+        for i in 1..31 {
+            let edit = i.to_string();
+            assert!(autosave.tick(seconds(start, i), document(&edit)).is_none());
+        }
+        assert_eq!(
+            written(autosave.tick(seconds(start, 31), document("31"))).as_deref(),
+            Some("31")
+        );
+    }
+
+    #[test]
+    fn test_clear_after_undone() {
+        let mut autosave = Autosave::new(Some("saved".into()), None);
+        let start = time::Instant::now();
+
+        autosave.tick(seconds(start, 1), document("a"));
+        assert!(written(autosave.tick(seconds(start, 4), document("a"))).is_some());
+
+        assert!(matches!(
+            autosave.tick(seconds(start, 5), document("saved")),
+            Some(AutosaveAction::Clear)
+        ));
+        assert!(!autosave.is_dirty());
+        assert!(!autosave.has_recovery());
+        assert!(autosave
+            .tick(seconds(start, 10), document("saved"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_hold_found_recovery() {
+        let recovery = |rank: &str| Recovery {
+            saved_at: Timestamp(0),
+            reviewed: 0,
+            rank: rank.into(),
+        };
+        // The recovery copy of the saved document is not offered.
+        assert!(Autosave::new(Some("saved".into()), Some(recovery("saved")))
+            .found
+            .is_none());
+
+        let mut autosave = Autosave::new(Some("saved".into()), Some(recovery("lost")));
+        let start = time::Instant::now();
+
+        // The recovery copy is not overwritten while the reviewer decides.
+        autosave.tick(seconds(start, 1), document("a"));
+        assert!(autosave.tick(seconds(start, 10), document("a")).is_none());
+        assert!(autosave.tick(seconds(start, 40), document("a")).is_none());
+
+        autosave.discard();
+        assert!(matches!(
+            autosave.tick(seconds(start, 40), || unreachable!()),
+            Some(AutosaveAction::Clear)
+        ));
+        assert_eq!(
+            written(autosave.tick(seconds(start, 41), document("a"))).as_deref(),
+            Some("a")
+        );
+    }
+}
@@ -0,0 +1,32 @@
+//! The values computed from the reviews, kept between the frames.
+
+use std::rc::Rc;
+
+/// A value kept until its key changes.
+///
+/// The key holds everything the value is computed from, such as
+/// [`crate::ui::ReviewToolApp::reviews_revision`], so a changed input
+/// computes the value again on the next frame.
+pub(crate) struct Cached<K, V> {
+    entry: Option<(K, Rc<V>)>,
+}
+
+impl<K, V> Default for Cached<K, V> {
+    fn default() -> Self {
+        Self { entry: None }
+    }
+}
+
+impl<K: PartialEq, V> Cached<K, V> {
+    /// The value of `key`, computed with `compute` only if the key has changed.
+    pub(crate) fn get(&mut self, key: K, compute: impl FnOnce() -> V) -> Rc<V> {
+        match &self.entry {
+            Some((cached, value)) if *cached == key => value.clone(),
+            _ => {
+                let value = Rc::new(compute());
+                self.entry = Some((key, value.clone()));
+                value
+            }
+        }
+    }
+}
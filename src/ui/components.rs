@@ -2,7 +2,27 @@
 //!
 //! Note that some of them are the extension of the `ReviewToolApp` struct.
 
+pub mod aggregate;
+pub mod agreement;
+pub mod dashboard;
+pub mod dump;
+pub mod export;
+pub mod filter;
 pub mod header;
+pub mod history;
+pub mod leaderboard;
+pub mod letters;
 pub mod manuscript_info;
 pub mod manuscript_list;
+pub mod pairwise;
+pub mod planner;
+pub mod program;
 pub mod rank;
+pub mod recovery;
+pub mod snapshots;
+pub mod spreadsheet;
+pub mod sync;
+pub mod tags;
+pub mod triage;
+pub mod undo;
+pub mod workspace;
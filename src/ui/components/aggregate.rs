@@ -0,0 +1,357 @@
+//! Component: Multi-reviewer Aggregation
+
+use std::rc::Rc;
+
+use eframe::egui;
+use egui::RichText;
+
+use crate::{
+    types::{
+        rank::{
+            aggregate::{Aggregation, Stats},
+            export::ReviewExport,
+            sheet::{ReviewSheet, ReviewerSheets},
+            MetaGroup, Rubric,
+        },
+        timestamp::Timestamp,
+    },
+    ui::{
+        cache::Cached,
+        components::{agreement::AgreementState, triage::TriageState},
+        file::{self, FilePicker, Filter, OpenedFile},
+        ReviewToolApp, View,
+    },
+};
+
+/// The filter of the reviewer files.
+const REVIEWER_FILTER: Filter<'static> = ("Review export (YAML)", &["yaml", "yml"]);
+
+/// The tab of the aggregation view.
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum AggregateTab {
+    /// The manuscripts ranked by the aggregated scores.
+    Ranking,
+
+    /// The inter-rater agreement of every rank item.
+    Agreement,
+
+    /// The manuscripts the reviewers disagree on.
+    Triage,
+}
+
+/// Everything the sheets to aggregate are computed from.
+#[derive(Clone, PartialEq)]
+pub(crate) struct SheetsKey {
+    reviews: u64,
+    loaded: u64,
+    include_own: bool,
+    reviewer: String,
+}
+
+/// The state of the aggregation view.
+pub(crate) struct AggregateState {
+    tab: AggregateTab,
+
+    /// The reviews of the other reviewers.
+    sheets: Vec<ReviewerSheets>,
+    /// Bumped when the reviews of the other reviewers are loaded or removed.
+    loaded: u64,
+
+    /// The sheets to aggregate, including the reviews in this app if needed.
+    all_sheets: Cached<SheetsKey, Vec<ReviewerSheets>>,
+    aggregation: Cached<SheetsKey, Aggregation>,
+
+    /// Aggregate the reviews in this app, too.
+    include_own: bool,
+
+    picker: FilePicker,
+
+    /// The errors of loading the reviewer files.
+    errors: Vec<String>,
+
+    pub(super) agreement: AgreementState,
+    pub(super) triage: TriageState,
+}
+
+impl Default for AggregateState {
+    fn default() -> Self {
+        Self {
+            tab: AggregateTab::Ranking,
+            sheets: Vec::new(),
+            loaded: 0,
+            all_sheets: Cached::default(),
+            aggregation: Cached::default(),
+            include_own: true,
+            picker: FilePicker::default(),
+            errors: Vec::new(),
+            agreement: AgreementState::default(),
+            triage: TriageState::default(),
+        }
+    }
+}
+
+/// Add `aggregate()` to Review Tool App. This trait is for specialization.
+pub(crate) trait AggregateExt {
+    fn aggregate(&mut self, ui: &mut egui::Ui);
+}
+
+impl<M: MetaGroup> AggregateExt for ReviewToolApp<M> {
+    default fn aggregate(&mut self, ui: &mut egui::Ui) {
+        ui.label("This meta group has not been supported, sorry :(");
+    }
+}
+
+impl<R: Rubric> AggregateExt for ReviewToolApp<R> {
+    fn aggregate(&mut self, ui: &mut egui::Ui) {
+        while let Some(opened) = self.aggregate.picker.take() {
+            if let Err(e) = self.load_reviewer(opened) {
+                self.aggregate.errors.push(e);
+            }
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            if ui
+                .button("Load reviewer files…")
+                .on_hover_text("Load the files exported by the other reviewers")
+                .clicked()
+            {
+                self.aggregate.picker.open_many(ui.ctx(), REVIEWER_FILTER);
+            }
+            ui.checkbox(&mut self.aggregate.include_own, "Include my reviews");
+
+            ui.separator();
+
+            let mut removed = None;
+            for (i, sheets) in self.aggregate.sheets.iter().enumerate() {
+                if ui
+                    .button(format!("{} ×", sheets.reviewer))
+                    .on_hover_text(format!(
+                        "Exported at {}. Click to remove.",
+                        sheets.exported_at
+                    ))
+                    .clicked()
+                {
+                    removed = Some(i);
+                }
+            }
+            if let Some(i) = removed {
+                self.aggregate.sheets.remove(i);
+                self.aggregate.loaded += 1;
+            }
+        });
+
+        if !self.aggregate.errors.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                for error in &self.aggregate.errors {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                if ui.small_button("Dismiss").clicked() {
+                    self.aggregate.errors.clear();
+                }
+            });
+        }
+
+        ui.separator();
+
+        let sheets = self.reviewer_sheets();
+        if sheets.is_empty() {
+            ui.label("Load the reviewer files to aggregate.");
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            let tab = &mut self.aggregate.tab;
+
+            ui.selectable_value(tab, AggregateTab::Ranking, "Ranking");
+            ui.selectable_value(tab, AggregateTab::Agreement, "Agreement");
+            ui.selectable_value(tab, AggregateTab::Triage, "Triage");
+        });
+
+        match self.aggregate.tab {
+            AggregateTab::Ranking => {
+                let aggregation = self.aggregation();
+
+                egui::ScrollArea::both().show(ui, |ui| self.aggregation_table(ui, &aggregation));
+            }
+            AggregateTab::Agreement => self.agreement(ui, &sheets),
+            AggregateTab::Triage => self.triage(ui, &sheets),
+        }
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    /// Load a reviewer file, replacing the loaded one of the same reviewer.
+    fn load_reviewer(&mut self, opened: Result<OpenedFile, file::Error>) -> Result<(), String> {
+        let opened = opened.map_err(|e| e.to_string())?;
+        let document =
+            String::from_utf8(opened.content).map_err(|e| format!("{}: {e}", opened.name))?;
+        let sheets = ReviewExport::<R>::from_yaml(&document)
+            .map(ReviewerSheets::from)
+            .map_err(|e| format!("{}: {e}", opened.name))?;
+
+        let loaded = &mut self.aggregate.sheets;
+        match loaded.iter_mut().find(|s| s.reviewer == sheets.reviewer) {
+            Some(existing) => *existing = sheets,
+            None => loaded.push(sheets),
+        }
+        self.aggregate.loaded += 1;
+
+        Ok(())
+    }
+
+    /// Everything the sheets to aggregate are computed from.
+    pub(crate) fn sheets_key(&self) -> SheetsKey {
+        SheetsKey {
+            reviews: self.reviews_revision,
+            loaded: self.aggregate.loaded,
+            include_own: self.aggregate.include_own,
+            reviewer: self.export.reviewer.trim().to_owned(),
+        }
+    }
+
+    /// The sheets to aggregate, including the reviews in this app if needed.
+    ///
+    /// They are computed again only after the reviews or the loaded sheets change.
+    pub(crate) fn reviewer_sheets(&mut self) -> Rc<Vec<ReviewerSheets>> {
+        let key = self.sheets_key();
+        let loaded = &self.aggregate.sheets;
+        let rank_groups = &self.rank_groups;
+
+        self.aggregate.all_sheets.get(key.clone(), || {
+            let mut sheets = loaded.clone();
+            if !key.include_own {
+                return sheets;
+            }
+
+            let reviewer = match key.reviewer.as_str() {
+                "" => "(me)".to_owned(),
+                name => name.to_owned(),
+            };
+
+            // the loaded file of myself is replaced by the current reviews.
+            sheets.retain(|s| s.reviewer != reviewer);
+            sheets.insert(
+                0,
+                ReviewerSheets {
+                    reviewer,
+                    rubric: R::ID.to_owned(),
+                    exported_at: Timestamp::now(),
+                    reviews: rank_groups
+                        .iter()
+                        .map(|(id, rank)| (*id, ReviewSheet::of(rank)))
+                        .collect(),
+                },
+            );
+
+            sheets
+        })
+    }
+
+    /// The aggregation of [`Self::reviewer_sheets`].
+    pub(crate) fn aggregation(&mut self) -> Rc<Aggregation> {
+        let key = self.sheets_key();
+        let sheets = self.reviewer_sheets();
+        let manuscripts = &self.manuscripts;
+
+        self.aggregate
+            .aggregation
+            .get(key, || Aggregation::new(manuscripts, &sheets))
+    }
+
+    fn aggregation_table(&mut self, ui: &mut egui::Ui, aggregation: &Aggregation) {
+        let groups = aggregation
+            .manuscripts
+            .first()
+            .map(|m| m.groups.iter().map(|g| g.name.clone()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let n = aggregation.reviewers.len();
+
+        egui::Grid::new("aggregation-table")
+            .num_columns(8 + groups.len())
+            .striped(true)
+            .show(ui, |ui| {
+                for header in [
+                    "#",
+                    "Title",
+                    "Reviewers",
+                    "Mean",
+                    "Median",
+                    "Min",
+                    "Max",
+                    "SD",
+                ] {
+                    ui.strong(header);
+                }
+                for group in &groups {
+                    ui.strong(group);
+                }
+                ui.end_row();
+
+                for (i, aggregate) in aggregation.manuscripts.iter().enumerate() {
+                    let Some(manuscript) = self.manuscripts.get(&aggregate.id).cloned() else {
+                        continue;
+                    };
+
+                    ui.label((i + 1).to_string());
+                    if ui
+                        .link(self.blinded(&manuscript.title, &manuscript).as_ref())
+                        .on_hover_text("Click to review this manuscript")
+                        .clicked()
+                    {
+                        self.current_selected = aggregate.id;
+                        self.view = View::Review;
+                    }
+
+                    let reviewers = format!("{}/{n}", aggregate.reviewers());
+                    if aggregate.missing.is_empty() {
+                        ui.label(reviewers);
+                    } else {
+                        ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {reviewers}"))
+                            .on_hover_text(format!(
+                                "Not reviewed by {}",
+                                aggregate.missing.join(", ")
+                            ));
+                    }
+
+                    match aggregate.total {
+                        Some(Stats {
+                            mean,
+                            median,
+                            min,
+                            max,
+                            std_dev,
+                            ..
+                        }) => {
+                            ui.label(RichText::new(format!("{mean:.2}")).strong());
+                            for value in [median, min, max, std_dev] {
+                                ui.label(format!("{value:.2}"));
+                            }
+                        }
+                        None => {
+                            for _ in 0..5 {
+                                ui.label("-");
+                            }
+                        }
+                    }
+
+                    for group in &aggregate.groups {
+                        match group.stats {
+                            Some(s) => ui.label(format!("{:.2} ± {:.2}", s.mean, s.std_dev)),
+                            None => ui.label("-"),
+                        }
+                        .on_hover_text(group.stats.map_or_else(
+                            String::new,
+                            |s| {
+                                format!(
+                                    "median {:.2}, min {:.2}, max {:.2}",
+                                    s.median, s.min, s.max
+                                )
+                            },
+                        ));
+                    }
+
+                    ui.end_row();
+                }
+            });
+    }
+}
@@ -0,0 +1,174 @@
+//! Component: Inter-rater Agreement Report
+
+use eframe::egui;
+use egui::{Color32, RichText};
+
+use crate::{
+    types::rank::{
+        agreement::{AgreementReport, Reliability},
+        sheet::ReviewerSheets,
+        Rubric,
+    },
+    ui::{
+        cache::Cached,
+        components::aggregate::SheetsKey,
+        file::{save_file, Filter},
+        ReviewToolApp,
+    },
+};
+
+/// The filter of the exported report.
+const REPORT_FILTER: Filter<'static> = ("CSV", &["csv"]);
+
+/// The state of the agreement report.
+#[derive(Default)]
+pub(crate) struct AgreementState {
+    /// Sort the items by alpha, the least agreed first.
+    least_agreed_first: bool,
+
+    /// The report, sorted as above.
+    report: Cached<(SheetsKey, bool), AgreementReport>,
+
+    /// The result of the last export.
+    message: Option<Result<String, String>>,
+}
+
+/// The color of an alpha by its reliability.
+fn alpha_color(ui: &egui::Ui, alpha: f64) -> Color32 {
+    match Reliability::of(alpha) {
+        Reliability::Reliable => ui.visuals().text_color(),
+        Reliability::Tentative => ui.visuals().warn_fg_color,
+        Reliability::Unreliable => ui.visuals().error_fg_color,
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    /// The agreement of every rank item among the reviewers.
+    pub(crate) fn agreement(&mut self, ui: &mut egui::Ui, sheets: &[ReviewerSheets]) {
+        if sheets.len() < 2 {
+            ui.label("At least 2 reviewers are needed to measure the agreement.");
+            return;
+        }
+
+        let key = (
+            self.sheets_key(),
+            self.aggregate.agreement.least_agreed_first,
+        );
+        let state = &mut self.aggregate.agreement;
+        let report = state.report.get(key, || {
+            let mut report = AgreementReport::new(sheets);
+            if state.least_agreed_first {
+                report.items.sort_by(|a, b| match (a.alpha, b.alpha) {
+                    (Some(a), Some(b)) => a.total_cmp(&b),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                });
+            }
+
+            report
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut state.least_agreed_first, "Least agreed first");
+
+            if ui
+                .button("Export CSV…")
+                .on_hover_text("Save this report as a CSV file")
+                .clicked()
+            {
+                state.message = Some(
+                    report
+                        .to_csv()
+                        .map_err(|e| e.to_string())
+                        .and_then(|csv| {
+                            save_file("agreement.csv", REPORT_FILTER, csv.as_bytes())
+                                .map_err(|e| e.to_string())
+                        })
+                        .map(|saved| match saved {
+                            Some(saved) => format!("Exported to {saved}."),
+                            None => "The export has been cancelled.".to_owned(),
+                        }),
+                );
+            }
+
+            match &state.message {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(message)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, message);
+                }
+                None => {}
+            }
+        });
+        ui.label(
+            RichText::new(
+                "Krippendorff's alpha (ordinal) of all the reviewers, and Cohen's kappa \
+                 (quadratic weights) of every pair. α ≥ 0.8 is reliable; α < 0.667 \
+                 suggests the item is ambiguous.",
+            )
+            .weak(),
+        );
+        ui.separator();
+
+        let format =
+            |value: Option<f64>| value.map_or_else(|| "-".to_owned(), |v| format!("{v:.3}"));
+        let pairs = report
+            .items
+            .first()
+            .map(|item| {
+                item.kappas
+                    .iter()
+                    .map(|k| {
+                        format!(
+                            "κ {} / {}",
+                            report.reviewers[k.reviewers.0], report.reviewers[k.reviewers.1]
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("agreement-table")
+                .num_columns(5 + pairs.len())
+                .striped(true)
+                .show(ui, |ui| {
+                    for header in ["Group", "Item", "α", "Units", "Mean κ"] {
+                        ui.strong(header);
+                    }
+                    for pair in &pairs {
+                        ui.strong(pair);
+                    }
+                    ui.end_row();
+
+                    for item in &report.items {
+                        ui.label(item.group_name.as_str());
+                        ui.label(item.item_name.as_str());
+
+                        match item.alpha {
+                            Some(alpha) => {
+                                ui.colored_label(alpha_color(ui, alpha), format!("{alpha:.3}"))
+                                    .on_hover_text(Reliability::of(alpha).as_str());
+                            }
+                            None => {
+                                ui.label("-").on_hover_text(
+                                    "Not enough data, or all the reviewers chose the same",
+                                );
+                            }
+                        }
+
+                        ui.label(item.units.to_string());
+                        ui.label(format(item.mean_kappa()));
+                        for kappa in &item.kappas {
+                            ui.label(format(kappa.kappa))
+                                .on_hover_text(format!("{} manuscripts", kappa.units));
+                        }
+
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
@@ -0,0 +1,202 @@
+//! Component: Dashboard
+
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use eframe::egui;
+use egui::{
+    plot::{Bar, BarChart, Plot},
+    RichText,
+};
+
+use crate::{
+    types::rank::{
+        dashboard::{Dashboard, SKEW_THRESHOLD},
+        sheet::ReviewSheet,
+        MetaGroup, Rubric, StandardChoice,
+    },
+    ui::{cache::Cached, ReviewToolApp},
+};
+
+const CHART_HEIGHT: f32 = 160.0;
+
+/// The state of the dashboard view.
+pub(crate) struct DashboardState {
+    /// The width of a bin of the score histograms.
+    bin_width: f64,
+
+    /// The dashboard, by the revision of the reviews.
+    dashboard: Cached<u64, Dashboard>,
+}
+
+impl Default for DashboardState {
+    fn default() -> Self {
+        Self {
+            bin_width: 1.0,
+            dashboard: Cached::default(),
+        }
+    }
+}
+
+/// Add `dashboard()` to Review Tool App. This trait is for specialization.
+pub(crate) trait DashboardExt {
+    fn dashboard(&mut self, ui: &mut egui::Ui);
+}
+
+impl<M: MetaGroup> DashboardExt for ReviewToolApp<M> {
+    default fn dashboard(&mut self, ui: &mut egui::Ui) {
+        ui.label("This meta group has not been supported, sorry :(");
+    }
+}
+
+impl<R: Rubric> DashboardExt for ReviewToolApp<R> {
+    fn dashboard(&mut self, ui: &mut egui::Ui) {
+        let dashboard = self.dashboard.dashboard.get(self.reviews_revision, || {
+            let reviews = self
+                .rank_groups
+                .iter()
+                .map(|(id, rank)| (*id, ReviewSheet::of(rank)))
+                .collect::<HashMap<_, _>>();
+
+            Dashboard::new(&self.manuscripts, &ReviewSheet::of(&R::default()), &reviews)
+        });
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.heading("Submissions");
+            ui.label(format!(
+                "Reviewed: {}    Pending: {}",
+                dashboard.reviewed, dashboard.pending
+            ));
+            bar_chart(
+                ui,
+                "dashboard-progress",
+                vec![
+                    ("Reviewed".to_owned(), dashboard.reviewed as f64),
+                    ("Pending".to_owned(), dashboard.pending as f64),
+                ],
+            );
+
+            ui.columns(2, |columns| {
+                columns[0].label(RichText::new("By type").strong());
+                bar_chart(
+                    &mut columns[0],
+                    "dashboard-type",
+                    dashboard
+                        .by_type
+                        .iter()
+                        .map(|(type_, n)| (type_.clone(), *n as f64))
+                        .collect(),
+                );
+                columns[1].label(RichText::new("By difficulty").strong());
+                bar_chart(
+                    &mut columns[1],
+                    "dashboard-difficulty",
+                    dashboard
+                        .by_difficulty
+                        .iter()
+                        .map(|(difficulty, n)| (difficulty.to_string(), *n as f64))
+                        .collect(),
+                );
+            });
+
+            ui.separator();
+            ui.heading("My scores");
+            ui.horizontal(|ui| {
+                ui.label("Bin width:");
+                ui.add(
+                    egui::DragValue::new(&mut self.dashboard.bin_width)
+                        .speed(0.1)
+                        .clamp_range(0.1..=100.0),
+                );
+            });
+            for group in &dashboard.groups {
+                ui.label(RichText::new(&group.name).strong());
+                let width = self.dashboard.bin_width;
+                bar_chart(
+                    ui,
+                    ("dashboard-group", &group.name),
+                    group
+                        .histogram(width)
+                        .into_iter()
+                        .map(|(start, n)| (format!("{start}–{}", start + width), n as f64))
+                        .collect(),
+                );
+            }
+
+            ui.separator();
+            ui.heading("My choices");
+            ui.label(format!(
+                "The items marked ⚠ got the same choice in {:.0}% or more of the reviews, \
+                 so they may not tell the manuscripts apart.",
+                SKEW_THRESHOLD * 100.0
+            ));
+            egui::Grid::new("dashboard-items")
+                .num_columns(3 + StandardChoice::ALL.len())
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(RichText::new("Group").strong());
+                    ui.label(RichText::new("Item").strong());
+                    for choice in StandardChoice::ALL {
+                        ui.label(RichText::new(format!("{} {choice}", choice.as_emoji())).strong());
+                    }
+                    ui.label("");
+                    ui.end_row();
+
+                    for item in &dashboard.items {
+                        let skewed = item.skewed();
+
+                        ui.label(&item.group);
+                        ui.label(&item.name);
+                        for choice in StandardChoice::ALL {
+                            let text = format!(
+                                "{} ({:.0}%)",
+                                item.counts[choice.ordinal()],
+                                item.share(choice) * 100.0
+                            );
+                            if skewed == Some(choice) {
+                                ui.colored_label(ui.visuals().warn_fg_color, text);
+                            } else {
+                                ui.label(text);
+                            }
+                        }
+                        if let Some(choice) = skewed {
+                            ui.colored_label(ui.visuals().warn_fg_color, "⚠")
+                                .on_hover_text(format!(
+                                    "You gave “{choice}” to {:.0}% of the reviewed manuscripts.",
+                                    item.share(choice) * 100.0
+                                ));
+                        } else {
+                            ui.label("");
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
+
+/// A bar chart of the labelled values, one bar for each.
+fn bar_chart(ui: &mut egui::Ui, id: impl std::hash::Hash, bars: Vec<(String, f64)>) {
+    let chart = BarChart::new(
+        bars.iter()
+            .enumerate()
+            .map(|(i, (label, value))| Bar::new(i as f64, *value).name(label).width(0.6))
+            .collect(),
+    );
+    let labels = bars.into_iter().map(|(label, _)| label).collect::<Vec<_>>();
+
+    Plot::new(id)
+        .height(CHART_HEIGHT)
+        .allow_zoom(false)
+        .allow_drag(false)
+        .allow_scroll(false)
+        .include_y(0.0)
+        .x_axis_formatter(move |x: f64, _: &RangeInclusive<f64>| {
+            // only label the bars, not the grid lines between them.
+            if x.fract() == 0.0 && x >= 0.0 {
+                labels.get(x as usize).cloned().unwrap_or_default()
+            } else {
+                String::new()
+            }
+        })
+        .show(ui, |plot| plot.bar_chart(chart));
+}
@@ -0,0 +1,134 @@
+//! Component: Dump
+
+use eframe::egui;
+
+use crate::{
+    types::rank::{
+        dump::{dump, DumpFormat},
+        MetaGroup, Rubric,
+    },
+    ui::{
+        cache::Cached,
+        file::{save_file, Filter},
+        ReviewToolApp,
+    },
+};
+
+/// The state of the dump window.
+#[derive(Default)]
+pub(crate) struct DumpState {
+    pub(crate) open: bool,
+    format: DumpFormat,
+
+    /// The dump, by the revision of the reviews, the format and the blind review.
+    dumped: Cached<(u64, DumpFormat, bool), Result<String, String>>,
+
+    /// The result of the last copy or save.
+    message: Option<Result<String, String>>,
+}
+
+/// Add `dump_window()` to Review Tool App. This trait is for specialization.
+pub(crate) trait DumpExt {
+    /// Show the reviews in memory in the chosen format.
+    fn dump_window(&mut self, ctx: &egui::Context);
+}
+
+impl<M: MetaGroup> DumpExt for ReviewToolApp<M> {
+    default fn dump_window(&mut self, _ctx: &egui::Context) {}
+}
+
+impl<R: Rubric> DumpExt for ReviewToolApp<R> {
+    fn dump_window(&mut self, ctx: &egui::Context) {
+        if !self.dump.open {
+            return;
+        }
+
+        let format = self.dump.format;
+        let mut cache = std::mem::take(&mut self.dump.dumped);
+        let dumped = cache.get((self.reviews_revision, format, self.blind), || {
+            dump(format, &self.manuscripts, &self.rank_groups, |m| {
+                self.blinded(&m.title, m).into_owned()
+            })
+            .map_err(|e| e.to_string())
+        });
+        self.dump.dumped = cache;
+
+        let mut open = self.dump.open;
+        egui::Window::new("Dump")
+            .open(&mut open)
+            .default_size([560.0, 480.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Format:");
+                    for f in DumpFormat::ALL {
+                        ui.selectable_value(&mut self.dump.format, f, f.name());
+                    }
+                });
+                ui.label("The reviews in memory, including the changes not saved yet.");
+
+                let text = match dumped.as_ref() {
+                    Ok(text) => text,
+                    Err(e) => {
+                        ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {e}"));
+                        return;
+                    }
+                };
+
+                ui.horizontal(|ui| {
+                    if ui.button("📋 Copy").clicked() {
+                        ui.output_mut(|o| o.copied_text = text.clone());
+                        self.state.dump();
+                        self.dump.message = Some(Ok(format!(
+                            "Copied the {} dump to the clipboard.",
+                            format.name()
+                        )));
+                    }
+                    if ui.button("Save…").clicked() {
+                        self.dump.message = Some(self.save_dump(format, text));
+                    }
+                });
+
+                match &self.dump.message {
+                    Some(Ok(message)) => {
+                        ui.label(message);
+                    }
+                    Some(Err(message)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {message}"));
+                    }
+                    None => {}
+                }
+
+                ui.separator();
+                egui::ScrollArea::both().show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut text.as_str())
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+            });
+
+        self.dump.open = open;
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    fn save_dump(&mut self, format: DumpFormat, text: &str) -> Result<String, String> {
+        let extensions: &[&str] = match format {
+            DumpFormat::Yaml => &["yaml", "yml"],
+            DumpFormat::Json => &["json"],
+            DumpFormat::Summary => &["txt"],
+        };
+        let filter: Filter<'_> = (format.name(), extensions);
+        let name = format!("reviews-{}.{}", R::ID, format.extension());
+
+        match save_file(&name, filter, text.as_bytes()) {
+            Ok(Some(saved)) => {
+                self.state.dump();
+                Ok(format!("Saved the {} dump to {saved}.", format.name()))
+            }
+            Ok(None) => Ok("The save has been cancelled.".to_owned()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
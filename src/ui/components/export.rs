@@ -0,0 +1,248 @@
+//! Component: Review Export and Import
+
+use eframe::egui;
+
+use crate::{
+    types::{
+        rank::{
+            crdt::ReviewSetExport,
+            export::{ImportMode, ImportSummary, ReviewExport},
+            undo::CommandKind,
+            MetaGroup, Rubric,
+        },
+        timestamp::Timestamp,
+    },
+    ui::{
+        file::{save_file, FilePicker, Filter, OpenedFile},
+        ReviewToolApp,
+    },
+};
+
+/// The error when the reviews are not replaced, since the snapshot of them failed.
+pub(crate) const NO_SNAPSHOT: &str =
+    "Failed to keep a snapshot of the reviews, so they are not changed.";
+
+/// The filter of the export files.
+const EXPORT_FILTER: Filter<'static> = ("Review export (YAML)", &["yaml", "yml"]);
+
+/// The state of the export window.
+pub(crate) struct ExportState {
+    /// Is the window open?
+    pub(crate) open: bool,
+
+    /// The name of the reviewer, written to the exports.
+    pub(crate) reviewer: String,
+
+    mode: ImportMode,
+    picker: FilePicker,
+
+    /// The picker of the files to merge.
+    merge_picker: FilePicker,
+
+    /// The result of the last export or import.
+    message: Option<Result<String, String>>,
+}
+
+impl ExportState {
+    pub(crate) fn new(reviewer: String) -> Self {
+        Self {
+            open: false,
+            reviewer,
+            mode: ImportMode::Merge,
+            picker: FilePicker::default(),
+            merge_picker: FilePicker::default(),
+            message: None,
+        }
+    }
+}
+
+/// Add `export_window()` to Review Tool App. This trait is for specialization.
+pub(crate) trait ExportExt {
+    fn export_window(&mut self, ctx: &egui::Context);
+}
+
+impl<M: MetaGroup> ExportExt for ReviewToolApp<M> {
+    default fn export_window(&mut self, _ctx: &egui::Context) {}
+}
+
+impl<R: Rubric> ExportExt for ReviewToolApp<R> {
+    fn export_window(&mut self, ctx: &egui::Context) {
+        if let Some(opened) = self.export.picker.take() {
+            self.export.message = Some(self.import_reviews(opened.map_err(|e| e.to_string())));
+        }
+        if let Some(opened) = self.export.merge_picker.take() {
+            self.export.message = Some(self.merge_reviews(opened.map_err(|e| e.to_string())));
+        }
+
+        let mut open = self.export.open;
+        egui::Window::new("Export / Import")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Reviewer:");
+                    ui.text_edit_singleline(&mut self.export.reviewer);
+                });
+
+                if ui
+                    .button("Export…")
+                    .on_hover_text("Save the reviews of this rubric to a file")
+                    .clicked()
+                {
+                    self.export.message = Some(self.export_reviews());
+                }
+
+                ui.separator();
+
+                ui.radio_value(
+                    &mut self.export.mode,
+                    ImportMode::Merge,
+                    "Merge: import the manuscripts not reviewed yet",
+                );
+                ui.radio_value(
+                    &mut self.export.mode,
+                    ImportMode::Restore,
+                    "Restore: replace all the current reviews",
+                );
+
+                if ui.button("Import…").clicked() {
+                    self.export.picker.open(ui.ctx(), EXPORT_FILTER);
+                }
+
+                ui.separator();
+
+                ui.label("Merge the reviews edited offline on another device:");
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Export for merging…")
+                        .on_hover_text("Save the reviews with the history of the edits")
+                        .clicked()
+                    {
+                        self.export.message = Some(self.export_review_set());
+                    }
+                    if ui
+                        .button("Merge…")
+                        .on_hover_text("Merge the edits of another device, keeping every comment")
+                        .clicked()
+                    {
+                        self.export.merge_picker.open(ui.ctx(), EXPORT_FILTER);
+                    }
+                });
+
+                match &self.export.message {
+                    Some(Ok(message)) => {
+                        ui.separator();
+                        ui.label(message);
+                    }
+                    Some(Err(message)) => {
+                        ui.separator();
+                        ui.colored_label(ui.visuals().error_fg_color, message);
+                    }
+                    None => {}
+                }
+            });
+        self.export.open = open;
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    fn export_reviews(&self) -> Result<String, String> {
+        let export = ReviewExport::new(&self.export.reviewer, &self.manuscripts, &self.rank_groups)
+            .with_history(&self.changelog);
+        let document = export.to_yaml().map_err(|e| e.to_string())?;
+
+        match save_file(&export.file_name(), EXPORT_FILTER, document.as_bytes()) {
+            Ok(Some(saved)) => Ok(format!(
+                "Exported {} reviews to {saved}.",
+                export.reviews.len()
+            )),
+            Ok(None) => Ok("The export has been cancelled.".to_owned()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn import_reviews(&mut self, opened: Result<OpenedFile, String>) -> Result<String, String> {
+        let opened = opened?;
+        let document = String::from_utf8(opened.content).map_err(|e| e.to_string())?;
+        let export = ReviewExport::<R>::from_yaml(&document).map_err(|e| e.to_string())?;
+
+        let reviewer = export.reviewer.clone();
+        let exported_at = export.exported_at;
+        let (manuscripts, mode) = (self.manuscripts.clone(), self.export.mode);
+        let ImportSummary {
+            imported,
+            kept,
+            unknown,
+        } = self
+            .replace_reviews(CommandKind::Import, "Before importing reviews", |rank| {
+                export.import(&manuscripts, rank, mode)
+            })
+            .ok_or(NO_SNAPSHOT)?;
+
+        let mut message = format!(
+            "Imported {imported} reviews of {reviewer} (exported at {exported_at}) from {}.",
+            opened.name
+        );
+        if kept > 0 {
+            message += &format!("\nKept {kept} reviewed manuscripts.");
+        }
+        if !unknown.is_empty() {
+            message += &format!(
+                "\nIgnored {} unknown manuscripts: {}",
+                unknown.len(),
+                unknown.join(", ")
+            );
+        }
+
+        Ok(message)
+    }
+
+    fn export_review_set(&mut self) -> Result<String, String> {
+        self.review_set
+            .record(&self.replica, &self.rank_groups, Timestamp::now())
+            .map_err(|e| e.to_string())?;
+
+        let export = ReviewSetExport::new::<R>(&self.export.reviewer, self.review_set.clone());
+        let document = export.to_yaml().map_err(|e| e.to_string())?;
+
+        match save_file(&export.file_name(), EXPORT_FILTER, document.as_bytes()) {
+            Ok(Some(saved)) => Ok(format!("Exported the reviews for merging to {saved}.")),
+            Ok(None) => Ok("The export has been cancelled.".to_owned()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn merge_reviews(&mut self, opened: Result<OpenedFile, String>) -> Result<String, String> {
+        let opened = opened?;
+        let document = String::from_utf8(opened.content).map_err(|e| e.to_string())?;
+        let export = ReviewSetExport::from_yaml::<R>(&document).map_err(|e| e.to_string())?;
+
+        // Record the local edits first, so they are merged rather than overwritten.
+        self.review_set
+            .record(&self.replica, &self.rank_groups, Timestamp::now())
+            .map_err(|e| e.to_string())?;
+
+        let mut merged = self.review_set.clone();
+        merged.merge(&export.reviews);
+        let resolved = merged.resolve().map_err(|e| e.to_string())?;
+        self.replace_reviews(CommandKind::Merge, "Before merging reviews", |rank| {
+            *rank = resolved
+        })
+        .ok_or(NO_SNAPSHOT)?;
+        self.review_set = merged;
+
+        let mut message = format!(
+            "Merged the reviews of {} (exported at {}) from {}.",
+            export.reviewer, export.exported_at, opened.name
+        );
+        let conflicts = self.review_set.conflicts().count();
+        if conflicts > 0 {
+            message += &format!(
+                "\n{conflicts} fields were edited on both devices: the comments are joined \
+                 with a blank line, and the others take the later edit by the device clocks."
+            );
+        }
+
+        Ok(message)
+    }
+}
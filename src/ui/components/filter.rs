@@ -0,0 +1,124 @@
+//! Component: Manuscript Filter
+
+use std::collections::BTreeSet;
+
+use eframe::egui;
+
+use crate::{
+    types::{
+        rank::{filter::ReviewedFilter, MetaGroup, Rubric},
+        Difficulty, ManuscriptId,
+    },
+    ui::ReviewToolApp,
+};
+
+/// Add `filter_bar()` and `is_filtered_in()` to Review Tool App. This trait is for specialization.
+pub(crate) trait FilterExt {
+    /// The structured filter of the manuscript list.
+    ///
+    /// `listed` and `total` are the numbers of the listed and all the manuscripts.
+    fn filter_bar(&mut self, ui: &mut egui::Ui, listed: usize, total: usize);
+
+    /// Does the manuscript meet the filter?
+    fn is_filtered_in(&self, id: &ManuscriptId) -> bool;
+}
+
+impl<M: MetaGroup> FilterExt for ReviewToolApp<M> {
+    default fn filter_bar(&mut self, _ui: &mut egui::Ui, _listed: usize, _total: usize) {}
+
+    default fn is_filtered_in(&self, _id: &ManuscriptId) -> bool {
+        true
+    }
+}
+
+impl<R: Rubric> FilterExt for ReviewToolApp<R> {
+    fn filter_bar(&mut self, ui: &mut egui::Ui, listed: usize, total: usize) {
+        let types = self
+            .manuscripts
+            .values()
+            .map(|m| m.type_.clone())
+            .collect::<BTreeSet<_>>();
+        let filter = &mut self.filter;
+
+        egui::CollapsingHeader::new(format!("Filter ({listed} of {total} shown)"))
+            .id_source("manuscript-filter")
+            .default_open(filter.is_active())
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for reviewed in ReviewedFilter::ALL {
+                        ui.selectable_value(&mut filter.reviewed, reviewed, reviewed.name());
+                    }
+                });
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("Difficulty:");
+                    for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+                        toggle_in(
+                            ui,
+                            &mut filter.difficulties,
+                            difficulty,
+                            difficulty.as_ref(),
+                        );
+                    }
+                });
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("Type:");
+                    for type_ in types {
+                        let text = type_.clone();
+                        toggle_in(ui, &mut filter.types, type_, &text);
+                    }
+                });
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("Score:");
+                    optional_bound(ui, "min", &mut filter.min_score);
+                    optional_bound(ui, "max", &mut filter.max_score);
+                });
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.checkbox(&mut filter.commented_only, "Has my comment");
+
+                    if ui
+                        .add_enabled(filter.is_active(), egui::Button::new("Clear"))
+                        .on_hover_text("Clear all the conditions")
+                        .clicked()
+                    {
+                        filter.clear();
+                    }
+                });
+            });
+    }
+
+    fn is_filtered_in(&self, id: &ManuscriptId) -> bool {
+        let Some(manuscript) = self.manuscripts.get(id) else {
+            return false;
+        };
+
+        self.filter
+            .matches(manuscript, self.rank_groups.get(id), self.tags.get(id))
+    }
+}
+
+/// A toggle which adds `value` to or removes it from `set`.
+fn toggle_in<T: Ord>(ui: &mut egui::Ui, set: &mut BTreeSet<T>, value: T, text: &str) {
+    let mut checked = set.contains(&value);
+    if ui.toggle_value(&mut checked, text).changed() {
+        if checked {
+            set.insert(value);
+        } else {
+            set.remove(&value);
+        }
+    }
+}
+
+/// A checkbox to enable a bound, with the value of the bound.
+fn optional_bound(ui: &mut egui::Ui, label: &str, bound: &mut Option<f64>) {
+    let mut enabled = bound.is_some();
+    if ui.checkbox(&mut enabled, label).changed() {
+        *bound = enabled.then_some(0.0);
+    }
+    if let Some(value) = bound {
+        ui.add(egui::DragValue::new(value).speed(0.5));
+    }
+}
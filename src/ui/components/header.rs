@@ -1,14 +1,17 @@
 //! Component: Header
 
 use eframe::{
-    egui::{Key, Modifiers},
+    egui::{self, Key, Modifiers},
     App, Storage,
 };
-use serde::Serialize;
 
 use crate::{
-    types::rank::MetaGroup,
-    ui::{ReviewToolApp, RANK_KEY},
+    types::rank::{registry, MetaGroup, Rubric},
+    ui::{RankBackup, ReviewToolApp, View},
+};
+
+use super::{
+    snapshots::SnapshotExt, spreadsheet::SpreadsheetExt, undo::UndoExt, workspace::WorkspaceExt,
 };
 
 trait SaveExt {
@@ -19,7 +22,7 @@ impl<M: MetaGroup> SaveExt for ReviewToolApp<M> {
     default fn save_btn(&mut self, _ui: &mut eframe::egui::Ui, _storage: &mut dyn Storage) {}
 }
 
-impl<M: MetaGroup + Serialize> SaveExt for ReviewToolApp<M> {
+impl<R: Rubric> SaveExt for ReviewToolApp<R> {
     fn save_btn(&mut self, ui: &mut eframe::egui::Ui, storage: &mut dyn Storage) {
         if ui.button("Save").clicked()
             || ui.input_mut(|s| s.consume_key(Modifiers::COMMAND, Key::S))
@@ -38,42 +41,105 @@ impl<M: MetaGroup> ReviewToolApp<M> {
                     ui.label("Current manuscripts:");
                     ui.label(self.manuscripts.len().to_string());
                 });
+                if !self.workspace.file.name.is_empty() {
+                    ui.label(format!("Workspace: {}", self.workspace.file.name));
+                }
             });
 
             ui.separator();
-            if let Some(s) = frame.storage_mut() {
-                if ui.button("Dump").clicked() {
-                    #[cfg(not(target_family = "wasm"))]
-                    {
-                        println!("{}", s.get_string(RANK_KEY).unwrap_or_default());
-                    }
+            self.rubric_menu(ui);
 
-                    #[cfg(target_family = "wasm")]
-                    {
-                        web_sys::console::log_1(&s.get_string(RANK_KEY).unwrap_or_default().into());
-                    }
+            ui.separator();
+            ui.selectable_value(&mut self.view, View::Review, "Review");
+            ui.selectable_value(&mut self.view, View::Leaderboard, "Leaderboard");
+            ui.selectable_value(&mut self.view, View::Dashboard, "Dashboard");
+            ui.selectable_value(&mut self.view, View::Pairwise, "Pairwise");
+            ui.selectable_value(&mut self.view, View::Aggregate, "Aggregate");
+            ui.selectable_value(&mut self.view, View::Program, "Program");
 
-                    self.state.dump();
-                }
-            }
+            ui.separator();
+            ui.toggle_value(&mut self.blind, "🙈 Blind")
+                .on_hover_text("Hide the speaker identity");
+
+            ui.separator();
+            ui.toggle_value(&mut self.dump.open, "Dump")
+                .on_hover_text("Show the reviews in memory as YAML, JSON or a summary");
             if let Some(s) = frame.storage_mut() {
                 self.save_btn(ui, s);
             }
+            self.undo_buttons(ui);
+            self.workspace_menu(ui);
+            ui.toggle_value(&mut self.export.open, "Export / Import")
+                .on_hover_text("Exchange the reviews with a file");
+            self.spreadsheet_menu(ui);
+            ui.toggle_value(&mut self.planner.open, "Assignment")
+                .on_hover_text("Assign the manuscripts to the reviewers");
+            ui.toggle_value(&mut self.sync.open, "Sync")
+                .on_hover_text("Sync the reviews with a sync server");
+            ui.toggle_value(&mut self.letters.open, "Letters")
+                .on_hover_text("Write the feedback letters to the authors");
             if ui
                 .button("Reset")
-                .on_hover_text("Double click to reset")
+                .on_hover_text(
+                    "Double click to reset all the reviews. \
+                     It can be undone, and a snapshot is kept to restore them.",
+                )
                 .double_clicked()
             {
-                // Clear the rank groups = Reset
-                self.rank_groups.clear();
-                self.state.reset();
+                self.reset_all();
             }
+            ui.toggle_value(&mut self.snapshots.open, "Snapshots")
+                .on_hover_text("Restore the reviews as of before a reset");
 
             ui.separator();
             ui.horizontal(|ui| {
                 ui.label("Status: ");
                 ui.label(self.state.get_human_text());
+
+                if self.autosave.is_dirty() {
+                    ui.colored_label(ui.visuals().warn_fg_color, "● Unsaved")
+                        .on_hover_text(if self.autosave.has_recovery() {
+                            "The changes have been autosaved to the recovery copy. Save to keep them."
+                        } else {
+                            "The changes will be autosaved to the recovery copy shortly."
+                        });
+                }
             });
+
+            match &self.rank_backup {
+                RankBackup::None => {}
+                RankBackup::Pending(_) => {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        "⚠ Failed to load the saved rank. It will be backed up on the next save.",
+                    );
+                }
+                RankBackup::Done(key) => {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!("⚠ The unloadable rank has been backed up to `{key}`."),
+                    );
+                }
+            }
         });
     }
 }
+
+impl<M: MetaGroup> ReviewToolApp<M> {
+    /// The menu to switch the rubric.
+    fn rubric_menu(&mut self, ui: &mut eframe::egui::Ui) {
+        let current = registry::find(self.rubric_id).map_or(self.rubric_id, |r| r.name);
+
+        egui::ComboBox::from_label("Rubric")
+            .selected_text(current)
+            .show_ui(ui, |ui| {
+                for rubric in registry::RUBRICS {
+                    let selected = rubric.id == self.rubric_id;
+
+                    if ui.selectable_label(selected, rubric.name).clicked() && !selected {
+                        self.rubric_request = Some(rubric);
+                    }
+                }
+            });
+    }
+}
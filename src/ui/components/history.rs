@@ -0,0 +1,181 @@
+//! Component: Change History
+
+use std::collections::HashMap;
+
+use eframe::egui;
+use serde_yaml::Value;
+
+use crate::{
+    types::{
+        rank::{
+            sheet::ReviewSheet,
+            undo::{self, snapshot, Change},
+            GroupMetaDatabase, MetaGroup, Rubric,
+        },
+        timestamp::Timestamp,
+        ManuscriptId,
+    },
+    ui::ReviewToolApp,
+};
+
+/// The values longer than this are truncated in the timeline.
+const MAX_VALUE_CHARS: usize = 40;
+
+/// Add `change_history()` to Review Tool App. This trait is for specialization.
+pub(crate) trait HistoryExt {
+    /// Show the change history of the selected manuscript.
+    fn change_history(&mut self, ui: &mut egui::Ui);
+}
+
+impl<M: MetaGroup> HistoryExt for ReviewToolApp<M> {
+    default fn change_history(&mut self, _ui: &mut egui::Ui) {}
+}
+
+impl<R: Rubric> HistoryExt for ReviewToolApp<R> {
+    fn change_history(&mut self, ui: &mut egui::Ui) {
+        let id = self.current_selected;
+        let entries = self.changelog.entries(&id);
+
+        egui::CollapsingHeader::new(format!("History ({} changes)", entries.len()))
+            .id_source(("change-history", id))
+            .show(ui, |ui| {
+                if let Some(times) = self.changelog.times(&id) {
+                    ui.label(format!("Started: {}", times.started));
+                    match times.finished {
+                        Some(finished) => ui.label(format!("Finished: {finished}")),
+                        None => ui.label("Finished: not yet"),
+                    };
+                    if let Some(seconds) = times.duration() {
+                        ui.label(format!("Time spent: {}", duration_text(seconds)));
+                    }
+                    ui.separator();
+                }
+
+                if entries.is_empty() {
+                    ui.label("No changes yet.");
+                }
+
+                egui::Grid::new(("change-history-entries", id))
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for entry in entries.iter().rev() {
+                            ui.label(entry.at.to_string());
+                            ui.label(&entry.field);
+                            ui.horizontal(|ui| {
+                                value_label(ui, &entry.old);
+                                ui.label("→");
+                                value_label(ui, &entry.new);
+                            });
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    /// Write the reviews with `write`, and log what it changed.
+    ///
+    /// Every write to the reviews goes through this – resets, restores,
+    /// imports, merges, syncs, workspaces and recoveries – except the edits
+    /// in the review form, which the undo history tracks and logs.
+    pub(crate) fn write_reviews<T>(
+        &mut self,
+        write: impl FnOnce(&mut GroupMetaDatabase<R>) -> T,
+    ) -> (T, Vec<Change>) {
+        let before = self
+            .rank_groups
+            .iter()
+            .map(|(id, meta)| Ok((*id, snapshot(Some(meta))?)))
+            .collect::<Result<HashMap<_, _>, undo::Error>>();
+
+        let result = write(&mut self.rank_groups);
+
+        let before = match before {
+            Ok(before) => before,
+            Err(e) => {
+                tracing::error!("failed to log the changes of the reviews: {e}");
+                return (result, Vec::new());
+            }
+        };
+        let ids = before.keys().chain(
+            self.rank_groups
+                .keys()
+                .filter(|id| !before.contains_key(id)),
+        );
+        let changes = ids
+            .map(|id| {
+                Ok(Change {
+                    id: *id,
+                    before: before.get(id).cloned().flatten(),
+                    after: snapshot(self.rank_groups.get(id))?,
+                })
+            })
+            .collect::<Result<Vec<_>, undo::Error>>();
+
+        match changes {
+            Ok(mut changes) => {
+                changes.retain(|c| c.before != c.after);
+                self.log_changes(changes.iter().map(|c| (c.id, &c.before, &c.after)));
+                (result, changes)
+            }
+            Err(e) => {
+                tracing::error!("failed to log the changes of the reviews: {e}");
+                (result, Vec::new())
+            }
+        }
+    }
+
+    /// Log the changes of the meta groups, serialized as in the undo history.
+    ///
+    /// The values computed from the reviews are computed again after a change,
+    /// and the review is recorded in the blind log once it is marked as reviewed.
+    pub(crate) fn log_changes<'a>(
+        &mut self,
+        changes: impl IntoIterator<Item = (ManuscriptId, &'a Option<Value>, &'a Option<Value>)>,
+    ) {
+        let at = Timestamp::now();
+        let sheet = |value: &Option<Value>| -> Result<ReviewSheet, serde_yaml::Error> {
+            let rank = match value {
+                Some(v) => serde_yaml::from_value::<R>(v.clone())?,
+                None => R::default(),
+            };
+            Ok(ReviewSheet::of(&rank))
+        };
+
+        for (id, before, after) in changes {
+            self.reviews_revision += 1;
+            self.sync.edited_at.insert(id, at);
+            match (sheet(before), sheet(after)) {
+                (Ok(before), Ok(after)) => {
+                    if !before.reviewed && after.reviewed {
+                        self.blind_log.record_review(id);
+                    }
+                    self.changelog.record(id, &before, &after, at);
+                }
+                (Err(e), _) | (_, Err(e)) => tracing::error!("failed to log the change: {e}"),
+            }
+        }
+    }
+}
+
+/// Show a value, truncated if it is long, with the full value on hover.
+fn value_label(ui: &mut egui::Ui, value: &str) {
+    if value.is_empty() {
+        ui.weak("(empty)");
+    } else if value.chars().count() > MAX_VALUE_CHARS {
+        let truncated = value.chars().take(MAX_VALUE_CHARS).collect::<String>();
+        ui.label(format!("{truncated}…")).on_hover_text(value);
+    } else {
+        ui.label(value);
+    }
+}
+
+fn duration_text(seconds: u64) -> String {
+    match seconds {
+        0..=59 => format!("{seconds}s"),
+        60..=3599 => format!("{}m {}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
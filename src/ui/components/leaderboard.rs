@@ -0,0 +1,146 @@
+//! Component: Leaderboard
+
+use std::collections::HashMap;
+
+use eframe::egui;
+use egui::RichText;
+
+use crate::{
+    types::rank::{
+        leaderboard::{Leaderboard, SortKey},
+        sheet::ReviewSheet,
+        MetaGroup, Rubric,
+    },
+    ui::{cache::Cached, ReviewToolApp, View},
+};
+
+/// The state of the leaderboard view.
+pub(crate) struct LeaderboardState {
+    key: SortKey,
+    descending: bool,
+
+    /// The sorted leaderboard, by the revision of the reviews and the order.
+    leaderboard: Cached<(u64, SortKey, bool), Leaderboard>,
+}
+
+impl Default for LeaderboardState {
+    fn default() -> Self {
+        Self {
+            key: SortKey::Total,
+            descending: true,
+            leaderboard: Cached::default(),
+        }
+    }
+}
+
+impl LeaderboardState {
+    /// The header of a sortable column.
+    fn header(&mut self, ui: &mut egui::Ui, key: SortKey, text: &str) {
+        let text = match (self.key == key, self.descending) {
+            (true, true) => format!("{text} ⏷"),
+            (true, false) => format!("{text} ⏶"),
+            (false, _) => text.to_owned(),
+        };
+
+        if ui
+            .selectable_label(self.key == key, RichText::new(text).strong())
+            .on_hover_text("Click to sort by this column")
+            .clicked()
+        {
+            if self.key == key {
+                self.descending = !self.descending;
+            } else {
+                // the scores are sorted from the highest, the text from A.
+                self.descending =
+                    matches!(key, SortKey::Group(_) | SortKey::Total | SortKey::Reviewed);
+                self.key = key;
+            }
+        }
+    }
+}
+
+/// Add `leaderboard()` to Review Tool App. This trait is for specialization.
+pub(crate) trait LeaderboardExt {
+    fn leaderboard(&mut self, ui: &mut egui::Ui);
+}
+
+impl<M: MetaGroup> LeaderboardExt for ReviewToolApp<M> {
+    default fn leaderboard(&mut self, ui: &mut egui::Ui) {
+        ui.label("This meta group has not been supported, sorry :(");
+    }
+}
+
+impl<R: Rubric> LeaderboardExt for ReviewToolApp<R> {
+    fn leaderboard(&mut self, ui: &mut egui::Ui) {
+        let (key, descending) = (self.leaderboard.key, self.leaderboard.descending);
+        let (manuscripts, rank_groups) = (&self.manuscripts, &self.rank_groups);
+        let leaderboard =
+            self.leaderboard
+                .leaderboard
+                .get((self.reviews_revision, key, descending), || {
+                    let reviews = rank_groups
+                        .iter()
+                        .map(|(id, rank)| (*id, ReviewSheet::of(rank)))
+                        .collect::<HashMap<_, _>>();
+                    let mut leaderboard =
+                        Leaderboard::new(manuscripts, &ReviewSheet::of(&R::default()), &reviews);
+                    leaderboard.sort(key, descending);
+
+                    leaderboard
+                });
+        let titles = leaderboard
+            .rows
+            .iter()
+            .map(|row| {
+                self.manuscripts.get(&row.id).map_or_else(
+                    || row.title.clone(),
+                    |m| self.blinded(&m.title, m).into_owned(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("leaderboard")
+                .num_columns(6 + leaderboard.groups.len())
+                .striped(true)
+                .show(ui, |ui| {
+                    let state = &mut self.leaderboard;
+
+                    ui.label(RichText::new("#").strong());
+                    state.header(ui, SortKey::Title, "Title");
+                    state.header(ui, SortKey::Type, "Type");
+                    state.header(ui, SortKey::Difficulty, "Difficulty");
+                    for (i, group) in leaderboard.groups.iter().enumerate() {
+                        state.header(ui, SortKey::Group(i), group);
+                    }
+                    state.header(ui, SortKey::Total, "Total");
+                    state.header(ui, SortKey::Reviewed, "Reviewed");
+                    ui.end_row();
+
+                    for (rank, (row, title)) in leaderboard.rows.iter().zip(titles).enumerate() {
+                        ui.label((rank + 1).to_string());
+
+                        if ui
+                            .selectable_label(self.current_selected == row.id, title)
+                            .on_hover_text("Click to review this manuscript")
+                            .clicked()
+                        {
+                            self.current_selected = row.id;
+                            self.view = View::Review;
+                        }
+                        ui.label(&row.type_);
+                        ui.label(row.difficulty.to_string());
+                        for score in &row.scores {
+                            ui.label(score.map_or_else(|| "-".to_owned(), |s| format!("{s:.2}")));
+                        }
+                        ui.label(
+                            row.total
+                                .map_or_else(|| "-".to_owned(), |s| format!("{s:.2}")),
+                        );
+                        ui.label(if row.reviewed { "✔" } else { "" });
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
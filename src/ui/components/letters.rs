@@ -0,0 +1,240 @@
+//! Component: Author Feedback Letters
+
+use std::collections::HashMap;
+
+use eframe::egui;
+use egui::RichText;
+
+use crate::{
+    types::{
+        letter::{self, Decision, Language, Letter, LetterTemplate, LetterTemplates, PLACEHOLDERS},
+        rank::{sheet::ReviewSheet, MetaGroup, Rubric},
+        timestamp::Timestamp,
+    },
+    ui::{
+        file::{save_file, Filter},
+        ReviewToolApp,
+    },
+};
+
+const MARKDOWN_FILTER: Filter<'static> = ("Markdown", &["md"]);
+const MBOX_FILTER: Filter<'static> = ("Mailbox (mbox)", &["mbox"]);
+
+/// The state of the letter window.
+pub(crate) struct LetterState {
+    /// Is the window open?
+    pub(crate) open: bool,
+
+    pub(crate) templates: LetterTemplates,
+
+    /// The sender address of the emails.
+    pub(crate) from: String,
+
+    language: Language,
+
+    /// The result of the last export.
+    message: Option<Result<String, String>>,
+}
+
+impl LetterState {
+    pub(crate) fn new(templates: LetterTemplates, from: String) -> Self {
+        Self {
+            open: false,
+            templates,
+            from,
+            language: Language::default(),
+            message: None,
+        }
+    }
+}
+
+/// Add `letter_window()` to Review Tool App. This trait is for specialization.
+pub(crate) trait LetterExt {
+    fn letter_window(&mut self, ctx: &egui::Context);
+}
+
+impl<M: MetaGroup> LetterExt for ReviewToolApp<M> {
+    default fn letter_window(&mut self, _ctx: &egui::Context) {}
+}
+
+impl<R: Rubric> LetterExt for ReviewToolApp<R> {
+    fn letter_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.letters.open;
+        egui::Window::new("Feedback letters")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Language:");
+                    for language in Language::ALL {
+                        ui.selectable_value(&mut self.letters.language, language, language.name());
+                    }
+                });
+
+                let language = self.letters.language;
+                let template = self.letters.templates.get_mut(language);
+                egui::Grid::new("letter-template")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Subject:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut template.subject)
+                                .desired_width(f32::INFINITY),
+                        );
+                        ui.end_row();
+
+                        ui.label("Body:");
+                        ui.add(
+                            egui::TextEdit::multiline(&mut template.body)
+                                .code_editor()
+                                .desired_rows(12)
+                                .desired_width(f32::INFINITY),
+                        );
+                        ui.end_row();
+                    });
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(RichText::new("Placeholders:").small().weak());
+                    for (name, description) in PLACEHOLDERS {
+                        ui.label(RichText::new(format!("{{{{{name}}}}}")).small().code())
+                            .on_hover_text(*description);
+                    }
+                });
+                if ui
+                    .button("Reset template")
+                    .on_hover_text("Double click to restore the built-in template")
+                    .double_clicked()
+                {
+                    *template = LetterTemplate::builtin(language);
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("From:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.letters.from)
+                            .hint_text("committee@example.com"),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Save Markdown…")
+                        .on_hover_text("All the letters in a Markdown file")
+                        .clicked()
+                    {
+                        self.letters.message = Some(self.save_letters(MARKDOWN_FILTER));
+                    }
+                    if ui
+                        .button("Save mbox…")
+                        .on_hover_text("All the letters in a mailbox to review in a mail client")
+                        .clicked()
+                    {
+                        self.letters.message = Some(self.save_letters(MBOX_FILTER));
+                    }
+                });
+
+                match &self.letters.message {
+                    Some(Ok(message)) => {
+                        ui.label(message);
+                    }
+                    Some(Err(message)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, message);
+                    }
+                    None => {}
+                }
+
+                ui.label(
+                    RichText::new(
+                        "The letters are written for the reviewed manuscripts with a decision, \
+                         set next to the shortlist star. The undecided ones are skipped. The \
+                         private notes, the tags and the item comments are never included.",
+                    )
+                    .small()
+                    .weak(),
+                );
+
+                ui.separator();
+                ui.label(RichText::new("Preview of the current manuscript").strong());
+                egui::ScrollArea::vertical()
+                    .max_height(240.0)
+                    .show(ui, |ui| match self.current_letter() {
+                        Ok(Some(letter)) => {
+                            ui.label(letter.to_markdown());
+                        }
+                        Ok(None) => {
+                            ui.label(
+                                RichText::new(
+                                    "The current manuscript has not been reviewed or decided.",
+                                )
+                                .weak(),
+                            );
+                        }
+                        Err(e) => {
+                            ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                        }
+                    });
+            });
+        self.letters.open = open;
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    /// The letters of the reviewed manuscripts.
+    fn feedback_letters(&self) -> Result<Vec<Letter>, letter::Error> {
+        let sheets = self
+            .rank_groups
+            .iter()
+            .map(|(id, rank)| (*id, ReviewSheet::of(rank)))
+            .collect::<HashMap<_, _>>();
+
+        Letter::of_reviewed(
+            &self.manuscripts,
+            &self.tags,
+            |id| sheets.get(id),
+            self.letters.templates.get(self.letters.language),
+            self.letters.language,
+        )
+    }
+
+    /// The letter of the current manuscript, if it has been reviewed and decided.
+    fn current_letter(&self) -> Result<Option<Letter>, letter::Error> {
+        let decision = Decision::of(&self.tags, &self.current_selected);
+        let Some(sheet) = self
+            .rank_groups
+            .get(&self.current_selected)
+            .map(ReviewSheet::of)
+            .filter(|s| s.reviewed && !decision.is_undecided())
+        else {
+            return Ok(None);
+        };
+
+        let language = self.letters.language;
+        self.letters
+            .templates
+            .get(language)
+            .render(self.get_current_manuscript(), decision, &sheet, language)
+            .map(Some)
+    }
+
+    fn save_letters(&self, filter: Filter<'static>) -> Result<String, String> {
+        let letters = self.feedback_letters().map_err(|e| e.to_string())?;
+        if letters.is_empty() {
+            return Err("No manuscript has been reviewed and decided.".to_owned());
+        }
+
+        let now = Timestamp::now();
+        let document = if filter == MBOX_FILTER {
+            letter::to_mbox(&letters, self.letters.from.trim(), now)
+        } else {
+            letter::to_markdown(&letters)
+        };
+
+        let name = format!("letters-{}.{}", now.file_stamp(), filter.1[0]);
+        match save_file(&name, filter, document.as_bytes()) {
+            Ok(Some(saved)) => Ok(format!("Saved {} letters to {saved}.", letters.len())),
+            Ok(None) => Ok("The export has been cancelled.".to_owned()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
@@ -1,17 +1,52 @@
 //! Component: Manuscript Info
 
+use std::borrow::Cow;
+
 use eframe::egui;
 use egui::{RichText, TextEdit};
 
-use crate::{types::rank::MetaGroup, ui::ReviewToolApp};
+use crate::{
+    types::{
+        blind::{mask, name_parts},
+        rank::MetaGroup,
+        Manuscript,
+    },
+    ui::ReviewToolApp,
+};
 
 impl<M: MetaGroup> ReviewToolApp<M> {
     pub(crate) fn manuscript(&mut self, ui: &mut eframe::egui::Ui) {
-        let selected = self.get_current_manuscript();
+        self.tag_editor(ui);
+        self.private_note(ui);
+
+        let selected = self.get_current_manuscript().clone();
+        if !self.blind {
+            self.blind_log.mark_unblinded(self.current_selected);
+        }
 
-        ui.heading(selected.title.as_str());
+        ui.heading(self.blinded(&selected.title, &selected).as_ref());
         ui.label(selected.type_.as_str());
-        ui.label(selected.abstract_.as_str());
+        ui.label(self.blinded(&selected.abstract_, &selected).as_ref());
+
+        if self.blind {
+            if let Some(leaks) = self
+                .identity_leaks
+                .get(&self.current_selected)
+                .filter(|l| !l.is_empty())
+            {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    "⚠ This manuscript may reveal the speaker.",
+                )
+                .on_hover_text(
+                    leaks
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                );
+            }
+        }
 
         ui.separator();
 
@@ -25,9 +60,11 @@ impl<M: MetaGroup> ReviewToolApp<M> {
                     ui.horizontal_top(|ui| {
                         ui.label("Audience: ");
 
-                        TextEdit::multiline(&mut selected.audience.as_str())
-                            .desired_rows(1)
-                            .show(ui);
+                        TextEdit::multiline(
+                            &mut self.blinded(&selected.audience, &selected).as_ref(),
+                        )
+                        .desired_rows(1)
+                        .show(ui);
                     });
 
                     ui.horizontal_top(|ui| {
@@ -41,25 +78,41 @@ impl<M: MetaGroup> ReviewToolApp<M> {
 
                 ui.vertical(|ui| {
                     ui.heading("Author");
-                    let name = RichText::new(selected.author.name.as_str()).size(15.0);
 
-                    ui.label(name);
-                    ui.text_edit_multiline(&mut selected.author.description.as_str());
+                    if self.blind {
+                        ui.label(RichText::new("Hidden in the blind review").italics().weak());
+                    } else {
+                        let name = RichText::new(selected.author.name.as_str()).size(15.0);
+
+                        ui.label(name);
+                        ui.text_edit_multiline(&mut selected.author.description.as_str());
+                    }
                 });
 
                 ui.end_row();
 
                 ui.vertical(|ui| {
                     ui.heading("Description");
-                    ui.text_edit_multiline(&mut selected.description.as_str());
+                    ui.text_edit_multiline(
+                        &mut self.blinded(&selected.description, &selected).as_ref(),
+                    );
                 });
 
                 ui.vertical(|ui| {
                     ui.heading("Extra");
-                    ui.text_edit_multiline(&mut selected.extra.as_str());
+                    ui.text_edit_multiline(&mut self.blinded(&selected.extra, &selected).as_ref());
                 });
 
                 ui.end_row();
             });
     }
+
+    /// Mask the speaker name in `text` if it is in the blind mode.
+    pub(crate) fn blinded<'a>(&self, text: &'a str, manuscript: &Manuscript) -> Cow<'a, str> {
+        if self.blind {
+            mask(text, &name_parts(&manuscript.author.name))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
 }
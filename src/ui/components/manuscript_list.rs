@@ -1,50 +1,156 @@
 //! Component: Manuscript List
 
-use std::{borrow::Cow, collections::hash_map::Entry};
+use std::{
+    borrow::Cow,
+    collections::{hash_map::Entry, HashSet},
+};
 
 use eframe::egui;
-use egui::{Key, Modifiers};
+use egui::{Key, Modifiers, RichText};
 
-use crate::{types::rank::MetaGroup, ui::ReviewToolApp};
+use crate::{
+    types::{rank::MetaGroup, ManuscriptId},
+    ui::{
+        components::{filter::FilterExt, tags::tag_chip},
+        ReviewToolApp,
+    },
+};
 
 impl<M: MetaGroup> ReviewToolApp<M> {
     pub(crate) fn manuscript_list(&mut self, ui: &mut eframe::egui::Ui) {
+        let assigned = self.assignment.of(&self.export.reviewer);
+        self.assignment_filter(ui, assigned.as_ref().map(|a| a.len()));
+        self.tag_filter(ui);
+
+        let listed = self
+            .manuscripts
+            .keys()
+            .filter(|id| self.is_listed(id, assigned.as_ref()))
+            .count();
+        self.filter_bar(ui, listed, self.manuscripts.len());
+        ui.separator();
+
+        if listed == 0 {
+            ui.label(RichText::new("No manuscripts match the filter.").weak());
+        }
+
         for (id, manuscript) in self.manuscripts.iter() {
+            if !self.is_listed(id, assigned.as_ref()) {
+                continue;
+            }
+
             let reviewed = match self.rank_groups.entry(*id) {
                 Entry::Occupied(entry) => entry.get().reviewed(),
                 Entry::Vacant(_) => false,
             };
 
             // the title string
-            let title = if reviewed {
-                Cow::Owned(format!("✔ {}", manuscript.title))
-            } else {
-                Cow::Borrowed(manuscript.title.as_str())
+            let manuscript_title = self.blinded(&manuscript.title, manuscript);
+            let title = match (reviewed, self.tags.is_starred(id)) {
+                (true, true) => Cow::Owned(format!("✔ ★ {manuscript_title}")),
+                (true, false) => Cow::Owned(format!("✔ {manuscript_title}")),
+                (false, true) => Cow::Owned(format!("★ {manuscript_title}")),
+                (false, false) => manuscript_title,
             };
 
-            ui.selectable_value(&mut self.current_selected, *id, title);
+            let response = ui.selectable_value(&mut self.current_selected, *id, title);
+            if self.blind {
+                if let Some(leaks) = self.identity_leaks.get(id).filter(|l| !l.is_empty()) {
+                    response.on_hover_text(format!(
+                        "⚠ This manuscript may reveal the speaker ({} findings)",
+                        leaks.len()
+                    ));
+                }
+            }
+
+            if let Some(tags) = self.tags.get(id).filter(|t| !t.tags.is_empty()) {
+                ui.horizontal_wrapped(|ui| {
+                    for tag in tags.tags.iter() {
+                        if tag_chip(ui, tag)
+                            .on_hover_text("Click to filter by this tag")
+                            .clicked()
+                        {
+                            self.filter.tags.insert(tag.clone());
+                        }
+                    }
+                });
+            }
         }
 
-        // ↓ key to select next item
+        // ↓ key to select next listed item
         if ui.input_mut(|s| s.consume_key(Modifiers::NONE, Key::ArrowDown)) {
-            self.current_selected = match self.manuscripts.next(&self.current_selected) {
+            let next = self.manuscripts.next_by(&self.current_selected, |id| {
+                self.is_listed(id, assigned.as_ref())
+            });
+            self.current_selected = match next {
                 Some(id) => *id,
                 None => {
-                    tracing::warn!("manuscript is empty");
+                    tracing::warn!("no manuscript is listed");
                     return;
                 }
             };
         }
 
-        // ↑ key to select previous item
+        // ↑ key to select previous listed item
         if ui.input_mut(|s| s.consume_key(Modifiers::NONE, Key::ArrowUp)) {
-            self.current_selected = match self.manuscripts.previous(&self.current_selected) {
+            let previous = self.manuscripts.previous_by(&self.current_selected, |id| {
+                self.is_listed(id, assigned.as_ref())
+            });
+            self.current_selected = match previous {
                 Some(id) => *id,
                 None => {
-                    tracing::warn!("manuscript is empty");
+                    tracing::warn!("no manuscript is listed");
                     return;
                 }
             };
         }
     }
 }
+
+impl<M: MetaGroup> ReviewToolApp<M> {
+    /// Is the manuscript listed with the assignment, the shortlist and the filter?
+    fn is_listed(&self, id: &ManuscriptId, assigned: Option<&HashSet<ManuscriptId>>) -> bool {
+        if let Some(assigned) = assigned.filter(|_| self.assigned_only) {
+            if !assigned.contains(id) {
+                return false;
+            }
+        }
+        if self.starred_only && !self.tags.is_starred(id) {
+            return false;
+        }
+
+        self.is_filtered_in(id)
+    }
+
+    /// The filter of the manuscripts assigned to the reviewer.
+    ///
+    /// `assigned` is the number of the assigned manuscripts,
+    /// or `None` if the reviewer is not in the assignment.
+    fn assignment_filter(&mut self, ui: &mut egui::Ui, assigned: Option<usize>) {
+        if self.assignment.is_empty() {
+            return;
+        }
+
+        match assigned {
+            Some(assigned) => {
+                ui.toggle_value(
+                    &mut self.assigned_only,
+                    format!("📋 Assigned to me ({assigned})"),
+                )
+                .on_hover_text(format!(
+                    "The manuscripts assigned to {}",
+                    self.export.reviewer
+                ));
+            }
+            None => {
+                ui.label(
+                    RichText::new(
+                        "Set your reviewer name in Export / Import to see your assignment.",
+                    )
+                    .small()
+                    .weak(),
+                );
+            }
+        }
+    }
+}
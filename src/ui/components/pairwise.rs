@@ -0,0 +1,254 @@
+//! Component: Pairwise Comparison
+
+use eframe::egui;
+use egui::{Key, Modifiers, RichText};
+
+use crate::{
+    types::{
+        pairwise::{Outcome, Ranking},
+        rank::{MetaGroup, Rubric},
+        ManuscriptId, RcManuscript,
+    },
+    ui::ReviewToolApp,
+};
+
+/// The state of the pairwise comparison view.
+#[derive(Default)]
+pub(crate) struct PairwiseState {
+    /// The key of the group to compare.
+    group: Option<&'static str>,
+
+    /// Rank with the comparisons of all the groups.
+    pooled: bool,
+
+    /// The pair being compared.
+    pair: Option<(ManuscriptId, ManuscriptId)>,
+
+    /// The pair compared or skipped just now.
+    last_pair: Option<(ManuscriptId, ManuscriptId)>,
+
+    /// The fitted ranking, and the (group, pooled) it fitted with.
+    ///
+    /// It is cleared when the comparisons change.
+    ranking: Option<(Ranking, (Option<&'static str>, bool))>,
+}
+
+/// Add `pairwise()` to Review Tool App. This trait is for specialization.
+pub(crate) trait PairwiseExt {
+    fn pairwise(&mut self, ui: &mut egui::Ui);
+}
+
+impl<M: MetaGroup> PairwiseExt for ReviewToolApp<M> {
+    default fn pairwise(&mut self, ui: &mut egui::Ui) {
+        ui.label("This meta group has not been supported, sorry :(");
+    }
+}
+
+impl<R: Rubric> PairwiseExt for ReviewToolApp<R> {
+    fn pairwise(&mut self, ui: &mut egui::Ui) {
+        let groups = R::default()
+            .groups()
+            .into_iter()
+            .map(|(key, group)| (key, group.name().to_owned()))
+            .collect::<Vec<_>>();
+
+        let Some(first_group) = groups.first().map(|(key, _)| *key) else {
+            ui.label("This rubric has no group to compare.");
+            return;
+        };
+        let group = *self.pairwise.group.get_or_insert(first_group);
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Compare on")
+                .selected_text(
+                    groups
+                        .iter()
+                        .find(|(key, _)| *key == group)
+                        .map_or(group, |(_, name)| name.as_str()),
+                )
+                .show_ui(ui, |ui| {
+                    for (key, name) in &groups {
+                        if ui.selectable_label(*key == group, name.as_str()).clicked() {
+                            self.pairwise.group = Some(*key);
+                            self.pairwise.pair = None;
+                        }
+                    }
+                });
+
+            ui.checkbox(&mut self.pairwise.pooled, "Rank with all groups");
+            ui.label(format!("{} comparisons", self.comparisons.len()));
+        });
+        ui.separator();
+
+        let available_height = ui.available_height();
+        egui::ScrollArea::vertical()
+            .id_source("pairwise-compare")
+            .max_height(available_height / 2.0)
+            .show(ui, |ui| self.compare(ui));
+        ui.separator();
+        egui::ScrollArea::vertical()
+            .id_source("pairwise-ranking")
+            .show(ui, |ui| self.ranking_table(ui));
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    /// Refit the ranking if it is cleared or the options changed.
+    fn refit_ranking(&mut self) {
+        let key = (self.pairwise.group, self.pairwise.pooled);
+
+        if matches!(&self.pairwise.ranking, Some((_, k)) if *k == key) {
+            return;
+        }
+
+        let ids = self
+            .manuscripts
+            .iter()
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        let group = if self.pairwise.pooled {
+            None
+        } else {
+            self.pairwise.group
+        };
+
+        let ranking = Ranking::fit(&ids, self.comparisons.of_group(group));
+        self.pairwise.ranking = Some((ranking, key));
+    }
+
+    /// Show the pair and record the outcome.
+    fn compare(&mut self, ui: &mut egui::Ui) {
+        let Some(group) = self.pairwise.group else {
+            return;
+        };
+
+        let pair = match self.pairwise.pair {
+            Some(pair) => Some(pair),
+            None => {
+                // choose with the ranking of this group only.
+                let ids = self
+                    .manuscripts
+                    .iter()
+                    .map(|(id, _)| *id)
+                    .collect::<Vec<_>>();
+                Ranking::fit(&ids, self.comparisons.of_group(Some(group)))
+                    .next_pair(self.pairwise.last_pair)
+            }
+        };
+        self.pairwise.pair = pair;
+
+        let Some((left, right)) = pair else {
+            ui.label("At least 2 manuscripts are needed to compare.");
+            return;
+        };
+        let (Some(left_m), Some(right_m)) =
+            (self.manuscripts.get(&left), self.manuscripts.get(&right))
+        else {
+            self.pairwise.pair = None;
+            return;
+        };
+
+        ui.columns(2, |columns| {
+            self.manuscript_card(&mut columns[0], left_m);
+            self.manuscript_card(&mut columns[1], right_m);
+        });
+
+        let mut outcome = None;
+        let mut skipped = false;
+
+        ui.horizontal(|ui| {
+            if ui.button("⬅ Left is stronger").clicked()
+                || ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowLeft))
+            {
+                outcome = Some(Outcome::Left);
+            }
+            if ui.button("Tie").clicked()
+                || ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowDown))
+            {
+                outcome = Some(Outcome::Tie);
+            }
+            if ui.button("Right is stronger ➡").clicked()
+                || ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowRight))
+            {
+                outcome = Some(Outcome::Right);
+            }
+            if ui.button("Skip").clicked() {
+                skipped = true;
+            }
+
+            ui.separator();
+            if ui
+                .add_enabled(!self.comparisons.is_empty(), egui::Button::new("Undo"))
+                .on_hover_text("Remove the last comparison")
+                .clicked()
+            {
+                self.comparisons.pop();
+                self.pairwise.ranking = None;
+            }
+
+            ui.label(RichText::new("← Left, ↓ Tie, → Right").weak());
+        });
+
+        if let Some(outcome) = outcome {
+            self.comparisons.record(left, right, group, outcome);
+            self.pairwise.ranking = None;
+        }
+        if outcome.is_some() || skipped {
+            self.pairwise.last_pair = Some((left, right));
+            self.pairwise.pair = None;
+        }
+    }
+
+    /// Show the fitted ordering next to the rubric scores.
+    fn ranking_table(&mut self, ui: &mut egui::Ui) {
+        self.refit_ranking();
+        let Some((ranking, _)) = &self.pairwise.ranking else {
+            return;
+        };
+
+        egui::Grid::new("pairwise-ranking-table")
+            .num_columns(6)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("#");
+                ui.strong("Title");
+                ui.strong("Strength");
+                ui.strong("95% CI");
+                ui.strong("Comparisons");
+                ui.strong("Rubric score");
+                ui.end_row();
+
+                for (i, entry) in ranking.ordered().into_iter().enumerate() {
+                    let Some(manuscript) = self.manuscripts.get(&entry.id) else {
+                        continue;
+                    };
+                    let (low, high) = entry.interval();
+
+                    ui.label((i + 1).to_string());
+                    ui.label(self.blinded(&manuscript.title, manuscript).as_ref());
+                    ui.label(format!("{:+.2}", entry.strength));
+                    ui.label(format!("{low:+.2} … {high:+.2}"));
+                    ui.label(format!("{} ({} wins)", entry.comparisons, entry.wins));
+                    ui.label(match self.rank_groups.get(&entry.id) {
+                        Some(rank) if rank.reviewed() => format!("{:.1}", rank.total_score()),
+                        Some(rank) => format!("{:.1} (not reviewed)", rank.total_score()),
+                        None => "-".to_owned(),
+                    });
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+impl<M: MetaGroup> ReviewToolApp<M> {
+    /// Show the manuscript to compare.
+    fn manuscript_card(&self, ui: &mut egui::Ui, manuscript: &RcManuscript) {
+        ui.heading(self.blinded(&manuscript.title, manuscript).as_ref());
+        ui.label(format!("{} · {}", manuscript.type_, manuscript.difficulty));
+        ui.separator();
+        ui.label(self.blinded(&manuscript.abstract_, manuscript).as_ref());
+        ui.collapsing("Description", |ui| {
+            ui.label(self.blinded(&manuscript.description, manuscript).as_ref());
+        });
+    }
+}
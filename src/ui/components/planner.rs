@@ -0,0 +1,286 @@
+//! Component: Reviewer Assignment Planner
+
+use eframe::egui;
+use egui::RichText;
+
+use crate::{
+    types::{
+        assignment::{Assignment, Reviewer, Roster},
+        rank::MetaGroup,
+    },
+    ui::{
+        file::{save_file, FilePicker, Filter, OpenedFile},
+        ReviewToolApp,
+    },
+};
+
+/// The filter of the assignment files.
+const ASSIGNMENT_FILTER: Filter<'static> = ("Assignment (YAML)", &["yaml", "yml"]);
+
+/// A reviewer in the roster editor.
+///
+/// The expertise and the conflicts are edited as comma-separated text.
+#[derive(Default)]
+struct ReviewerRow {
+    name: String,
+    expertise: String,
+    conflicts: String,
+}
+
+impl From<&Reviewer> for ReviewerRow {
+    fn from(reviewer: &Reviewer) -> Self {
+        Self {
+            name: reviewer.name.clone(),
+            expertise: reviewer.expertise.join(", "),
+            conflicts: reviewer.conflicts.join(", "),
+        }
+    }
+}
+
+impl ReviewerRow {
+    fn to_reviewer(&self) -> Reviewer {
+        let split = |text: &str| {
+            text.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(ToOwned::to_owned)
+                .collect()
+        };
+
+        Reviewer {
+            name: self.name.trim().to_owned(),
+            expertise: split(&self.expertise),
+            conflicts: split(&self.conflicts),
+        }
+    }
+}
+
+/// The state of the assignment planner.
+pub(crate) struct PlannerState {
+    /// Is the window open?
+    pub(crate) open: bool,
+
+    rows: Vec<ReviewerRow>,
+    per_manuscript: usize,
+    picker: FilePicker,
+
+    /// The result of the last action.
+    message: Option<Result<String, String>>,
+}
+
+impl PlannerState {
+    pub(crate) fn new(roster: Roster) -> Self {
+        Self {
+            open: false,
+            rows: roster.reviewers.iter().map(ReviewerRow::from).collect(),
+            per_manuscript: roster.per_manuscript,
+            picker: FilePicker::default(),
+            message: None,
+        }
+    }
+
+    /// The roster being edited. The reviewers without a name are skipped.
+    pub(crate) fn roster(&self) -> Roster {
+        Roster {
+            reviewers: self
+                .rows
+                .iter()
+                .map(ReviewerRow::to_reviewer)
+                .filter(|r| !r.name.is_empty())
+                .collect(),
+            per_manuscript: self.per_manuscript,
+        }
+    }
+}
+
+impl<M: MetaGroup> ReviewToolApp<M> {
+    /// The window to plan which reviewers review which manuscripts.
+    pub(crate) fn planner_window(&mut self, ctx: &egui::Context) {
+        if let Some(opened) = self.planner.picker.take() {
+            self.planner.message = Some(self.import_assignment(opened.map_err(|e| e.to_string())));
+        }
+
+        let mut open = self.planner.open;
+        egui::Window::new("Assignment")
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                self.roster_editor(ui);
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Assign")
+                        .on_hover_text("Assign the manuscripts to the reviewers above")
+                        .clicked()
+                    {
+                        self.assignment =
+                            self.planner.roster().assign(&self.manuscripts, &self.tags);
+                        self.planner.message = Some(Ok(format!(
+                            "Assigned {} manuscripts to {} reviewers.",
+                            self.assignment.manuscripts.len(),
+                            self.assignment.loads().len()
+                        )));
+                    }
+
+                    if ui
+                        .add_enabled(!self.assignment.is_empty(), egui::Button::new("Export…"))
+                        .on_hover_text("Save the assignment to share it with the reviewers")
+                        .clicked()
+                    {
+                        self.planner.message = Some(self.export_assignment());
+                    }
+
+                    if ui
+                        .button("Import…")
+                        .on_hover_text("Load the assignment shared by the organizer")
+                        .clicked()
+                    {
+                        self.planner.picker.open(ui.ctx(), ASSIGNMENT_FILTER);
+                    }
+
+                    if ui
+                        .add_enabled(!self.assignment.is_empty(), egui::Button::new("Clear"))
+                        .on_hover_text("Double click to show all the manuscripts again")
+                        .double_clicked()
+                    {
+                        self.assignment = Assignment::default();
+                    }
+                });
+
+                match &self.planner.message {
+                    Some(Ok(message)) => {
+                        ui.label(message);
+                    }
+                    Some(Err(message)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, message);
+                    }
+                    None => {}
+                }
+
+                if !self.assignment.is_empty() {
+                    ui.separator();
+                    self.assignment_summary(ui);
+                }
+            });
+        self.planner.open = open;
+    }
+
+    fn roster_editor(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Reviewers per manuscript:");
+            ui.add(egui::DragValue::new(&mut self.planner.per_manuscript).clamp_range(1..=10));
+        });
+
+        let mut removed = None;
+        egui::Grid::new("roster")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Name", "Expertise", "Conflicts", ""] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+
+                for (i, row) in self.planner.rows.iter_mut().enumerate() {
+                    ui.add(egui::TextEdit::singleline(&mut row.name).desired_width(100.0));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut row.expertise)
+                            .hint_text("AI, Web, …")
+                            .desired_width(140.0),
+                    )
+                    .on_hover_text("The manuscript types or tags, comma-separated");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut row.conflicts)
+                            .hint_text("Speakers, …")
+                            .desired_width(140.0),
+                    )
+                    .on_hover_text("The speakers this reviewer must not review, comma-separated");
+                    if ui.small_button("×").clicked() {
+                        removed = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = removed {
+            self.planner.rows.remove(i);
+        }
+
+        if ui.button("Add reviewer").clicked() {
+            self.planner.rows.push(ReviewerRow::default());
+        }
+    }
+
+    /// The load of every reviewer, and the manuscripts lacking reviewers.
+    fn assignment_summary(&self, ui: &mut egui::Ui) {
+        let loads = self
+            .assignment
+            .loads()
+            .into_iter()
+            .map(|(reviewer, load)| format!("{reviewer}: {load}"))
+            .collect::<Vec<_>>();
+        ui.label(format!("Load: {}", loads.join(", ")));
+
+        for (id, assigned) in self.assignment.understaffed() {
+            let title = self.manuscripts.get(id).map_or_else(
+                || "(unknown manuscript)".into(),
+                |m| self.blinded(&m.title, m),
+            );
+
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "⚠ {title}: {assigned}/{} reviewers without conflicts",
+                    self.assignment.per_manuscript
+                ),
+            );
+        }
+
+        ui.label(
+            RichText::new(
+                "Every reviewer sees only their assigned manuscripts, \
+                 by the reviewer name in Export / Import.",
+            )
+            .weak(),
+        );
+    }
+
+    fn export_assignment(&self) -> Result<String, String> {
+        let document = self.assignment.to_yaml().map_err(|e| e.to_string())?;
+
+        match save_file("assignment.yaml", ASSIGNMENT_FILTER, document.as_bytes()) {
+            Ok(Some(saved)) => Ok(format!("Exported the assignment to {saved}.")),
+            Ok(None) => Ok("The export has been cancelled.".to_owned()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn import_assignment(&mut self, opened: Result<OpenedFile, String>) -> Result<String, String> {
+        let opened = opened?;
+        let document = String::from_utf8(opened.content).map_err(|e| e.to_string())?;
+        let assignment = Assignment::from_yaml(&document).map_err(|e| e.to_string())?;
+
+        let unknown = assignment
+            .manuscripts
+            .iter()
+            .filter(|(id, _)| !self.manuscripts.contains_key(id))
+            .count();
+        self.assignment = assignment;
+
+        let mut message = format!("Imported the assignment from {}.", opened.name);
+        if unknown > 0 {
+            message += &format!("\nIgnored {unknown} unknown manuscripts.");
+        }
+        match self.assignment.of(&self.export.reviewer) {
+            Some(assigned) => {
+                message += &format!("\n{} manuscripts are assigned to you.", assigned.len());
+            }
+            None => {
+                message += "\nSet your reviewer name in Export / Import to see your manuscripts.";
+            }
+        }
+
+        Ok(message)
+    }
+}
@@ -0,0 +1,387 @@
+//! Component: Program Builder
+
+use std::collections::HashMap;
+
+use eframe::egui;
+use egui::RichText;
+
+use crate::{
+    types::{
+        program::{Category, Program, Quota, Slot, Violation},
+        rank::{MetaGroup, Rubric},
+        timestamp::Timestamp,
+        Difficulty, ManuscriptId,
+    },
+    ui::{
+        file::{save_file, Filter},
+        ReviewToolApp,
+    },
+};
+
+const CSV_FILTER: Filter<'static> = ("CSV", &["csv"]);
+const ICS_FILTER: Filter<'static> = ("iCalendar", &["ics"]);
+
+/// The state of the program view.
+#[derive(Default)]
+pub(crate) struct ProgramState {
+    pub(crate) program: Program,
+
+    /// The result of the last action.
+    message: Option<Result<String, String>>,
+}
+
+impl ProgramState {
+    pub(crate) fn new(program: Program) -> Self {
+        Self {
+            program,
+            message: None,
+        }
+    }
+}
+
+/// Add `program()` to Review Tool App. This trait is for specialization.
+pub(crate) trait ProgramExt {
+    fn program(&mut self, ui: &mut egui::Ui);
+}
+
+impl<M: MetaGroup> ProgramExt for ReviewToolApp<M> {
+    default fn program(&mut self, ui: &mut egui::Ui) {
+        ui.label("This meta group has not been supported, sorry :(");
+    }
+}
+
+impl<R: Rubric> ProgramExt for ReviewToolApp<R> {
+    fn program(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Rooms, slots and quotas")
+            .id_source("program-config")
+            .show(ui, |ui| self.program_config(ui));
+
+        ui.horizontal(|ui| {
+            if ui
+                .button("Fill")
+                .on_hover_text(
+                    "Fill the program with the talks of the highest total score, keeping the \
+                     pinned ones. \
+                     The scores are aggregated as in the Aggregate view.",
+                )
+                .clicked()
+            {
+                let scores = self.program_scores();
+                self.program.program.fill(&self.manuscripts, &scores);
+                self.program.message = Some(Ok(format!(
+                    "Placed {} of {} scored talks.",
+                    self.program.program.talks.len(),
+                    scores.len()
+                )));
+            }
+            if ui
+                .button("Clear")
+                .on_hover_text("Double click to remove all the talks, including the pinned ones")
+                .double_clicked()
+            {
+                self.program.program.talks.clear();
+            }
+
+            ui.separator();
+
+            if ui.button("Export CSV…").clicked() {
+                self.program.message = Some(self.export_program(CSV_FILTER));
+            }
+            if ui
+                .button("Export ICS…")
+                .on_hover_text("Import the program to a calendar. The date is required.")
+                .clicked()
+            {
+                self.program.message = Some(self.export_program(ICS_FILTER));
+            }
+        });
+
+        match &self.program.message {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(message)) => {
+                ui.colored_label(ui.visuals().error_fg_color, message);
+            }
+            None => {}
+        }
+        for violation in self.program.program.violations(&self.manuscripts) {
+            let text = match violation {
+                Violation::SpeakerTwice { speaker } => {
+                    format!("⚠ {speaker} gives more than one talk.")
+                }
+                Violation::TooLong { id, minutes } => format!(
+                    "⚠ “{}” ({minutes} min) is longer than its slot.",
+                    self.program_title(&id)
+                ),
+                Violation::OverQuota { category, count } => {
+                    format!("⚠ There are {count} talks of {category}, over the quota.")
+                }
+            };
+            ui.colored_label(ui.visuals().warn_fg_color, text);
+        }
+
+        ui.separator();
+
+        egui::ScrollArea::both().show(ui, |ui| self.program_grid(ui));
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    /// The mean total score of every reviewed manuscript.
+    fn program_scores(&mut self) -> HashMap<ManuscriptId, f64> {
+        self.aggregation()
+            .manuscripts
+            .iter()
+            .filter_map(|m| Some((m.id, m.total?.mean)))
+            .collect()
+    }
+
+    fn export_program(&self, filter: Filter<'static>) -> Result<String, String> {
+        let program = &self.program.program;
+        let document = if filter == ICS_FILTER {
+            program.to_ics(&self.manuscripts, Timestamp::now())
+        } else {
+            program.to_csv(&self.manuscripts)
+        }
+        .map_err(|e| e.to_string())?;
+
+        match save_file(
+            &format!("program.{}", filter.1[0]),
+            filter,
+            document.as_bytes(),
+        ) {
+            Ok(Some(saved)) => Ok(format!("Exported the program to {saved}.")),
+            Ok(None) => Ok("The export has been cancelled.".to_owned()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+impl<M: MetaGroup> ReviewToolApp<M> {
+    fn program_title(&self, id: &ManuscriptId) -> String {
+        self.manuscripts.get(id).map_or_else(
+            || "(unknown manuscript)".to_owned(),
+            |m| self.blinded(&m.title, m).into_owned(),
+        )
+    }
+
+    /// The editor of the rooms, the slots and the quotas.
+    fn program_config(&mut self, ui: &mut egui::Ui) {
+        let config = &mut self.program.program.config;
+
+        egui::Grid::new("program-settings")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Date:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut config.date)
+                        .hint_text("YYYY-MM-DD")
+                        .desired_width(96.0),
+                );
+                ui.end_row();
+
+                ui.label("Default length:")
+                    .on_hover_text("The length of a talk whose type has no duration");
+                ui.add(
+                    egui::DragValue::new(&mut config.default_minutes)
+                        .clamp_range(1..=600)
+                        .suffix(" min"),
+                );
+                ui.end_row();
+            });
+
+        ui.label(RichText::new("Rooms").strong());
+        ui.horizontal_wrapped(|ui| {
+            let mut removed = None;
+            for (i, room) in config.rooms.iter_mut().enumerate() {
+                ui.add(egui::TextEdit::singleline(room).desired_width(80.0));
+                if ui.small_button("×").clicked() {
+                    removed = Some(i);
+                }
+            }
+            if let Some(i) = removed {
+                config.rooms.remove(i);
+                self.program.program.talks.retain(|t| t.room != i);
+                for talk in &mut self.program.program.talks {
+                    talk.room -= usize::from(talk.room > i);
+                }
+            }
+            if ui.button("+ Room").clicked() {
+                config.rooms.push(format!("R{}", config.rooms.len()));
+            }
+        });
+
+        ui.label(RichText::new("Slots").strong());
+        let config = &mut self.program.program.config;
+        let mut removed = None;
+        for (i, slot) in config.slots.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                let valid = slot.start_minute().is_ok();
+                ui.add(
+                    egui::TextEdit::singleline(&mut slot.start)
+                        .hint_text("HH:MM")
+                        .desired_width(48.0)
+                        .text_color_opt((!valid).then(|| ui.visuals().error_fg_color)),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut slot.minutes)
+                        .clamp_range(1..=600)
+                        .suffix(" min"),
+                );
+                if ui.small_button("×").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed {
+            config.slots.remove(i);
+            self.program.program.talks.retain(|t| t.slot != i);
+            for talk in &mut self.program.program.talks {
+                talk.slot -= usize::from(talk.slot > i);
+            }
+        }
+        let config = &mut self.program.program.config;
+        if ui.button("+ Slot").clicked() {
+            let minutes = config.default_minutes;
+            config.slots.push(Slot {
+                start: String::new(),
+                minutes,
+            });
+        }
+
+        ui.label(RichText::new("Quotas").strong());
+        let mut removed = None;
+        for (i, quota) in config.quotas.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label("At most");
+                ui.add(egui::DragValue::new(&mut quota.max).clamp_range(0..=999));
+                ui.label("talks of");
+
+                match &mut quota.category {
+                    Category::Type(type_) => {
+                        ui.label("the type containing");
+                        ui.add(egui::TextEdit::singleline(type_).desired_width(120.0));
+                    }
+                    Category::Difficulty(difficulty) => {
+                        ui.label("the difficulty");
+                        egui::ComboBox::from_id_source(("quota-difficulty", i))
+                            .selected_text(difficulty.to_string())
+                            .show_ui(ui, |ui| {
+                                for d in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+                                    ui.selectable_value(difficulty, d, d.to_string());
+                                }
+                            });
+                    }
+                }
+                if ui.small_button("×").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed {
+            config.quotas.remove(i);
+        }
+        ui.horizontal(|ui| {
+            if ui.button("+ Type quota").clicked() {
+                config.quotas.push(Quota {
+                    category: Category::Type(String::new()),
+                    max: 1,
+                });
+            }
+            if ui.button("+ Difficulty quota").clicked() {
+                config.quotas.push(Quota {
+                    category: Category::Difficulty(Difficulty::Hard),
+                    max: 1,
+                });
+            }
+        });
+    }
+
+    /// The slots × rooms grid of the talks.
+    fn program_grid(&mut self, ui: &mut egui::Ui) {
+        let program = &self.program.program;
+        let (rooms, slots) = (program.config.rooms.len(), program.config.slots.len());
+        let mut placed = None;
+        let mut removed = None;
+        let mut toggled = None;
+
+        egui::Grid::new("program-grid")
+            .num_columns(rooms + 1)
+            .striped(true)
+            .min_col_width(160.0)
+            .show(ui, |ui| {
+                ui.label("");
+                for room in &program.config.rooms {
+                    ui.label(RichText::new(room).strong());
+                }
+                ui.end_row();
+
+                for slot in 0..slots {
+                    let config = &program.config.slots[slot];
+                    ui.label(format!("{} ({} min)", config.start, config.minutes));
+
+                    for room in 0..rooms {
+                        let talk = program.at(room, slot);
+
+                        ui.horizontal(|ui| {
+                            let selected =
+                                talk.map_or_else(|| "—".to_owned(), |t| self.program_title(&t.id));
+                            egui::ComboBox::from_id_source(("program-cell", room, slot))
+                                .selected_text(selected)
+                                .width(160.0)
+                                .show_ui(ui, |ui| {
+                                    for (id, manuscript) in self.manuscripts.iter() {
+                                        let text = format!(
+                                            "{} ({} min)",
+                                            self.blinded(&manuscript.title, manuscript),
+                                            program.minutes(manuscript)
+                                        );
+                                        if ui
+                                            .selectable_label(
+                                                talk.is_some_and(|t| t.id == *id),
+                                                text,
+                                            )
+                                            .clicked()
+                                        {
+                                            placed = Some((*id, room, slot));
+                                        }
+                                    }
+                                });
+
+                            if let Some(talk) = talk {
+                                if ui
+                                    .selectable_label(talk.pinned, "📌")
+                                    .on_hover_text("Keep this talk in place when filling")
+                                    .clicked()
+                                {
+                                    toggled = Some((room, slot));
+                                }
+                                if ui.small_button("×").clicked() {
+                                    removed = Some((room, slot));
+                                }
+                            }
+                        });
+                    }
+                    ui.end_row();
+                }
+            });
+
+        let program = &mut self.program.program;
+        if let Some((id, room, slot)) = placed {
+            program.place(id, room, slot);
+        }
+        if let Some((room, slot)) = removed {
+            program.remove(room, slot);
+        }
+        if let Some((room, slot)) = toggled {
+            if let Some(talk) = program
+                .talks
+                .iter_mut()
+                .find(|t| t.room == room && t.slot == slot)
+            {
+                talk.pinned = !talk.pinned;
+            }
+        }
+    }
+}
@@ -1,14 +1,17 @@
 use std::ops::{Deref, DerefMut};
 
 use eframe::egui;
-use egui::{Button, DragValue, Sense, TextEdit, Widget};
+use egui::{Button, DragValue, RichText, Sense, TextEdit, Widget};
 
 use crate::{
     types::rank::{
-        sitcon_gdsc, CommentableItemGroup, Item, ItemGroup, MetaGroup, MutableMetaGroup,
-        StandardChoice,
+        CommentableItemGroup, Item, ItemGroup, IterableMetaGroup, MetaGroup, MutableMetaGroup,
+        Rubric, ScoreBreakdown, ScoreRow, StandardChoice,
+    },
+    ui::{
+        components::{history::HistoryExt, snapshots::SnapshotExt},
+        ReviewToolApp,
     },
-    ui::ReviewToolApp,
 };
 
 pub struct RankComponent<'a, M: MetaGroup>(pub(crate) &'a mut M);
@@ -22,28 +25,15 @@ trait ReviewedExt {
     fn show_reviewed_button(&mut self, ui: &mut eframe::egui::Ui);
 }
 
-impl<'a> RankComponent<'a, sitcon_gdsc::Group> {
+impl<'a, M: IterableMetaGroup> RankComponent<'a, M> {
     fn show(&mut self, ui: &mut eframe::egui::Ui) {
-        render_item_group_with_comment(&mut self.0.subject, ui, |ui, group| {
-            ui.add(&mut ChoiceWidget::new(&mut group.student_related));
-            ui.add(&mut ChoiceWidget::new(&mut group.community_related));
-            ui.add(&mut ChoiceWidget::new(&mut group.coding_related));
-            ui.add(&mut ChoiceWidget::new(&mut group.floss_related));
-        });
-
-        render_item_group_with_comment(&mut self.0.content, ui, |ui, group| {
-            ui.add(&mut ChoiceWidget::new(&mut group.knowledges));
-            ui.add(&mut ChoiceWidget::new(&mut group.experiences));
-            ui.add(&mut ChoiceWidget::new(&mut group.uniqueness));
-            ui.add(&mut ChoiceWidget::new(&mut group.structure));
-            ui.add(&mut ChoiceWidget::new(&mut group.completeness));
-        });
-
-        render_item_group_with_comment(&mut self.0.expressive, ui, |ui, group| {
-            ui.add(&mut ChoiceWidget::new(&mut group.organized));
-            ui.add(&mut ChoiceWidget::new(&mut group.fluent));
-            ui.add(&mut ChoiceWidget::new(&mut group.completeness));
-        });
+        for (_, group) in self.0.groups_mut() {
+            render_item_group_with_comment(group, ui, |ui, group| {
+                for (_, item) in group.items_mut() {
+                    ui.add(&mut ChoiceWidget::new(item));
+                }
+            });
+        }
     }
 }
 
@@ -82,16 +72,47 @@ impl<M: MetaGroup> RankExt for ReviewToolApp<M> {
     }
 }
 
-impl RankExt for ReviewToolApp<sitcon_gdsc::Group> {
+impl<R: Rubric> RankExt for ReviewToolApp<R> {
     fn rank(&mut self, ui: &mut eframe::egui::Ui) {
+        let id = self.current_selected;
+        let was_blind = self.blind_log.was_blind(&id);
         let mut c = RankComponent(self.get_current_rank_or_set_default());
-        c.show_reviewed_button(ui);
+
+        let reviewed = c.0.reviewed();
+        let mut reset = false;
+        ui.horizontal(|ui| {
+            c.show_reviewed_button(ui);
+            reset = ui
+                .button("Reset this review")
+                .on_hover_text(
+                    "Double click to reset the review of this manuscript. \
+                     It can be undone, and a snapshot is kept to restore it.",
+                )
+                .double_clicked();
+
+            match was_blind {
+                Some(true) if reviewed => {
+                    ui.label("🙈 Reviewed blind");
+                }
+                Some(false) if reviewed => {
+                    ui.label("👀 Reviewed with the speaker shown");
+                }
+                _ => {}
+            }
+        });
         ui.separator();
         c.show(ui);
+
+        if reset {
+            self.reset_current();
+        }
+
+        ui.separator();
+        self.change_history(ui);
     }
 }
 
-fn render_item_group_with_comment<G: CommentableItemGroup>(
+fn render_item_group_with_comment<G: CommentableItemGroup + ?Sized>(
     group: &mut G,
     ui: &mut eframe::egui::Ui,
     add_choice_widget: impl FnOnce(&mut eframe::egui::Ui, &mut G),
@@ -102,7 +123,7 @@ fn render_item_group_with_comment<G: CommentableItemGroup>(
     })
 }
 
-fn render_item_group_advanced<G: ItemGroup>(
+fn render_item_group_advanced<G: ItemGroup + ?Sized>(
     group: &mut G,
     ui: &mut eframe::egui::Ui,
     add_choice_widget: impl FnOnce(&mut eframe::egui::Ui, &mut G),
@@ -127,8 +148,10 @@ fn render_item_group_advanced<G: ItemGroup>(
                     ui.add(DragValue::new(&mut group.score()));
                 });
 
-                ui.label("分數描述");
-                ui.text_edit_multiline(&mut group.score_description().unwrap_or_default());
+                if let Some(breakdown) = group.score_breakdown() {
+                    ui.label("分數明細");
+                    render_score_breakdown(ui, group.name(), &breakdown);
+                }
 
                 right_pane_bottom(ui, group);
             });
@@ -136,16 +159,57 @@ fn render_item_group_advanced<G: ItemGroup>(
         });
 }
 
+/// Render the score breakdown as a table.
+fn render_score_breakdown(ui: &mut eframe::egui::Ui, id: &str, breakdown: &ScoreBreakdown) {
+    egui::Grid::new(("score-breakdown", id))
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong("項目");
+            ui.strong("選擇");
+            ui.strong("分數");
+            ui.end_row();
+
+            for row in breakdown.rows() {
+                match row {
+                    ScoreRow::Item {
+                        name,
+                        choice,
+                        points,
+                    } => {
+                        ui.label(name);
+                        ui.label(format!("{} {}", choice.as_emoji(), choice));
+                        ui.label(format!("{points:.1}"));
+                    }
+                    ScoreRow::Adjustment {
+                        description,
+                        points,
+                    } => {
+                        ui.label(RichText::new(description).italics());
+                        ui.label("");
+                        ui.label(RichText::new(format!("{points:+.1}")).italics());
+                    }
+                }
+                ui.end_row();
+            }
+
+            ui.strong("總分");
+            ui.label("");
+            ui.strong(format!("{:.1}", breakdown.total()));
+            ui.end_row();
+        });
+}
+
 /// The choice widget.
-pub struct ChoiceWidget<'a, I: Item>(&'a mut I);
+pub struct ChoiceWidget<'a, I: Item + ?Sized>(&'a mut I);
 
-impl<'a, I: Item> ChoiceWidget<'a, I> {
+impl<'a, I: Item + ?Sized> ChoiceWidget<'a, I> {
     pub fn new(item: &'a mut I) -> Self {
         Self(item)
     }
 }
 
-impl<'a, I: Item> Deref for ChoiceWidget<'a, I> {
+impl<'a, I: Item + ?Sized> Deref for ChoiceWidget<'a, I> {
     type Target = I;
 
     fn deref(&self) -> &Self::Target {
@@ -153,13 +217,13 @@ impl<'a, I: Item> Deref for ChoiceWidget<'a, I> {
     }
 }
 
-impl<'a, I: Item> DerefMut for ChoiceWidget<'a, I> {
+impl<'a, I: Item + ?Sized> DerefMut for ChoiceWidget<'a, I> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.0
     }
 }
 
-impl<'a, I: Item> Widget for &mut ChoiceWidget<'a, I> {
+impl<'a, I: Item + ?Sized> Widget for &mut ChoiceWidget<'a, I> {
     fn ui(self, ui: &mut egui::Ui) -> egui::Response {
         egui::ComboBox::from_label(self.0.name())
             .selected_text(self.0.choice().as_ref())
@@ -0,0 +1,129 @@
+//! Component: Autosave and Crash Recovery
+
+#[cfg(not(target_family = "wasm"))]
+use std::time;
+
+#[cfg(target_family = "wasm")]
+use web_time as time;
+
+use eframe::egui;
+
+use crate::{
+    types::rank::{undo::CommandKind, versioned, MetaGroup, Rubric},
+    ui::{
+        autosave::{AutosaveAction, Recovery, CHECK_INTERVAL},
+        recovery_key, store_yaml, ReviewToolApp,
+    },
+};
+
+/// Add `recovery_window()` and `autosave_tick()` to Review Tool App. This trait is for specialization.
+pub(crate) trait RecoveryExt {
+    /// Offer to restore the reviews autosaved before the app was closed.
+    fn recovery_window(&mut self, ctx: &egui::Context);
+
+    /// Check the unsaved changes and autosave them.
+    fn autosave_tick(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame);
+}
+
+impl<M: MetaGroup> RecoveryExt for ReviewToolApp<M> {
+    default fn recovery_window(&mut self, _ctx: &egui::Context) {}
+
+    default fn autosave_tick(&mut self, _ctx: &egui::Context, _frame: &mut eframe::Frame) {}
+}
+
+impl<R: Rubric> RecoveryExt for ReviewToolApp<R> {
+    fn recovery_window(&mut self, ctx: &egui::Context) {
+        let Some(Recovery {
+            saved_at, reviewed, ..
+        }) = self.autosave.found
+        else {
+            return;
+        };
+
+        egui::Window::new("Recover unsaved reviews")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "The app was closed with unsaved reviews. They were autosaved at {saved_at}, \
+                     with {reviewed} manuscripts reviewed."
+                ));
+                ui.label("Restore them, replacing the saved reviews?");
+
+                ui.horizontal(|ui| {
+                    if ui.button("Restore").clicked() {
+                        self.restore_recovery();
+                    }
+                    if ui
+                        .button("Discard")
+                        .on_hover_text("Keep the saved reviews and delete the autosaved ones")
+                        .clicked()
+                    {
+                        self.autosave.discard();
+                    }
+                });
+
+                if let Some(message) = &self.autosave.message {
+                    ui.colored_label(ui.visuals().error_fg_color, message);
+                }
+            });
+    }
+
+    fn autosave_tick(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let rank_groups = &self.rank_groups;
+        let action = self.autosave.tick(time::Instant::now(), || {
+            let document = versioned::serialize(rank_groups)
+                .map_err(|e| tracing::error!("failed to serialize rank: {e}"))
+                .ok()?;
+
+            Some((
+                document,
+                rank_groups.values().filter(|m| m.reviewed()).count(),
+            ))
+        });
+
+        if let (Some(action), Some(storage)) = (action, frame.storage_mut()) {
+            match action {
+                AutosaveAction::Write(recovery) => {
+                    store_yaml(storage, &recovery_key(R::ID), &Some(recovery));
+                    tracing::info!("the unsaved reviews have been autosaved");
+                }
+                AutosaveAction::Clear => {
+                    store_yaml(storage, &recovery_key(R::ID), &None::<Recovery>);
+                }
+            }
+            storage.flush();
+        }
+
+        // Keep checking while idle, so the last changes are autosaved.
+        if self.autosave.is_dirty() {
+            ctx.request_repaint_after(CHECK_INTERVAL);
+        }
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    fn restore_recovery(&mut self) {
+        let Some(recovery) = &self.autosave.found else {
+            return;
+        };
+
+        match versioned::deserialize::<R>(&recovery.rank) {
+            Ok(rank) => {
+                let reason = "Before restoring the unsaved reviews";
+                if self
+                    .replace_reviews(CommandKind::Recover, reason, |current| *current = rank)
+                    .is_none()
+                {
+                    return;
+                }
+                self.autosave.found = None;
+                self.autosave.message = None;
+            }
+            Err(e) => {
+                self.autosave.message = Some(format!("Failed to restore the reviews: {e}"));
+            }
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! Component: Reset and Snapshots
+
+use eframe::egui;
+
+use crate::{
+    types::{
+        rank::{
+            snapshot::{Snapshot, SnapshotHistory},
+            undo::CommandKind,
+            versioned, GroupMetaDatabase, MetaGroup, Rubric,
+        },
+        timestamp::Timestamp,
+    },
+    ui::ReviewToolApp,
+};
+
+/// The state of the snapshots.
+#[derive(Default)]
+pub(crate) struct SnapshotState {
+    pub(crate) history: SnapshotHistory,
+    pub(crate) open: bool,
+
+    /// The result of the last reset or restore.
+    message: Option<Result<String, String>>,
+}
+
+impl SnapshotState {
+    pub(crate) fn new(history: SnapshotHistory) -> Self {
+        Self {
+            history,
+            ..Default::default()
+        }
+    }
+}
+
+/// Add `reset_all()`, `reset_current()` and `snapshot_window()` to Review Tool App.
+/// This trait is for specialization.
+pub(crate) trait SnapshotExt {
+    /// Reset all the reviews, keeping a snapshot of them.
+    fn reset_all(&mut self);
+
+    /// Reset the review of the selected manuscript, keeping a partial snapshot of it.
+    fn reset_current(&mut self);
+
+    /// The window to restore the snapshots.
+    fn snapshot_window(&mut self, ctx: &egui::Context);
+}
+
+impl<M: MetaGroup> SnapshotExt for ReviewToolApp<M> {
+    default fn reset_all(&mut self) {
+        self.rank_groups.clear();
+        self.state.reset();
+    }
+
+    default fn reset_current(&mut self) {
+        self.rank_groups.remove(&self.current_selected);
+    }
+
+    default fn snapshot_window(&mut self, _ctx: &egui::Context) {}
+}
+
+impl<R: Rubric> SnapshotExt for ReviewToolApp<R> {
+    fn reset_all(&mut self) {
+        if self.rank_groups.is_empty() {
+            return;
+        }
+        if self
+            .replace_reviews(
+                CommandKind::Reset,
+                "Before resetting all the reviews",
+                |rank| rank.clear(),
+            )
+            .is_none()
+        {
+            return;
+        }
+
+        self.state.reset();
+        self.snapshots.message = Some(Ok(
+            "All the reviews have been reset. Restore them from “Snapshots”.".to_owned(),
+        ));
+    }
+
+    fn reset_current(&mut self) {
+        let id = self.current_selected;
+        let title = self
+            .manuscripts
+            .get(&id)
+            .map_or_else(String::new, |m| self.blinded(&m.title, m).into_owned());
+
+        // Keep the review alone, so resetting many reviews one by one does not
+        // push out the snapshots of all the reviews.
+        let Some(review) = self.rank_groups.remove(&id) else {
+            return;
+        };
+        let mut reviews = GroupMetaDatabase::new();
+        reviews.insert(id, review);
+        let snapshot = Snapshot::take_partial(
+            &reviews,
+            &format!("Before resetting “{title}”"),
+            Timestamp::now(),
+        );
+        if let Some(review) = reviews.remove(&id) {
+            self.rank_groups.insert(id, review);
+        }
+        if !self.keep_snapshot(snapshot) {
+            return;
+        }
+
+        self.change_reviews(CommandKind::Reset, |rank| {
+            rank.insert(id, R::default());
+        });
+    }
+
+    fn snapshot_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.snapshots.open;
+
+        egui::Window::new("Restore snapshot")
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    "The reviews are kept as a snapshot before they are reset, restored, \
+                     imported, merged, synced, opened from a workspace or recovered. \
+                     Resetting a single review keeps that review only, and restoring it \
+                     puts back that review only. Restoring a snapshot keeps the current \
+                     reviews as another snapshot.",
+                );
+                ui.separator();
+
+                if self.snapshots.history.is_empty() {
+                    ui.label("No snapshots yet.");
+                }
+
+                let mut restore = None;
+                let mut delete = None;
+                egui::Grid::new("snapshots")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        let snapshots = self.snapshots.history.snapshots();
+                        for (index, snapshot) in snapshots.iter().enumerate().rev() {
+                            ui.label(snapshot.taken_at.to_string());
+                            ui.label(&snapshot.reason);
+                            if snapshot.partial {
+                                ui.label(format!("{} of the reviews", snapshot.total));
+                            } else {
+                                ui.label(format!(
+                                    "{} / {} reviewed",
+                                    snapshot.reviewed, snapshot.total
+                                ));
+                            }
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .button("Restore")
+                                    .on_hover_text(if snapshot.partial {
+                                        "Put back the reviews in this snapshot"
+                                    } else {
+                                        "Replace the current reviews with this snapshot"
+                                    })
+                                    .clicked()
+                                {
+                                    restore = Some(index);
+                                }
+                                if ui
+                                    .button("🗑")
+                                    .on_hover_text("Double click to delete this snapshot")
+                                    .double_clicked()
+                                {
+                                    delete = Some(index);
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+
+                if let Some(index) = restore {
+                    self.restore_snapshot(index);
+                }
+                if let Some(index) = delete {
+                    self.snapshots.history.remove(index);
+                }
+
+                match &self.snapshots.message {
+                    Some(Ok(message)) => {
+                        ui.label(message);
+                    }
+                    Some(Err(message)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {message}"));
+                    }
+                    None => {}
+                }
+            });
+
+        self.snapshots.open = open;
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    /// Change the reviews as a whole with `change`, as one command of `kind`.
+    /// A snapshot of the reviews before is kept if anything is changed.
+    ///
+    /// Return `None` without changing anything if the snapshot fails.
+    pub(crate) fn replace_reviews<T>(
+        &mut self,
+        kind: CommandKind,
+        reason: &str,
+        change: impl FnOnce(&mut GroupMetaDatabase<R>) -> T,
+    ) -> Option<T> {
+        let snapshot = match Snapshot::take(&self.rank_groups, reason, Timestamp::now()) {
+            // The reviewer asked for these, so the routine snapshots don't push them out.
+            Ok(snapshot) => Snapshot {
+                protected: matches!(kind, CommandKind::Reset | CommandKind::Restore),
+                ..snapshot
+            },
+            Err(e) => {
+                self.snapshot_failed(&e);
+                return None;
+            }
+        };
+
+        let (result, changes) = self.write_reviews(change);
+        if !changes.is_empty() {
+            self.snapshots.history.push(snapshot);
+        }
+        self.record_changes(kind, changes);
+
+        Some(result)
+    }
+
+    /// Keep a snapshot. Return `false` if it fails,
+    /// so the reviews are not changed without a snapshot.
+    fn keep_snapshot(&mut self, snapshot: Result<Snapshot, versioned::Error>) -> bool {
+        match snapshot {
+            Ok(snapshot) => {
+                self.snapshots.history.push(snapshot);
+                true
+            }
+            Err(e) => {
+                self.snapshot_failed(&e);
+                false
+            }
+        }
+    }
+
+    fn snapshot_failed(&mut self, error: &versioned::Error) {
+        tracing::error!("failed to take the snapshot: {error}");
+        self.snapshots.message = Some(Err(format!(
+            "Failed to keep a snapshot, so the reviews are not changed: {error}"
+        )));
+        self.snapshots.open = true;
+    }
+
+    fn restore_snapshot(&mut self, index: usize) {
+        let Some(snapshot) = self.snapshots.history.get(index).cloned() else {
+            return;
+        };
+
+        let mut restored = match snapshot.restore::<R>() {
+            Ok(restored) => restored,
+            Err(e) => {
+                self.snapshots.message = Some(Err(format!("Failed to restore the snapshot: {e}")));
+                return;
+            }
+        };
+        let reason = format!("Before restoring the snapshot of {}", snapshot.taken_at);
+        let restore = |rank: &mut GroupMetaDatabase<R>| {
+            if snapshot.partial {
+                rank.extend(restored.drain());
+            } else {
+                *rank = restored;
+            }
+        };
+        if self
+            .replace_reviews(CommandKind::Restore, &reason, restore)
+            .is_none()
+        {
+            return;
+        }
+
+        self.snapshots.message = Some(Ok(if snapshot.partial {
+            format!(
+                "Put back {} reviews of the snapshot of {}.",
+                snapshot.total, snapshot.taken_at
+            )
+        } else {
+            format!(
+                "Restored the snapshot of {}, with {} manuscripts reviewed.",
+                snapshot.taken_at, snapshot.reviewed
+            )
+        }));
+    }
+}
@@ -0,0 +1,94 @@
+//! Component: Spreadsheet Export
+
+use std::collections::HashMap;
+
+use eframe::egui;
+
+use crate::{
+    types::{
+        rank::{sheet::ReviewSheet, spreadsheet::Spreadsheet, MetaGroup, Rubric},
+        timestamp::Timestamp,
+    },
+    ui::{
+        file::{save_file, Filter},
+        ReviewToolApp,
+    },
+};
+
+const CSV_FILTER: Filter<'static> = ("CSV", &["csv"]);
+const XLSX_FILTER: Filter<'static> = ("Excel workbook", &["xlsx"]);
+
+/// Add `spreadsheet_menu()` to Review Tool App. This trait is for specialization.
+pub(crate) trait SpreadsheetExt {
+    fn spreadsheet_menu(&mut self, ui: &mut egui::Ui);
+}
+
+impl<M: MetaGroup> SpreadsheetExt for ReviewToolApp<M> {
+    default fn spreadsheet_menu(&mut self, _ui: &mut egui::Ui) {}
+}
+
+impl<R: Rubric> SpreadsheetExt for ReviewToolApp<R> {
+    fn spreadsheet_menu(&mut self, ui: &mut egui::Ui) {
+        ui.menu_button("Spreadsheet", |ui| {
+            if ui
+                .button("CSV…")
+                .on_hover_text("One row per manuscript, for Excel or Google Sheets")
+                .clicked()
+            {
+                self.spreadsheet_message = Some(self.export_spreadsheet(CSV_FILTER));
+                ui.close_menu();
+            }
+            if ui.button("XLSX…").clicked() {
+                self.spreadsheet_message = Some(self.export_spreadsheet(XLSX_FILTER));
+                ui.close_menu();
+            }
+        });
+
+        match &self.spreadsheet_message {
+            Some(Err(message)) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {message}"));
+            }
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            None => {}
+        }
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    /// The spreadsheet of the current reviews.
+    fn spreadsheet(&self) -> Spreadsheet {
+        let reviews = self
+            .rank_groups
+            .iter()
+            .map(|(id, rank)| (*id, ReviewSheet::of(rank)))
+            .collect::<HashMap<_, _>>();
+
+        Spreadsheet::new(&self.manuscripts, &ReviewSheet::of(&R::default()), &reviews)
+    }
+
+    fn export_spreadsheet(&self, filter: Filter<'static>) -> Result<String, String> {
+        let spreadsheet = self.spreadsheet();
+        let content = if filter == XLSX_FILTER {
+            spreadsheet.to_xlsx().map_err(|e| e.to_string())?
+        } else {
+            spreadsheet
+                .to_csv()
+                .map_err(|e| e.to_string())?
+                .into_bytes()
+        };
+
+        let name = format!(
+            "reviews-{}-{}.{}",
+            R::ID,
+            Timestamp::now().file_stamp(),
+            filter.1[0]
+        );
+        match save_file(&name, filter, &content) {
+            Ok(Some(saved)) => Ok(format!("Exported to {saved}.")),
+            Ok(None) => Ok("The export has been cancelled.".to_owned()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Component: Sync Server
+
+use eframe::egui;
+use egui::RichText;
+
+use crate::{
+    types::{
+        rank::{
+            sync::{
+                encode_path_segment, snapshot, EditTimes, Snapshot, SyncBase, SyncResponse, Winner,
+            },
+            undo::CommandKind,
+            MetaGroup, Rubric,
+        },
+        timestamp::Timestamp,
+    },
+    ui::{components::export::NO_SNAPSHOT, http::PendingRequest, ReviewToolApp},
+};
+
+/// The sync server started by `review-server` by default.
+pub(crate) const DEFAULT_SERVER: &str = "http://127.0.0.1:8080";
+
+/// The state of the sync window.
+pub(crate) struct SyncState {
+    /// Is the window open?
+    pub(crate) open: bool,
+
+    /// The URL of the sync server.
+    pub(crate) server: String,
+
+    /// The token the sync server requires.
+    pub(crate) token: String,
+
+    /// The reviews as of the last sync.
+    pub(crate) base: SyncBase,
+
+    /// The time every review was last edited on this device.
+    pub(crate) edited_at: EditTimes,
+
+    request: PendingRequest,
+
+    /// The snapshot the request in flight was made of.
+    snapshot: Snapshot,
+
+    /// The result of the last sync.
+    message: Option<Result<String, String>>,
+}
+
+impl SyncState {
+    pub(crate) fn new(
+        server: Option<String>,
+        token: Option<String>,
+        base: SyncBase,
+        edited_at: EditTimes,
+    ) -> Self {
+        Self {
+            open: false,
+            server: server.unwrap_or_else(|| DEFAULT_SERVER.to_owned()),
+            token: token.unwrap_or_default(),
+            base,
+            edited_at,
+            request: PendingRequest::default(),
+            snapshot: Snapshot::new(),
+            message: None,
+        }
+    }
+}
+
+/// Add `sync_window()` to Review Tool App. This trait is for specialization.
+pub(crate) trait SyncExt {
+    fn sync_window(&mut self, ctx: &egui::Context);
+}
+
+impl<M: MetaGroup> SyncExt for ReviewToolApp<M> {
+    default fn sync_window(&mut self, _ctx: &egui::Context) {}
+}
+
+impl<R: Rubric> SyncExt for ReviewToolApp<R> {
+    fn sync_window(&mut self, ctx: &egui::Context) {
+        if let Some(response) = self.sync.request.take() {
+            self.sync.message = Some(self.adopt_response(response.map_err(|e| e.to_string())));
+        }
+
+        let mut open = self.sync.open;
+        egui::Window::new("Sync")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("sync-settings")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Server:");
+                        ui.text_edit_singleline(&mut self.sync.server);
+                        ui.end_row();
+
+                        ui.label("Token:");
+                        ui.add(egui::TextEdit::singleline(&mut self.sync.token).password(true));
+                        ui.end_row();
+
+                        ui.label("Reviewer:");
+                        ui.text_edit_singleline(&mut self.export.reviewer);
+                        ui.end_row();
+                    });
+
+                ui.horizontal(|ui| {
+                    let ready =
+                        !self.sync.request.in_flight() && !self.export.reviewer.trim().is_empty();
+
+                    if ui
+                        .add_enabled(ready, egui::Button::new("Sync now"))
+                        .on_hover_text("Send the changed reviews and receive the others")
+                        .clicked()
+                    {
+                        self.sync.message = self.start_sync(ui.ctx()).err().map(Err);
+                    }
+                    if self.sync.request.in_flight() {
+                        ui.spinner();
+                    }
+                });
+
+                match &self.sync.message {
+                    Some(Ok(message)) => {
+                        ui.label(message);
+                    }
+                    Some(Err(message)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, message);
+                    }
+                    None => {}
+                }
+
+                ui.label(
+                    RichText::new(
+                        "A review changed on both this and another device since the last sync \
+                         is a conflict, and the later edit wins by the device clocks.",
+                    )
+                    .small()
+                    .weak(),
+                );
+            });
+        self.sync.open = open;
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    fn start_sync(&mut self, ctx: &egui::Context) -> Result<(), String> {
+        let snapshot = snapshot(&self.rank_groups).map_err(|e| e.to_string())?;
+        let request = self.sync.base.changes(
+            &snapshot,
+            R::VERSION,
+            &self.sync.edited_at,
+            Timestamp::now(),
+        );
+        let body = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+
+        let url = format!(
+            "{}/api/sync/{}/{}",
+            self.sync.server.trim().trim_end_matches('/'),
+            encode_path_segment(R::ID),
+            encode_path_segment(self.export.reviewer.trim())
+        );
+
+        self.sync.snapshot = snapshot;
+        let token = self.sync.token.trim().to_owned();
+        self.sync.request.post_json(ctx, url, token, body);
+        Ok(())
+    }
+
+    fn adopt_response(&mut self, response: Result<String, String>) -> Result<String, String> {
+        let response: SyncResponse = serde_json::from_str(&response?).map_err(|e| e.to_string())?;
+        let snapshot = std::mem::take(&mut self.sync.snapshot);
+
+        let mut base = std::mem::take(&mut self.sync.base);
+        let adopted = self.replace_reviews(CommandKind::Sync, "Before syncing", |rank| {
+            base.adopt(&response, rank, &snapshot)
+        });
+        self.sync.base = base;
+        let adopted = adopted.ok_or(NO_SNAPSHOT)?;
+
+        // An adopted review was last edited when the server says, not now.
+        for &id in &adopted.replaced {
+            self.sync
+                .edited_at
+                .insert(id, response.reviews[&id].updated_at);
+        }
+
+        let mut message = format!(
+            "Synced {} reviews at {}.",
+            response.reviews.len(),
+            Timestamp::now()
+        );
+        let title = |id| {
+            self.manuscripts.get(id).map_or_else(
+                || "(unknown manuscript)".into(),
+                |m| self.blinded(&m.title, m),
+            )
+        };
+        for conflict in &response.conflicts {
+            let title = title(&conflict.id);
+
+            message += &match conflict.winner {
+                Winner::Local => format!("\n⚠ {title}: overwrote the change of another device."),
+                Winner::Server => {
+                    format!("\n⚠ {title}: replaced by the newer change of another device.")
+                }
+            };
+        }
+        for (id, e) in &adopted.skipped {
+            message += &format!("\n⚠ {}: skipped the review on the server: {e}", title(id));
+        }
+
+        Ok(message)
+    }
+}
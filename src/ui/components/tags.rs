@@ -0,0 +1,182 @@
+//! Component: Tags and Shortlist
+
+use eframe::egui;
+use egui::{Key, RichText};
+
+use crate::{
+    types::{
+        letter::{Decision, Language},
+        rank::MetaGroup,
+    },
+    ui::ReviewToolApp,
+};
+
+/// The max number of the autocompletion suggestions.
+const MAX_SUGGESTIONS: usize = 6;
+
+/// Show a tag as a small chip.
+pub(crate) fn tag_chip(ui: &mut egui::Ui, tag: &str) -> egui::Response {
+    ui.add(
+        egui::Button::new(RichText::new(tag).small())
+            .small()
+            .fill(ui.visuals().faint_bg_color),
+    )
+}
+
+/// Show the star of the shortlist.
+pub(crate) fn star_text(starred: bool) -> &'static str {
+    if starred {
+        "★"
+    } else {
+        "☆"
+    }
+}
+
+impl<M: MetaGroup> ReviewToolApp<M> {
+    /// The editor of the tags, the shortlist star and the decision of the current manuscript.
+    pub(crate) fn tag_editor(&mut self, ui: &mut egui::Ui) {
+        let id = self.current_selected;
+
+        ui.horizontal_wrapped(|ui| {
+            let tags = self.tags.get_or_default(id);
+
+            if ui
+                .button(star_text(tags.starred))
+                .on_hover_text("Add to or remove from the shortlist")
+                .clicked()
+            {
+                tags.starred = !tags.starred;
+            }
+
+            egui::ComboBox::from_id_source("decision")
+                .selected_text(tags.decision.label(Language::English))
+                .width(110.0)
+                .show_ui(ui, |ui| {
+                    for decision in Decision::ALL {
+                        ui.selectable_value(
+                            &mut tags.decision,
+                            decision,
+                            decision.label(Language::English),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text("The decision told by the feedback letters");
+
+            let mut removed = None;
+            for tag in tags.tags.iter() {
+                if tag_chip(ui, &format!("{tag} ×"))
+                    .on_hover_text("Click to remove this tag")
+                    .clicked()
+                {
+                    removed = Some(tag.clone());
+                }
+            }
+            if let Some(tag) = removed {
+                tags.remove(&tag);
+            }
+
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.tag_input)
+                    .hint_text("Add a tag…")
+                    .desired_width(120.0),
+            );
+
+            if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                let input = std::mem::take(&mut self.tag_input);
+                self.tags.get_or_default(id).insert(&input);
+                response.request_focus();
+            }
+        });
+
+        if self.tag_input.is_empty() {
+            return;
+        }
+
+        // Autocomplete from the tags already used.
+        let suggestions = self
+            .tags
+            .suggest(&self.tag_input, self.tags.get(&id))
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+
+        if suggestions.is_empty() {
+            return;
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(RichText::new("Suggestions:").small().weak());
+
+            for suggestion in suggestions {
+                if tag_chip(ui, &suggestion).clicked() {
+                    self.tags.get_or_default(id).insert(&suggestion);
+                    self.tag_input.clear();
+                }
+            }
+        });
+    }
+
+    /// The private note of the current manuscript.
+    pub(crate) fn private_note(&mut self, ui: &mut egui::Ui) {
+        let note = &mut self.tags.get_or_default(self.current_selected).note;
+
+        egui::CollapsingHeader::new(if note.is_empty() {
+            "Private note"
+        } else {
+            "Private note ✏"
+        })
+        .id_source("private-note")
+        .show(ui, |ui| {
+            ui.add(
+                egui::TextEdit::multiline(note)
+                    .hint_text("Only you can see this note. It is never sent to the author.")
+                    .desired_rows(2),
+            );
+        });
+    }
+
+    /// The filter of the manuscript list by tags and the shortlist.
+    pub(crate) fn tag_filter(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.toggle_value(&mut self.starred_only, "★ Shortlist");
+
+            let all_tags = self.tags.all_tags();
+
+            let selected = &mut self.filter.tags;
+            let selected_text = if selected.is_empty() {
+                "All tags".to_owned()
+            } else {
+                selected.iter().cloned().collect::<Vec<_>>().join(" + ")
+            };
+
+            egui::ComboBox::from_id_source("tag-filter")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    if ui
+                        .selectable_label(selected.is_empty(), "All tags")
+                        .clicked()
+                    {
+                        selected.clear();
+                    }
+
+                    for (tag, count) in all_tags {
+                        let mut checked = selected.contains(tag);
+                        if ui
+                            .checkbox(&mut checked, format!("{tag} ({count})"))
+                            .changed()
+                        {
+                            if checked {
+                                selected.insert(tag.to_owned());
+                            } else {
+                                selected.remove(tag);
+                            }
+                        }
+                    }
+                })
+                .response
+                .on_hover_text("List the manuscripts with all the selected tags");
+        });
+    }
+}
@@ -0,0 +1,231 @@
+//! Component: Disagreement Triage
+
+use eframe::egui;
+use egui::RichText;
+
+use crate::{
+    types::{
+        rank::{
+            sheet::ReviewerSheets,
+            triage::{triage, Disagreement, TriageOrder},
+            Rubric,
+        },
+        ManuscriptId,
+    },
+    ui::{cache::Cached, components::aggregate::SheetsKey, ReviewToolApp, View},
+};
+
+/// The state of the triage view.
+#[derive(Default)]
+pub(crate) struct TriageState {
+    order: TriageOrder,
+
+    /// The manuscript to compare the reviews of.
+    selected: Option<ManuscriptId>,
+
+    /// The manuscripts sorted by the disagreement, in the order above.
+    disagreements: Cached<(SheetsKey, TriageOrder), Vec<Disagreement>>,
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    /// The manuscripts sorted by the disagreement, and the reviews side by side.
+    pub(crate) fn triage(&mut self, ui: &mut egui::Ui, sheets: &[ReviewerSheets]) {
+        if sheets.len() < 2 {
+            ui.label("At least 2 reviewers are needed to triage the disagreement.");
+            return;
+        }
+
+        let order = self.aggregate.triage.order;
+        let key = (self.sheets_key(), order);
+        let manuscripts = &self.manuscripts;
+        let disagreements = self
+            .aggregate
+            .triage
+            .disagreements
+            .get(key, || triage(manuscripts, sheets, order));
+
+        ui.horizontal(|ui| {
+            let order = &mut self.aggregate.triage.order;
+
+            ui.label("Sort by:");
+            ui.radio_value(order, TriageOrder::TotalSpread, "Total score spread");
+            ui.radio_value(order, TriageOrder::ItemDisagreement, "Item disagreement");
+        });
+        ui.separator();
+
+        if disagreements.is_empty() {
+            ui.label("No manuscript has been reviewed by 2 reviewers yet.");
+            return;
+        }
+
+        egui::Grid::new("triage-ui")
+            .num_columns(2)
+            .min_col_width(256.0)
+            .min_row_height(ui.available_height())
+            .show(ui, |ui| {
+                ui.push_id("triage-list", |ui| {
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        self.triage_list(ui, &disagreements);
+                    });
+                });
+
+                ui.push_id("triage-reviews", |ui| {
+                    egui::ScrollArea::both().show(ui, |ui| {
+                        let selected = self
+                            .aggregate
+                            .triage
+                            .selected
+                            .and_then(|id| disagreements.iter().find(|d| d.id == id));
+
+                        match selected {
+                            Some(disagreement) => self.side_by_side(ui, sheets, disagreement),
+                            None => {
+                                ui.label("Select a manuscript to compare the reviews.");
+                            }
+                        }
+                    });
+                });
+
+                ui.end_row();
+            });
+    }
+
+    fn triage_list(&mut self, ui: &mut egui::Ui, disagreements: &[Disagreement]) {
+        for disagreement in disagreements {
+            let Some(manuscript) = self.manuscripts.get(&disagreement.id).cloned() else {
+                continue;
+            };
+
+            let title = self.blinded(&manuscript.title, &manuscript);
+            let selected = self.aggregate.triage.selected == Some(disagreement.id);
+
+            ui.vertical(|ui| {
+                if ui.selectable_label(selected, title.as_ref()).clicked() {
+                    self.aggregate.triage.selected = Some(disagreement.id);
+                }
+
+                let total = disagreement.total;
+                ui.label(
+                    RichText::new(format!(
+                        "SD {:.2} ({:.1} – {:.1}) · item disagreement {:.2} · {} disputed items",
+                        total.std_dev,
+                        total.min,
+                        total.max,
+                        disagreement.item_disagreement,
+                        disagreement.disputed_items.len()
+                    ))
+                    .small()
+                    .weak(),
+                );
+            });
+            ui.add_space(4.0);
+        }
+    }
+
+    /// Every reviewer's choices and comments of the manuscript, side by side.
+    fn side_by_side(
+        &mut self,
+        ui: &mut egui::Ui,
+        sheets: &[ReviewerSheets],
+        disagreement: &Disagreement,
+    ) {
+        let id = disagreement.id;
+        let reviews = sheets
+            .iter()
+            .filter_map(|s| Some((s.reviewer.as_str(), s.reviewed(&id)?)))
+            .collect::<Vec<_>>();
+        let Some((_, first)) = reviews.first() else {
+            return;
+        };
+
+        if let Some(manuscript) = self.manuscripts.get(&id).cloned() {
+            ui.horizontal(|ui| {
+                ui.heading(self.blinded(&manuscript.title, &manuscript).as_ref());
+                if ui.button("Review").clicked() {
+                    self.current_selected = id;
+                    self.view = View::Review;
+                }
+            });
+        }
+
+        egui::Grid::new("triage-side-by-side")
+            .num_columns(1 + reviews.len())
+            .min_col_width(160.0)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Item");
+                for (reviewer, _) in &reviews {
+                    ui.strong(*reviewer);
+                }
+                ui.end_row();
+
+                for group in &first.groups {
+                    ui.strong(group.name.as_str());
+                    for (_, review) in &reviews {
+                        ui.strong(
+                            review
+                                .group(group.key)
+                                .map_or_else(|| "-".to_owned(), |g| format!("{:.1}", g.score)),
+                        );
+                    }
+                    ui.end_row();
+
+                    for item in &group.items {
+                        match disagreement.disputed(group.key, item.key) {
+                            Some(disputed) => {
+                                ui.colored_label(
+                                    ui.visuals().warn_fg_color,
+                                    format!("⚠ {}", item.name),
+                                )
+                                .on_hover_text(format!(
+                                    "The choices differ by {} levels",
+                                    disputed.range
+                                ));
+                            }
+                            None => {
+                                ui.label(item.name.as_str());
+                            }
+                        }
+
+                        for (_, review) in &reviews {
+                            let choice = review
+                                .group(group.key)
+                                .and_then(|g| g.items.iter().find(|i| i.key == item.key));
+
+                            ui.vertical(|ui| match choice {
+                                Some(choice) => {
+                                    ui.label(format!(
+                                        "{} {}",
+                                        choice.choice.as_emoji(),
+                                        choice.choice
+                                    ));
+                                    if let Some(comment) =
+                                        choice.comment.as_deref().filter(|c| !c.is_empty())
+                                    {
+                                        ui.label(RichText::new(comment).small().weak());
+                                    }
+                                }
+                                None => {
+                                    ui.label("-");
+                                }
+                            });
+                        }
+                        ui.end_row();
+                    }
+
+                    ui.label(RichText::new("Comment").italics());
+                    for (_, review) in &reviews {
+                        let comment = review.group(group.key).map_or("", |g| g.comment.as_str());
+                        ui.label(comment);
+                    }
+                    ui.end_row();
+                }
+
+                ui.strong("總分");
+                for (_, review) in &reviews {
+                    ui.strong(format!("{:.1}", review.total));
+                }
+                ui.end_row();
+            });
+    }
+}
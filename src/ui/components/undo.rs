@@ -0,0 +1,223 @@
+//! Component: Undo and Redo
+
+#[cfg(not(target_family = "wasm"))]
+use std::time;
+
+#[cfg(target_family = "wasm")]
+use web_time as time;
+
+use eframe::egui::{self, Key, Modifiers};
+use serde_yaml::Value;
+
+use crate::{
+    types::{
+        rank::{
+            undo::{self, snapshot, Change, Command, CommandKind, UndoHistory},
+            GroupMetaDatabase, MetaGroup, Rubric,
+        },
+        ManuscriptId,
+    },
+    ui::ReviewToolApp,
+};
+
+/// The edits of the same manuscript within this interval are undone at once.
+const MERGE_WINDOW: time::Duration = time::Duration::from_secs(1);
+
+/// The state of the undo history.
+#[derive(Default)]
+pub(crate) struct UndoState {
+    history: UndoHistory,
+
+    /// The meta group of the selected manuscript as of the last frame.
+    tracked: Option<(ManuscriptId, Option<Value>)>,
+    /// When the last edit was recorded.
+    edited_at: Option<time::Instant>,
+
+    /// The error of the last undo or redo.
+    message: Option<String>,
+}
+
+/// Add `undo_tick()` and `undo_buttons()` to Review Tool App. This trait is for specialization.
+pub(crate) trait UndoExt {
+    /// Record the edits made since the last frame, and handle Ctrl+Z and Ctrl+Shift+Z.
+    fn undo_tick(&mut self, ctx: &egui::Context);
+
+    fn undo_buttons(&mut self, ui: &mut egui::Ui);
+}
+
+impl<M: MetaGroup> UndoExt for ReviewToolApp<M> {
+    default fn undo_tick(&mut self, _ctx: &egui::Context) {}
+
+    default fn undo_buttons(&mut self, _ui: &mut egui::Ui) {}
+}
+
+impl<R: Rubric> UndoExt for ReviewToolApp<R> {
+    fn undo_tick(&mut self, ctx: &egui::Context) {
+        self.track_edit();
+
+        // Leave the shortcuts to the focused text field, which has its own undo.
+        if ctx.memory(|m| m.focus().is_some()) {
+            return;
+        }
+
+        let (undo, redo) = ctx.input_mut(|i| {
+            (
+                i.consume_key(Modifiers::COMMAND, Key::Z),
+                i.consume_key(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z),
+            )
+        });
+        if undo {
+            self.undo();
+        }
+        if redo {
+            self.redo();
+        }
+    }
+
+    fn undo_buttons(&mut self, ui: &mut egui::Ui) {
+        let undo_text = self.undo.history.last().map(|c| self.command_text(c));
+        let redo_text = self.undo.history.next().map(|c| self.command_text(c));
+
+        if ui
+            .add_enabled(undo_text.is_some(), egui::Button::new("Undo"))
+            .on_hover_text(format!("Undo {} (Ctrl+Z)", undo_text.unwrap_or_default()))
+            .clicked()
+        {
+            self.undo();
+        }
+        if ui
+            .add_enabled(redo_text.is_some(), egui::Button::new("Redo"))
+            .on_hover_text(format!(
+                "Redo {} (Ctrl+Shift+Z)",
+                redo_text.unwrap_or_default()
+            ))
+            .clicked()
+        {
+            self.redo();
+        }
+
+        if let Some(message) = &self.undo.message {
+            ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {message}"));
+        }
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    /// Change the reviews with `change`, recorded as one command of `kind`
+    /// and logged in the change history.
+    pub(crate) fn change_reviews<T>(
+        &mut self,
+        kind: CommandKind,
+        change: impl FnOnce(&mut GroupMetaDatabase<R>) -> T,
+    ) -> T {
+        let (result, changes) = self.write_reviews(change);
+        self.record_changes(kind, changes);
+
+        result
+    }
+
+    /// Record the changes written by [`Self::write_reviews`] as one command of `kind`.
+    pub(crate) fn record_changes(&mut self, kind: CommandKind, changes: Vec<Change>) {
+        self.undo.history.record(kind, changes);
+        // The change is recorded as a whole, not as an edit of the selected manuscript.
+        self.undo.tracked = None;
+    }
+
+    /// Record the edit of the selected manuscript since the last frame.
+    fn track_edit(&mut self) {
+        let id = self.current_selected;
+        let current = match snapshot(self.rank_groups.get(&id)) {
+            Ok(current) => current,
+            Err(e) => {
+                tracing::error!("failed to track the edit: {e}");
+                return;
+            }
+        };
+
+        let Some((tracked, before)) = self.undo.tracked.replace((id, current.clone())) else {
+            return;
+        };
+        if tracked != id || before == current {
+            return;
+        }
+
+        self.log_changes([(id, &before, &current)]);
+
+        let now = time::Instant::now();
+        let merge = self
+            .undo
+            .edited_at
+            .is_some_and(|t| now.duration_since(t) < MERGE_WINDOW);
+        self.undo.history.record_edit(
+            Change {
+                id,
+                before,
+                after: current,
+            },
+            merge,
+        );
+        self.undo.edited_at = Some(now);
+    }
+
+    fn undo(&mut self) {
+        let mut history = std::mem::take(&mut self.undo.history);
+        let (result, _) =
+            self.write_reviews(|rank| history.undo(rank).map(|c| c.map(Command::reversed)));
+        self.undo.history = history;
+        self.after_undo(result);
+    }
+
+    fn redo(&mut self) {
+        let mut history = std::mem::take(&mut self.undo.history);
+        let (result, _) = self.write_reviews(|rank| history.redo(rank).map(|c| c.cloned()));
+        self.undo.history = history;
+        self.after_undo(result);
+    }
+
+    /// Show the command as applied.
+    fn after_undo(&mut self, result: Result<Option<Command>, undo::Error>) {
+        match result {
+            Ok(applied) => {
+                if let Some(command) = applied {
+                    // Show the manuscript whose review is changed.
+                    if let Some(id) = command
+                        .manuscript()
+                        .filter(|id| self.manuscripts.contains_key(id))
+                    {
+                        self.current_selected = id;
+                    }
+                }
+                self.undo.message = None;
+            }
+            Err(e) => self.undo.message = Some(e.to_string()),
+        }
+
+        // The undone state is not a new edit, and the next edit starts a new command.
+        self.undo.tracked = None;
+        self.undo.edited_at = None;
+        self.track_edit();
+    }
+}
+
+impl<M: MetaGroup> ReviewToolApp<M> {
+    fn command_text(&self, command: &Command) -> String {
+        match command.kind {
+            CommandKind::Edit => {
+                let title = command
+                    .manuscript()
+                    .and_then(|id| self.manuscripts.get(&id));
+                match title {
+                    Some(m) => format!("the edit of “{}”", self.blinded(&m.title, m)),
+                    None => "the edit".to_owned(),
+                }
+            }
+            CommandKind::Reset => format!("the reset of {} reviews", command.changes.len()),
+            CommandKind::Restore => "the restore of the snapshot".to_owned(),
+            CommandKind::Import => format!("the import of {} reviews", command.changes.len()),
+            CommandKind::Merge => format!("the merge of {} reviews", command.changes.len()),
+            CommandKind::Sync => format!("the sync of {} reviews", command.changes.len()),
+            CommandKind::OpenWorkspace => "the opening of the workspace".to_owned(),
+            CommandKind::Recover => "the recovery of the unsaved reviews".to_owned(),
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! Component: Workspace
+
+use std::path::Path;
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    types::{
+        rank::{
+            registry::{self, RubricEntry},
+            undo::CommandKind,
+            MetaGroup, Rubric,
+        },
+        workspace::{ManuscriptSource, OpenWorkspace, Workspace, DEFAULT_MANUSCRIPTS_PATH},
+        ManuscriptDatabase,
+    },
+    ui::{
+        file::{overwrite_file, FilePicker, Filter, OpenedFile},
+        Error, ReviewToolApp,
+    },
+};
+
+/// The filter of the workspace files.
+const WORKSPACE_FILTER: Filter<'static> = ("Review workspace (YAML)", &["yaml", "yml"]);
+
+/// The workspace file of the event, stored with the state of the event.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct WorkspaceFile {
+    /// The name of the event.
+    pub(crate) name: String,
+
+    /// The workspace file, which "Save" writes to without asking.
+    path: Option<String>,
+
+    /// Embed a copy of the manuscripts instead of referring to their CSV.
+    embed: bool,
+    /// The CSV of the manuscripts, when they are not embedded.
+    manuscripts_path: String,
+}
+
+impl Default for WorkspaceFile {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            path: None,
+            embed: true,
+            manuscripts_path: DEFAULT_MANUSCRIPTS_PATH.to_owned(),
+        }
+    }
+}
+
+/// The state of the workspace menu.
+pub(crate) struct WorkspaceState {
+    pub(crate) file: WorkspaceFile,
+
+    /// The workspace open in the app; `None` for the manuscripts read on startup.
+    pub(crate) open: Option<OpenWorkspace>,
+
+    picker: FilePicker,
+    /// The workspace the user opened, for [`crate::ui::ReviewTool`] to switch to.
+    request: Option<WorkspaceRequest>,
+    /// Has the user requested to close the workspace?
+    close: bool,
+
+    /// The result of the last action.
+    message: Option<Result<String, String>>,
+}
+
+impl WorkspaceState {
+    pub(crate) fn new(file: WorkspaceFile, open: Option<OpenWorkspace>) -> Self {
+        Self {
+            file,
+            open,
+            picker: FilePicker::default(),
+            request: None,
+            close: false,
+            message: None,
+        }
+    }
+
+    /// Take the workspace the user requested to open.
+    pub(crate) fn take_request(&mut self) -> Option<WorkspaceRequest> {
+        self.request.take()
+    }
+
+    /// Take the request to close the workspace.
+    pub(crate) fn take_close_request(&mut self) -> bool {
+        std::mem::take(&mut self.close)
+    }
+
+    /// Tell the user the workspace failed to open.
+    pub(crate) fn failed(&mut self, error: &Error) {
+        self.message = Some(Err(format!("Failed to open the workspace: {error}")));
+    }
+}
+
+/// A workspace opened by the user, with its manuscripts loaded.
+pub(crate) struct WorkspaceRequest {
+    pub(crate) rubric: &'static RubricEntry,
+    pub(crate) manuscripts: ManuscriptDatabase,
+    pub(crate) workspace: Workspace,
+
+    /// The path of the workspace file, if known.
+    pub(crate) path: Option<String>,
+
+    /// The workspace to reopen on startup.
+    pub(crate) open: OpenWorkspace,
+}
+
+/// Add `workspace_menu()` to Review Tool App. This trait is for specialization.
+pub(crate) trait WorkspaceExt {
+    fn workspace_menu(&mut self, ui: &mut egui::Ui);
+}
+
+impl<M: MetaGroup> WorkspaceExt for ReviewToolApp<M> {
+    default fn workspace_menu(&mut self, _ui: &mut egui::Ui) {}
+}
+
+impl<R: Rubric> WorkspaceExt for ReviewToolApp<R> {
+    fn workspace_menu(&mut self, ui: &mut egui::Ui) {
+        if let Some(opened) = self.workspace.picker.take() {
+            if let Err(e) = self.read_workspace(opened.map_err(|e| e.to_string())) {
+                self.workspace.message = Some(Err(e));
+            }
+        }
+
+        ui.menu_button("Workspace", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Event:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.workspace.file.name)
+                        .hint_text("SITCON 2024"),
+                );
+            });
+            ui.checkbox(&mut self.workspace.file.embed, "Embed the manuscripts")
+                .on_hover_text("Save a copy of the manuscripts, so the workspace works on its own");
+            if !self.workspace.file.embed {
+                ui.horizontal(|ui| {
+                    ui.label("Manuscripts CSV:");
+                    ui.text_edit_singleline(&mut self.workspace.file.manuscripts_path);
+                });
+            }
+
+            ui.separator();
+
+            if ui
+                .button("Open…")
+                .on_hover_text(
+                    "Replace the manuscripts, the reviews and the tags with a workspace. \
+                     Save the current workspace first.",
+                )
+                .clicked()
+            {
+                self.workspace.picker.open(ui.ctx(), WORKSPACE_FILTER);
+                ui.close_menu();
+            }
+            if ui
+                .add_enabled(self.workspace.open.is_some(), egui::Button::new("Close"))
+                .on_hover_text(format!(
+                    "Return to the manuscripts in {DEFAULT_MANUSCRIPTS_PATH}. \
+                     The state of the workspace is kept for when it is opened again."
+                ))
+                .clicked()
+            {
+                self.workspace.close = true;
+                ui.close_menu();
+            }
+            let save = ui.button("Save");
+            let save = match &self.workspace.file.path {
+                Some(path) => save.on_hover_text(format!("Save to {path}")),
+                None => save,
+            };
+            if save.clicked() {
+                self.workspace.message = Some(self.save_workspace(false));
+                ui.close_menu();
+            }
+            if ui.button("Save As…").clicked() {
+                self.workspace.message = Some(self.save_workspace(true));
+                ui.close_menu();
+            }
+        });
+
+        match &self.workspace.message {
+            Some(Err(message)) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {message}"));
+            }
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            None => {}
+        }
+    }
+}
+
+impl<R: Rubric> ReviewToolApp<R> {
+    fn save_workspace(&mut self, save_as: bool) -> Result<String, String> {
+        let source = if self.workspace.file.embed {
+            ManuscriptSource::embed(&self.manuscripts)
+        } else {
+            // the workspace may be saved to another directory.
+            let path = &self.workspace.file.manuscripts_path;
+            ManuscriptSource::Path(
+                std::fs::canonicalize(path)
+                    .map_or_else(|_| path.clone(), |p| p.display().to_string()),
+            )
+        };
+
+        self.tags.compact();
+        let mut workspace = Workspace::new(
+            &self.workspace.file.name,
+            source,
+            &self.rank_groups,
+            &self.tags,
+        )
+        .map_err(|e| e.to_string())?;
+        workspace.history = self.changelog.clone();
+        let document = workspace.to_yaml().map_err(|e| e.to_string())?;
+
+        let path = self.workspace.file.path.as_deref().filter(|_| !save_as);
+        match overwrite_file(
+            path,
+            &workspace.file_name(),
+            WORKSPACE_FILTER,
+            document.as_bytes(),
+        ) {
+            Ok(Some(saved)) => {
+                self.workspace.file.path = Some(saved.clone());
+                Ok(format!("Saved the workspace to {saved}."))
+            }
+            Ok(None) => Ok("The save has been cancelled.".to_owned()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Read the workspace and its manuscripts, and request to switch to it.
+    fn read_workspace(&mut self, opened: Result<OpenedFile, String>) -> Result<(), String> {
+        let opened = opened?;
+        let document = String::from_utf8(opened.content).map_err(|e| e.to_string())?;
+        let workspace = Workspace::from_yaml(&document).map_err(|e| e.to_string())?;
+        let rubric = registry::find(&workspace.rubric)
+            .ok_or_else(|| Error::UnknownRubric(workspace.rubric.clone()).to_string())?;
+
+        let dir = opened.path.as_deref().and_then(|p| Path::new(p).parent());
+        let manuscripts: ManuscriptDatabase = workspace
+            .manuscripts
+            .load(dir)
+            .map_err(|e| e.to_string())?
+            .into();
+        if manuscripts.is_empty() {
+            return Err(Error::NoManuscript.to_string());
+        }
+
+        let open = OpenWorkspace::new(
+            opened.path.as_deref().unwrap_or(&workspace.name),
+            workspace.manuscripts.clone().resolve(dir),
+        );
+        self.workspace.request = Some(WorkspaceRequest {
+            rubric,
+            manuscripts,
+            workspace,
+            path: opened.path,
+            open,
+        });
+        Ok(())
+    }
+
+    /// Replace the reviews, the tags and the change history with the workspace.
+    pub(crate) fn load_workspace(
+        &mut self,
+        workspace: Workspace,
+        path: Option<String>,
+    ) -> Result<(), Error> {
+        let rank = workspace.rank()?;
+
+        // The opening is logged on top of the history of the workspace.
+        let changelog = std::mem::replace(&mut self.changelog, workspace.history);
+        let reason = format!(
+            "Before opening {}",
+            path.as_deref().unwrap_or(&workspace.name)
+        );
+        if self
+            .replace_reviews(CommandKind::OpenWorkspace, &reason, |current| {
+                *current = rank
+            })
+            .is_none()
+        {
+            self.changelog = changelog;
+            return Err(Error::Snapshot);
+        }
+        self.tags = workspace.tags;
+
+        self.workspace.file.embed = matches!(workspace.manuscripts, ManuscriptSource::Embedded(_));
+        if let ManuscriptSource::Path(manuscripts) = workspace
+            .manuscripts
+            .clone()
+            .resolve(path.as_deref().and_then(|p| Path::new(p).parent()))
+        {
+            self.workspace.file.manuscripts_path = manuscripts;
+        }
+        self.workspace.message = Some(Ok(format!(
+            "Opened {}: {} reviews of {} manuscripts.",
+            path.as_deref().unwrap_or(&workspace.name),
+            self.rank_groups.len(),
+            self.manuscripts.len()
+        )));
+        self.workspace.file.name = workspace.name;
+        self.workspace.file.path = path;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+//! Save and open the files, in both the native and the web build.
+
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use eframe::egui;
+
+/// The filter of a file dialog, such as `("YAML", &["yaml", "yml"])`.
+pub(crate) type Filter<'a> = (&'a str, &'a [&'a str]);
+
+/// A file opened by the user.
+pub(crate) struct OpenedFile {
+    pub name: String,
+    pub content: Vec<u8>,
+
+    /// The full path of the file. It is unknown on the web.
+    pub path: Option<String>,
+}
+
+/// Ask the user to open files.
+///
+/// The files are read asynchronously on the web,
+/// so poll them with [`FilePicker::take`] in every frame.
+#[derive(Default)]
+pub(crate) struct FilePicker {
+    opened: Rc<RefCell<VecDeque<Result<OpenedFile, Error>>>>,
+}
+
+impl FilePicker {
+    /// Show the dialog to open a file.
+    pub(crate) fn open(&self, ctx: &egui::Context, filter: Filter<'_>) {
+        self.pick(ctx, filter, false);
+    }
+
+    /// Show the dialog to open several files.
+    pub(crate) fn open_many(&self, ctx: &egui::Context, filter: Filter<'_>) {
+        self.pick(ctx, filter, true);
+    }
+
+    fn pick(&self, ctx: &egui::Context, filter: Filter<'_>, many: bool) {
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let _ = ctx;
+
+            let dialog = rfd::FileDialog::new().add_filter(filter.0, filter.1);
+            let paths = if many {
+                dialog.pick_files().unwrap_or_default()
+            } else {
+                dialog.pick_file().into_iter().collect()
+            };
+
+            let mut opened = self.opened.borrow_mut();
+            for path in paths {
+                opened.push_back(
+                    std::fs::read(&path)
+                        .map(|content| OpenedFile {
+                            name: path
+                                .file_name()
+                                .map(|name| name.to_string_lossy().into_owned())
+                                .unwrap_or_default(),
+                            content,
+                            path: Some(path.display().to_string()),
+                        })
+                        .map_err(Error::from),
+                );
+            }
+        }
+
+        #[cfg(target_family = "wasm")]
+        {
+            let dialog = rfd::AsyncFileDialog::new().add_filter(filter.0, filter.1);
+            let opened = self.opened.clone();
+            let ctx = ctx.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let files = if many {
+                    dialog.pick_files().await.unwrap_or_default()
+                } else {
+                    dialog.pick_file().await.into_iter().collect()
+                };
+
+                for file in files {
+                    let content = file.read().await;
+
+                    opened.borrow_mut().push_back(Ok(OpenedFile {
+                        name: file.file_name(),
+                        content,
+                        path: None,
+                    }));
+                }
+                ctx.request_repaint();
+            });
+        }
+    }
+
+    /// Take a file opened by the user, if any.
+    pub(crate) fn take(&self) -> Option<Result<OpenedFile, Error>> {
+        self.opened.borrow_mut().pop_front()
+    }
+}
+
+/// Save `content` to the file the user chooses, or download it on the web.
+///
+/// Return where the file is saved, or `None` if the user cancelled.
+pub(crate) fn save_file(
+    name: &str,
+    filter: Filter<'_>,
+    content: &[u8],
+) -> Result<Option<String>, Error> {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        let Some(path) = rfd::FileDialog::new()
+            .set_file_name(name)
+            .add_filter(filter.0, filter.1)
+            .save_file()
+        else {
+            return Ok(None);
+        };
+
+        std::fs::write(&path, content)?;
+        Ok(Some(path.display().to_string()))
+    }
+
+    #[cfg(target_family = "wasm")]
+    {
+        let _ = filter;
+
+        download(name, content).map_err(|e| Error::Download(format!("{e:?}")))?;
+        Ok(Some(name.to_owned()))
+    }
+}
+
+/// Save `content` to `path`, or to the file the user chooses if there is no path.
+///
+/// The web build always downloads the file as `name`.
+pub(crate) fn overwrite_file(
+    path: Option<&str>,
+    name: &str,
+    filter: Filter<'_>,
+    content: &[u8],
+) -> Result<Option<String>, Error> {
+    #[cfg(not(target_family = "wasm"))]
+    if let Some(path) = path {
+        std::fs::write(path, content)?;
+        return Ok(Some(path.to_owned()));
+    }
+
+    #[cfg(target_family = "wasm")]
+    let _ = path;
+
+    save_file(name, filter, content)
+}
+
+/// Download `content` as the file `name` in the browser.
+#[cfg(target_family = "wasm")]
+fn download(name: &str, content: &[u8]) -> Result<(), eframe::wasm_bindgen::JsValue> {
+    use eframe::wasm_bindgen::JsCast;
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(content));
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document")?;
+    let anchor = document
+        .create_element("a")?
+        .dyn_into::<web_sys::HtmlAnchorElement>()?;
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[cfg(target_family = "wasm")]
+    #[error("failed to download the file: {0}")]
+    Download(String),
+}
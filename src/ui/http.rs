@@ -0,0 +1,127 @@
+//! The HTTP requests, in both the native and the web build.
+
+use std::sync::{Arc, Mutex};
+
+use eframe::egui;
+
+/// A request whose response arrives asynchronously.
+///
+/// Poll the response with [`PendingRequest::take`] in every frame.
+#[derive(Default)]
+pub(crate) struct PendingRequest {
+    response: Arc<Mutex<Option<Result<String, Error>>>>,
+    in_flight: bool,
+}
+
+impl PendingRequest {
+    /// Is a request waiting for its response?
+    pub(crate) fn in_flight(&self) -> bool {
+        self.in_flight
+    }
+
+    /// POST the JSON `body` to `url`, with the bearer `token` unless it is empty.
+    pub(crate) fn post_json(
+        &mut self,
+        ctx: &egui::Context,
+        url: String,
+        token: String,
+        body: String,
+    ) {
+        self.in_flight = true;
+
+        let response = self.response.clone();
+        let ctx = ctx.clone();
+        let send = move |result| {
+            if let Ok(mut response) = response.lock() {
+                *response = Some(result);
+            }
+            ctx.request_repaint();
+        };
+
+        #[cfg(not(target_family = "wasm"))]
+        std::thread::spawn(move || {
+            let mut request = ureq::post(&url).set("Content-Type", "application/json");
+            if !token.is_empty() {
+                request = request.set("Authorization", &format!("Bearer {token}"));
+            }
+
+            let result = match request.send_string(&body) {
+                Ok(response) => response.into_string().map_err(Error::from),
+                Err(ureq::Error::Status(status, response)) => Err(Error::Status(
+                    status,
+                    response.into_string().unwrap_or_default(),
+                )),
+                Err(e) => Err(Error::Transport(e.to_string())),
+            };
+
+            send(result);
+        });
+
+        #[cfg(target_family = "wasm")]
+        wasm_bindgen_futures::spawn_local(async move {
+            send(fetch("POST", &url, &token, &body).await);
+        });
+    }
+
+    /// Take the response, if it has arrived.
+    pub(crate) fn take(&mut self) -> Option<Result<String, Error>> {
+        let response = self.response.lock().ok()?.take()?;
+        self.in_flight = false;
+
+        Some(response)
+    }
+}
+
+/// Send the request with the Fetch API of the browser.
+#[cfg(target_family = "wasm")]
+async fn fetch(method: &str, url: &str, token: &str, body: &str) -> Result<String, Error> {
+    use eframe::wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+
+    let transport = |e: JsValue| Error::Transport(format!("{e:?}"));
+
+    let mut init = web_sys::RequestInit::new();
+    init.method(method).body(Some(&JsValue::from_str(body)));
+
+    let request = web_sys::Request::new_with_str_and_init(url, &init).map_err(transport)?;
+    request
+        .headers()
+        .set("Content-Type", "application/json")
+        .map_err(transport)?;
+    if !token.is_empty() {
+        request
+            .headers()
+            .set("Authorization", &format!("Bearer {token}"))
+            .map_err(transport)?;
+    }
+
+    let window = web_sys::window().ok_or_else(|| Error::Transport("no window".to_owned()))?;
+    let response = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .and_then(|r| r.dyn_into::<web_sys::Response>())
+        .map_err(transport)?;
+    let text = JsFuture::from(response.text().map_err(transport)?)
+        .await
+        .map_err(transport)?
+        .as_string()
+        .unwrap_or_default();
+
+    if response.ok() {
+        Ok(text)
+    } else {
+        Err(Error::Status(response.status(), text))
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("the server replied {0}: {1}")]
+    Status(u16, String),
+
+    #[error("failed to connect to the server: {0}")]
+    Transport(String),
+
+    #[cfg(not(target_family = "wasm"))]
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,67 @@
+//! The review apps of the registered rubrics.
+//!
+//! To add a rubric, register it in [`crate::types::rank::registry`]
+//! and its review app in [`APPS`].
+
+use std::rc::Rc;
+
+use crate::types::{
+    rank::{registry::RubricEntry, sitcon_gdsc, Rubric},
+    workspace::OpenWorkspace,
+    ManuscriptDatabase,
+};
+
+use super::{Error, ReviewToolApp, RubricApp};
+
+type BuildFn = fn(
+    Option<&dyn eframe::Storage>,
+    Rc<ManuscriptDatabase>,
+    Option<OpenWorkspace>,
+) -> Result<Box<dyn RubricApp>, Error>;
+
+/// The review app of a registered rubric.
+struct AppEntry {
+    /// The ID of the rubric. See [`Rubric::ID`].
+    id: &'static str,
+
+    build: BuildFn,
+}
+
+impl AppEntry {
+    const fn of<R: Rubric>() -> Self {
+        Self {
+            id: R::ID,
+            build: build_of::<R>,
+        }
+    }
+}
+
+fn build_of<R: Rubric>(
+    storage: Option<&dyn eframe::Storage>,
+    manuscripts: Rc<ManuscriptDatabase>,
+    open: Option<OpenWorkspace>,
+) -> Result<Box<dyn RubricApp>, Error> {
+    Ok(Box::new(ReviewToolApp::<R>::new(
+        storage,
+        manuscripts,
+        open,
+    )?))
+}
+
+/// The review apps, one for every rubric in [`crate::types::rank::registry::RUBRICS`].
+const APPS: &[AppEntry] = &[AppEntry::of::<sitcon_gdsc::Group>()];
+
+/// Build the review app of the `rubric`, in the `open` workspace if any.
+pub(crate) fn build(
+    rubric: &RubricEntry,
+    storage: Option<&dyn eframe::Storage>,
+    manuscripts: Rc<ManuscriptDatabase>,
+    open: Option<OpenWorkspace>,
+) -> Result<Box<dyn RubricApp>, Error> {
+    let app = APPS
+        .iter()
+        .find(|a| a.id == rubric.id)
+        .ok_or_else(|| Error::UnknownRubric(rubric.id.to_owned()))?;
+
+    (app.build)(storage, manuscripts, open)
+}
@@ -119,22 +119,21 @@ impl State {
         const STRING_TABLE: &[(StateBit, &str)] = &[
             (
                 STATE_SAVED | STATE_DUMP | STATE_RESET,
-                "Dumped the reset state! See console.",
+                "The rank has been reset, saved & dumped!",
             ),
             (
-                // Reset but not saved, dump the previous state.
                 STATE_RESET | STATE_DUMP,
-                "Dumped the previous state! See console.",
+                "The rank has been reset & dumped!",
             ),
             (
                 STATE_SAVED | STATE_DUMP,
-                "Dumped the saved state! See console.",
+                "The rank has been saved & dumped!",
             ),
             (
                 STATE_SAVED | STATE_RESET,
                 "The rank has been saved & reset!",
             ),
-            (STATE_DUMP, "The rank has been dumped! See console."),
+            (STATE_DUMP, "The rank has been dumped!"),
             (STATE_SAVED, "The rank has been saved!"),
             (STATE_RESET, "The rank has been reset!"),
             (STATE_NOTHING, ""),
@@ -39,9 +39,7 @@ pub async fn run() -> Result<(), JsValue> {
     runner
         .start("review-tool", web_options, {
             Box::new(|cc| {
-                Box::new(review_tool::ui::ReviewToolApp::<
-                    review_tool::types::rank::sitcon_gdsc::Group,
-                >::new(cc, manuscripts).expect("run UI"))
+                Box::new(review_tool::ui::ReviewTool::new(cc, manuscripts, None).expect("run UI"))
             })
         })
         .await?;